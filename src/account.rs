@@ -1,11 +1,19 @@
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::{
+    cell::Cell,
     collections::HashMap,
-    fs::{File, OpenOptions},
-    io::{BufReader, BufWriter},
-    path::Path
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
 };
+use sha1::{Digest, Sha1};
+use zeroize::Zeroizing;
+use crate::account_files::{self, StorageLock, MAX_BACKUPS};
+use crate::account_transfer::{ImportAction, ImportCandidate};
+use crate::auth::{offline_session, AuthProviderKind};
 use crate::jvm::SessionInfo;
+use crate::vault::{KdfParams, VaultFile, VaultKey};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StoredAccount {
@@ -18,6 +26,26 @@ pub struct StoredAccount {
     pub session_type: String,
     pub created_at: u64,
     pub last_used: Option<u64>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Папка в списке; `None` — без группы
+    #[serde(default)]
+    pub group: Option<String>,
+    #[serde(default)]
+    pub notes: String,
+    #[serde(default)]
+    pub favorite: bool,
+    /// host:port последнего подключения с этой учёткой
+    #[serde(default)]
+    pub last_server: Option<String>,
+    /// Шаблоны `host:port` (`*` в хосте, порт можно опустить), при подключении к ним учётка ставится сама
+    #[serde(default)]
+    pub servers: Vec<String>,
+    #[serde(default)]
+    pub provider: AuthProviderKind,
+    /// clientToken Yggdrasil, без него refresh выдаёт новый токен только при совпадении сессии
+    #[serde(default)]
+    pub client_token: Option<String>,
 }
 
 impl StoredAccount {
@@ -26,10 +54,7 @@ impl StoredAccount {
     }
 
     pub fn new_with_password(name: String, session: SessionInfo, password: String) -> Self {
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
+        let now = now_secs();
 
         Self {
             name,
@@ -40,6 +65,14 @@ impl StoredAccount {
             session_type: session.session_type,
             created_at: now,
             last_used: None,
+            tags: Vec::new(),
+            group: None,
+            notes: String::new(),
+            favorite: false,
+            last_server: None,
+            servers: Vec::new(),
+            provider: AuthProviderKind::default(),
+            client_token: None,
         }
     }
 
@@ -65,6 +98,18 @@ impl StoredAccount {
         self.format_time(Some(self.created_at))
     }
 
+    /// Подстрока без учёта регистра в имени, нике, UUID или тегах
+    pub fn matches_search(&self, query: &str) -> bool {
+        let query = query.trim().to_lowercase();
+        if query.is_empty() {
+            return true;
+        }
+        [&self.name, &self.username, &self.player_id]
+            .into_iter()
+            .chain(&self.tags)
+            .any(|field| field.to_lowercase().contains(&query))
+    }
+
     pub fn format_last_used(&self) -> String {
         self.format_time(self.last_used)
     }
@@ -96,25 +141,255 @@ impl StoredAccount {
     }
 }
 
+/// Больше записей журнала не храним, старые отбрасываются
+const MAX_HISTORY: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SwitchTrigger {
+    Manual,
+    AutoBind,
+    Reauth,
+    Revert,
+}
+
+impl SwitchTrigger {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Manual => "Manual",
+            Self::AutoBind => "Auto-bind",
+            Self::Reauth => "Re-auth",
+            Self::Revert => "Revert",
+        }
+    }
+}
+
+/// Запись журнала. `previous` — сессия до смены целиком, по ней работает откат.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionSwitch {
+    pub timestamp: u64,
+    pub trigger: SwitchTrigger,
+    pub from_account: Option<String>,
+    pub to_account: Option<String>,
+    pub to_username: String,
+    pub previous: SessionInfo,
+    /// `None` — смена прошла
+    pub error: Option<String>,
+}
+
+impl SessionSwitch {
+    pub fn succeeded(&self) -> bool {
+        self.error.is_none()
+    }
+
+    pub fn format_time(&self) -> String {
+        chrono::DateTime::from_timestamp(self.timestamp as i64, 0)
+            .map(|dt| dt.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AccountSort {
+    Name,
+    Username,
+    Created,
+    LastUsed,
+    Server,
+}
+
+impl AccountSort {
+    pub const ALL: [AccountSort; 5] = [Self::Name, Self::Username, Self::Created, Self::LastUsed, Self::Server];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Name => "Name",
+            Self::Username => "Username",
+            Self::Created => "Created",
+            Self::LastUsed => "Last used",
+            Self::Server => "Server",
+        }
+    }
+
+    /// Возрастающий порядок; пустые `last_used`/`last_server` всегда в конце, даже при `descending`
+    pub fn sort(self, accounts: &mut [StoredAccount], descending: bool) {
+        use std::cmp::Ordering;
+        fn flip(order: Ordering, descending: bool) -> Ordering {
+            if descending { order.reverse() } else { order }
+        }
+        fn optional<T: Ord>(a: &Option<T>, b: &Option<T>, descending: bool) -> Ordering {
+            match (a, b) {
+                (Some(a), Some(b)) => flip(a.cmp(b), descending),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            }
+        }
+
+        accounts.sort_by(|a, b| {
+            let order = match self {
+                Self::Name => flip(a.name.to_lowercase().cmp(&b.name.to_lowercase()), descending),
+                Self::Username => flip(a.username.to_lowercase().cmp(&b.username.to_lowercase()), descending),
+                Self::Created => flip(a.created_at.cmp(&b.created_at), descending),
+                Self::LastUsed => optional(&a.last_used, &b.last_used, descending),
+                Self::Server => optional(&a.last_server, &b.last_server, descending),
+            };
+            order.then_with(|| a.name.cmp(&b.name))
+        });
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AccountStorage {
     pub accounts: HashMap<String, StoredAccount>,
     pub version: u32,
+    /// Журнал смен сессии, старые записи в начале
+    #[serde(default)]
+    pub history: Vec<SessionSwitch>,
 }
 
 impl Default for AccountStorage {
     fn default() -> Self {
         Self {
             accounts: HashMap::new(),
-            version: 1,
+            version: STORAGE_VERSION,
+            history: Vec::new(),
+        }
+    }
+}
+
+/// Шаг `i` переводит хранилище из версии `i + 1` в `i + 2`. Новые шаги только дописываются в конец.
+const MIGRATIONS: &[fn(&mut Value)] = &[v1_fill_account_defaults, v2_add_organization, v3_add_last_server, v4_add_servers, v5_add_history, v6_add_provider];
+
+pub const STORAGE_VERSION: u32 = MIGRATIONS.len() as u32 + 1;
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+/// v2: у ранних записей нет `created_at` и `last_used`, ключ словаря — единственное имя
+fn v1_fill_account_defaults(value: &mut Value) {
+    let now = now_secs();
+    let Some(accounts) = value.get_mut("accounts").and_then(Value::as_object_mut) else { return };
+    for (key, account) in accounts.iter_mut() {
+        let Some(account) = account.as_object_mut() else { continue };
+        account.entry("name").or_insert_with(|| Value::from(key.clone()));
+        account.entry("password").or_insert_with(|| Value::from(""));
+        account.entry("created_at").or_insert_with(|| Value::from(now));
+        account.entry("last_used").or_insert(Value::Null);
+    }
+}
+
+/// v3: теги, группа, заметки и избранное
+fn v2_add_organization(value: &mut Value) {
+    let Some(accounts) = value.get_mut("accounts").and_then(Value::as_object_mut) else { return };
+    for account in accounts.values_mut() {
+        let Some(account) = account.as_object_mut() else { continue };
+        account.entry("tags").or_insert_with(|| Value::Array(Vec::new()));
+        account.entry("group").or_insert(Value::Null);
+        account.entry("notes").or_insert_with(|| Value::from(""));
+        account.entry("favorite").or_insert(Value::Bool(false));
+    }
+}
+
+/// v4: последний сервер для сортировки списка
+fn v3_add_last_server(value: &mut Value) {
+    let Some(accounts) = value.get_mut("accounts").and_then(Value::as_object_mut) else { return };
+    for account in accounts.values_mut() {
+        if let Some(account) = account.as_object_mut() {
+            account.entry("last_server").or_insert(Value::Null);
+        }
+    }
+}
+
+/// v5: привязки к серверам
+fn v4_add_servers(value: &mut Value) {
+    let Some(accounts) = value.get_mut("accounts").and_then(Value::as_object_mut) else { return };
+    for account in accounts.values_mut() {
+        if let Some(account) = account.as_object_mut() {
+            account.entry("servers").or_insert_with(|| Value::Array(Vec::new()));
         }
     }
 }
 
+/// v6: журнал смен сессии
+fn v5_add_history(value: &mut Value) {
+    if let Some(storage) = value.as_object_mut() {
+        storage.entry("history").or_insert_with(|| Value::Array(Vec::new()));
+    }
+}
+
+/// v7: провайдер авторизации. Старые учётки получены через лаунчер.
+fn v6_add_provider(value: &mut Value) {
+    let Some(accounts) = value.get_mut("accounts").and_then(Value::as_object_mut) else { return };
+    for account in accounts.values_mut() {
+        if let Some(account) = account.as_object_mut() {
+            account.entry("provider").or_insert_with(|| serde_json::json!({ "kind": "launcher" }));
+            account.entry("client_token").or_insert(Value::Null);
+        }
+    }
+}
+
+/// Доводит JSON хранилища до текущей версии. Файлы новее отказываемся читать,
+/// иначе незнакомые поля молча пропадут при следующем сохранении.
+/// Возвращает true, если что-то мигрировало и файл стоит переписать.
+fn migrate_storage(value: &mut Value) -> Result<bool, String> {
+    let version = value
+        .get("version")
+        .and_then(Value::as_u64)
+        .ok_or_else(|| "Missing storage version".to_string())? as u32;
+    if version == 0 || version > STORAGE_VERSION {
+        return Err(format!(
+            "Account storage version {} is not supported (expected 1..={}), update the tool",
+            version, STORAGE_VERSION
+        ));
+    }
+    for step in &MIGRATIONS[version as usize - 1..] {
+        step(value);
+    }
+    value["version"] = Value::from(STORAGE_VERSION);
+    if version < STORAGE_VERSION {
+        tracing::info!("Migrated account storage from version {} to {}", version, STORAGE_VERSION);
+    }
+    Ok(version < STORAGE_VERSION)
+}
+
+/// Поля диалога редактирования учётки
+#[derive(Debug, Clone)]
+pub struct AccountEdit {
+    pub name: String,
+    pub session: SessionInfo,
+    pub group: String,
+    pub tags: Vec<String>,
+    pub notes: String,
+    pub servers: Vec<String>,
+    pub provider: AuthProviderKind,
+}
+
 #[derive(Debug, Clone)]
 pub struct AccountManager {
     storage: AccountStorage,
     config_path: String,
+    /// Ключ разблокированного хранилища; `None` — файл открытый
+    vault_key: Option<VaultKey>,
+    /// Файл зашифрован, а пароль ещё не введён: аккаунтов в памяти нет, запись запрещена
+    locked: bool,
+    /// 0 — без автоблокировки
+    auto_lock_minutes: u32,
+    last_activity: Instant,
+    /// Заблокировано по простою, UI ещё не показал уведомление
+    auto_locked: bool,
+    /// Копия уже снята с этой загрузки: иначе каждое сохранение вытесняет по одной из MAX_BACKUPS
+    backed_up: Cell<bool>,
+    /// SHA-1 файла, каким он был при последнем чтении или записи отсюда; `None` — файла не было
+    disk_digest: Cell<Option<[u8; 20]>>,
+    /// Файл поменял другой экземпляр игры: запись отклонена, пока не перечитаем
+    stale: Cell<bool>,
+    /// Основной файл есть, но не прочитался. Пока не восстановлен, запись запрещена,
+    /// иначе пустое хранилище затрёт то, что ещё можно спасти.
+    load_error: Option<String>,
 }
 
 impl AccountManager {
@@ -123,6 +398,15 @@ impl AccountManager {
         let mut manager = Self {
             storage: AccountStorage::default(),
             config_path,
+            vault_key: None,
+            locked: false,
+            auto_lock_minutes: 0,
+            last_activity: Instant::now(),
+            auto_locked: false,
+            backed_up: Cell::new(false),
+            disk_digest: Cell::new(None),
+            stale: Cell::new(false),
+            load_error: None,
         };
 
         if let Err(e) = manager.load_accounts() {
@@ -133,53 +417,322 @@ impl AccountManager {
     }
 
     pub fn load_accounts(&mut self) -> Result<(), String> {
+        self.backed_up.set(false);
+        self.stale.set(false);
+        self.disk_digest.set(None);
         if !Path::new(&self.config_path).exists() {
+            self.load_error = None;
             return Ok(());
         }
 
-        let file = File::open(&self.config_path)
-            .map_err(|e| format!("Failed to open config file: {}", e))?;
+        let result = self.read_accounts();
+        self.load_error = result.as_ref().err().cloned();
+        if let Ok(true) = result {
+            // до записи старая версия уходит в резервные копии
+            if let Err(e) = self.save_accounts() {
+                tracing::warn!("Failed to write migrated accounts: {}", e);
+            }
+        }
+        result.map(|_| ())
+    }
 
-        let reader = BufReader::new(file);
-        self.storage = serde_json::from_reader(reader)
+    /// true — хранилище мигрировало и его надо записать
+    fn read_accounts(&mut self) -> Result<bool, String> {
+        let mut raw = self.read_config()?;
+        if VaultFile::is_vault(&raw) {
+            let vault: VaultFile = serde_json::from_value(raw)
+                .map_err(|e| format!("Failed to parse encrypted config: {}", e))?;
+            self.auto_lock_minutes = vault.auto_lock_minutes;
+            self.storage = AccountStorage::default();
+            self.vault_key = None;
+            self.locked = true;
+            tracing::info!("Account storage is encrypted, waiting for master password");
+            return Ok(false);
+        }
+
+        let migrated = migrate_storage(&mut raw)?;
+        self.storage = serde_json::from_value(raw)
             .map_err(|e| format!("Failed to parse config file: {}", e))?;
 
         tracing::info!("Loaded {} accounts from config", self.storage.accounts.len());
-        Ok(())
+        Ok(migrated)
+    }
+
+    fn read_config(&self) -> Result<Value, String> {
+        let _lock = StorageLock::acquire(&self.config_path)?;
+        let bytes = fs::read(&self.config_path)
+            .map_err(|e| format!("Failed to open config file: {}", e))?;
+        self.disk_digest.set(Some(Sha1::digest(&bytes).into()));
+
+        serde_json::from_slice(&bytes)
+            .map_err(|e| format!("Failed to parse config file: {}", e))
+    }
+
+    /// Файл поменял другой экземпляр игры после нашего чтения
+    pub fn is_stale(&self) -> bool {
+        self.stale.get()
     }
 
     pub fn save_accounts(&self) -> Result<(), String> {
-        let file = OpenOptions::new()
-            .create(true)
-            .write(true)
-            .truncate(true)
-            .open(&self.config_path)
-            .map_err(|e| format!("Failed to create config file: {}", e))?;
+        if self.locked {
+            return Err("Account storage is locked".to_string());
+        }
 
-        let writer = BufWriter::new(file);
-        serde_json::to_writer_pretty(writer, &self.storage)
-            .map_err(|e| format!("Failed to write config file: {}", e))?;
+        if let Some(e) = &self.load_error {
+            return Err(format!("Accounts file failed to load, restore a backup first: {}", e));
+        }
+
+        // всё сериализуем до того, как трогать файл
+        let bytes = match &self.vault_key {
+            Some(key) => {
+                let plain = Zeroizing::new(
+                    serde_json::to_vec(&self.storage)
+                        .map_err(|e| format!("Failed to serialize accounts: {}", e))?,
+                );
+                serde_json::to_vec_pretty(&key.seal(&plain, self.auto_lock_minutes)?)
+            }
+            None => serde_json::to_vec_pretty(&self.storage),
+        }
+        .map_err(|e| format!("Failed to serialize config: {}", e))?;
+
+        let _lock = StorageLock::acquire(&self.config_path)?;
+        // блокировка только упорядочивает записи: без сверки запись из старого состояния
+        // молча сотрёт учётки, которые успел сохранить второй экземпляр игры
+        let on_disk = fs::read(&self.config_path).ok().map(|bytes| Sha1::digest(&bytes).into());
+        if on_disk != self.disk_digest.get() {
+            self.stale.set(true);
+            return Err("sessions.json was changed by another game instance. Reload accounts and repeat the change".to_string());
+        }
+        if !self.backed_up.get() {
+            match account_files::backup(&self.config_path, MAX_BACKUPS) {
+                Ok(()) => self.backed_up.set(true),
+                Err(e) => tracing::warn!("Failed to back up accounts: {}", e),
+            }
+        }
+        account_files::write_atomic(&self.config_path, &bytes)?;
+        self.disk_digest.set(Some(Sha1::digest(&bytes).into()));
 
         tracing::info!("Saved {} accounts to config", self.storage.accounts.len());
         Ok(())
     }
 
+    pub fn load_error(&self) -> Option<&str> {
+        self.load_error.as_deref()
+    }
+
+    pub fn backups(&self) -> Vec<PathBuf> {
+        account_files::list_backups(&self.config_path)
+    }
+
+    /// Ставит копию на место основного файла (испорченный откладывается) и перечитывает её
+    pub fn restore_backup(&mut self, backup: &Path) -> Result<(), String> {
+        {
+            let _lock = StorageLock::acquire(&self.config_path)?;
+            account_files::restore_backup(&self.config_path, backup)?;
+        }
+        self.vault_key = None;
+        self.locked = false;
+        self.storage = AccountStorage::default();
+        self.load_accounts()
+    }
+
+    /// Откладывает нечитаемый файл и начинает с пустого хранилища
+    pub fn start_fresh(&mut self) -> Result<(), String> {
+        {
+            let _lock = StorageLock::acquire(&self.config_path)?;
+            account_files::set_aside(&self.config_path)?;
+        }
+        self.vault_key = None;
+        self.locked = false;
+        self.storage = AccountStorage::default();
+        self.disk_digest.set(None);
+        self.stale.set(false);
+        self.load_error = None;
+        Ok(())
+    }
+
+    /// После шифрования старые открытые копии выдали бы токены, поэтому удаляются
+    fn remove_plain_backups(&self) {
+        for path in self.backups() {
+            let plain = fs::read(&path)
+                .ok()
+                .and_then(|bytes| serde_json::from_slice::<Value>(&bytes).ok())
+                .is_none_or(|v| !VaultFile::is_vault(&v));
+            if plain {
+                let _ = fs::remove_file(&path);
+            }
+        }
+    }
+
+    pub fn is_encrypted(&self) -> bool {
+        self.locked || self.vault_key.is_some()
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.locked
+    }
+
+    /// Параметры KDF заблокированного файла: по ним ключ выводится вне мьютекса
+    pub fn locked_kdf(&self) -> Result<KdfParams, String> {
+        if !self.locked {
+            return Err("Account storage is not locked".to_string());
+        }
+        let vault: VaultFile = serde_json::from_value(self.read_config()?)
+            .map_err(|e| format!("Failed to parse encrypted config: {}", e))?;
+        Ok(vault.kdf)
+    }
+
+    /// Ключ уже выведен (`VaultKey::derive` в фоне), здесь только расшифровка
+    pub fn unlock(&mut self, key: VaultKey) -> Result<(), String> {
+        if !self.locked {
+            return Ok(());
+        }
+
+        let raw = self.read_config()?;
+        let vault: VaultFile = serde_json::from_value(raw)
+            .map_err(|e| format!("Failed to parse encrypted config: {}", e))?;
+        if *key.kdf() != vault.kdf {
+            return Err("Storage file changed while unlocking, try again".to_string());
+        }
+        let plain = key.open(&vault)?;
+        let mut raw: Value = serde_json::from_slice(&plain)
+            .map_err(|e| format!("Failed to parse decrypted accounts: {}", e))?;
+        let migrated = migrate_storage(&mut raw)?;
+        self.storage = serde_json::from_value(raw)
+            .map_err(|e| format!("Failed to parse decrypted accounts: {}", e))?;
+
+        self.auto_lock_minutes = vault.auto_lock_minutes;
+        self.vault_key = Some(key);
+        self.locked = false;
+        self.touch();
+        tracing::info!("Unlocked {} accounts", self.storage.accounts.len());
+        if migrated {
+            if let Err(e) = self.save_accounts() {
+                tracing::warn!("Failed to write migrated accounts: {}", e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Забывает ключ и расшифрованные аккаунты; файл на диске не трогается
+    pub fn lock(&mut self) {
+        if self.vault_key.is_none() {
+            return;
+        }
+        self.vault_key = None;
+        self.storage = AccountStorage::default();
+        self.locked = true;
+        tracing::info!("Account storage locked");
+    }
+
+    /// Шифрует хранилище ключом нового пароля (`VaultKey::create` в фоне).
+    /// Для открытого файла это и есть разовая миграция.
+    pub fn set_master_key(&mut self, key: VaultKey) -> Result<(), String> {
+        if self.locked {
+            return Err("Account storage is locked".to_string());
+        }
+
+        let previous = self.vault_key.replace(key);
+        if let Err(e) = self.save_accounts() {
+            self.vault_key = previous;
+            return Err(e);
+        }
+        self.remove_plain_backups();
+        // открытые копии удалены, следующее сохранение снимет уже зашифрованную
+        self.backed_up.set(false);
+        Ok(())
+    }
+
+    /// Возвращает открытый формат
+    pub fn remove_master_password(&mut self) -> Result<(), String> {
+        if self.locked {
+            return Err("Account storage is locked".to_string());
+        }
+
+        let previous = self.vault_key.take();
+        if let Err(e) = self.save_accounts() {
+            self.vault_key = previous;
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    pub fn auto_lock_minutes(&self) -> u32 {
+        self.auto_lock_minutes
+    }
+
+    pub fn set_auto_lock_minutes(&mut self, minutes: u32) -> Result<(), String> {
+        self.auto_lock_minutes = minutes;
+        self.touch();
+        if self.vault_key.is_some() {
+            self.save_accounts()?;
+        }
+        Ok(())
+    }
+
+    /// Отметка активности пользователя для автоблокировки
+    pub fn touch(&mut self) {
+        self.last_activity = Instant::now();
+    }
+
+    pub fn auto_lock_due(&self) -> bool {
+        self.vault_key.is_some()
+            && self.auto_lock_minutes > 0
+            && self.last_activity.elapsed() >= Duration::from_secs(self.auto_lock_minutes as u64 * 60)
+    }
+
+    /// Блокирует по простою; вызывается из swap buffers, даже когда меню скрыто
+    pub fn enforce_auto_lock(&mut self) {
+        if self.auto_lock_due() {
+            self.lock();
+            self.auto_locked = true;
+        }
+    }
+
+    /// true один раз после автоблокировки, чтобы UI показал уведомление
+    pub fn take_auto_locked(&mut self) -> bool {
+        std::mem::take(&mut self.auto_locked)
+    }
+
     pub fn add_account(&mut self, name: String, session: SessionInfo) -> Result<(), String> {
         self.add_account_with_password(name, session, String::new())
     }
 
     pub fn add_account_with_password(&mut self, name: String, session: SessionInfo, password: String) -> Result<(), String> {
-        if self.storage.accounts.contains_key(&name) {
+        self.insert_account(StoredAccount::new_with_password(name, session, password))
+    }
+
+    /// Готовая учётка (провайдер, clientToken уже заполнены) — одна запись на диск
+    pub fn insert_account(&mut self, account: StoredAccount) -> Result<(), String> {
+        if self.locked {
+            return Err("Account storage is locked".to_string());
+        }
+
+        if self.storage.accounts.contains_key(&account.name) {
             return Err("Account with this name already exists".to_string());
         }
 
-        let account = StoredAccount::new_with_password(name.clone(), session, password);
-        self.storage.accounts.insert(name, account);
+        self.storage.accounts.insert(account.name.clone(), account);
         self.save_accounts()?;
 
         Ok(())
     }
 
+    /// Учётка для offline/LAN-серверов: UUID считается из ника, пароль не нужен
+    pub fn add_offline_account(&mut self, name: String, username: &str) -> Result<(), String> {
+        if self.locked {
+            return Err("Account storage is locked".to_string());
+        }
+        if self.storage.accounts.contains_key(&name) {
+            return Err("Account with this name already exists".to_string());
+        }
+
+        let mut account = StoredAccount::new(name.clone(), offline_session(username)?);
+        account.provider = AuthProviderKind::Offline;
+        self.storage.accounts.insert(name, account);
+        self.save_accounts()
+    }
+
     pub fn remove_account(&mut self, name: &str) -> Result<(), String> {
         if !self.storage.accounts.contains_key(name) {
             return Err("Account not found".to_string());
@@ -196,25 +749,114 @@ impl AccountManager {
     }
 
 
-    pub fn update_account(&mut self, name: &str, session: SessionInfo) -> Result<(), String> {
-        self.update_account_with_password(name, session, None)
+    /// Всё из диалога редактирования одной записью на диск.
+    /// Теги чистятся от пробелов и повторов, пустая группа значит «без группы».
+    pub fn edit_account(&mut self, original_name: &str, edit: AccountEdit) -> Result<(), String> {
+        if self.locked {
+            return Err("Account storage is locked".to_string());
+        }
+        for pattern in edit.servers.iter().map(|p| p.trim()).filter(|p| !p.is_empty()) {
+            crate::server_binding::validate_pattern(pattern)?;
+        }
+        if edit.name != original_name && self.storage.accounts.contains_key(&edit.name) {
+            return Err("Account with this name already exists".to_string());
+        }
+        let mut account = self.storage.accounts.remove(original_name).ok_or_else(|| "Account not found".to_string())?;
+
+        account.name = edit.name.clone();
+        account.username = edit.session.username;
+        account.player_id = edit.session.player_id;
+        account.access_token = edit.session.access_token;
+        account.session_type = edit.session.session_type;
+        let group = edit.group.trim();
+        account.group = (!group.is_empty()).then(|| group.to_string());
+        account.tags.clear();
+        for tag in edit.tags.iter().map(|t| t.trim()).filter(|t| !t.is_empty()) {
+            if !account.tags.iter().any(|t| t == tag) {
+                account.tags.push(tag.to_string());
+            }
+        }
+        account.notes = edit.notes;
+        account.servers = edit
+            .servers
+            .iter()
+            .map(|p| p.trim().to_lowercase())
+            .filter(|p| !p.is_empty())
+            .collect();
+        account.servers.dedup();
+        account.provider = edit.provider;
+        account.update_last_used();
+
+        self.storage.accounts.insert(edit.name, account);
+        self.save_accounts()
+    }
+
+    /// Учётка с самым точным подходящим шаблоном: без `*` важнее, затем длиннее
+    pub fn account_for_server(&self, server: &str) -> Option<&StoredAccount> {
+        self.storage
+            .accounts
+            .values()
+            .filter_map(|account| {
+                account
+                    .servers
+                    .iter()
+                    .filter(|p| crate::server_binding::pattern_matches(p, server))
+                    .map(|p| (!p.contains('*'), p.len()))
+                    .max()
+                    .map(|rank| (rank, account))
+            })
+            .max_by(|(a, x), (b, y)| a.cmp(b).then_with(|| y.name.cmp(&x.name)))
+            .map(|(_, account)| account)
     }
 
-    pub fn update_account_with_password(&mut self, name: &str, session: SessionInfo, password: Option<String>) -> Result<(), String> {
-        if let Some(account) = self.storage.accounts.get_mut(name) {
-            account.username = session.username;
-            account.player_id = session.player_id;
-            account.access_token = session.access_token;
-            account.session_type = session.session_type;
-            if let Some(pwd) = password {
-                account.password = pwd;
+    /// Запоминает сервер у учёток с этим ником. Заблокированное хранилище пропускаем молча.
+    pub fn record_server(&mut self, username: &str, server: &str) -> Result<(), String> {
+        if self.locked || self.load_error.is_some() {
+            return Ok(());
+        }
+        let mut changed = false;
+        for account in self.storage.accounts.values_mut().filter(|a| a.username == username) {
+            if account.last_server.as_deref() != Some(server) {
+                account.last_server = Some(server.to_string());
+                changed = true;
             }
-            account.update_last_used();
-            self.save_accounts()?;
-            Ok(())
-        } else {
-            Err("Account not found".to_string())
         }
+        if changed { self.save_accounts() } else { Ok(()) }
+    }
+
+    pub fn history(&self) -> &[SessionSwitch] {
+        &self.storage.history
+    }
+
+    /// Имя сохранённой учётки с этой сессией, если такая есть
+    pub fn account_name_for(&self, session: &SessionInfo) -> Option<String> {
+        self.storage
+            .accounts
+            .values()
+            .find(|a| a.username == session.username && a.player_id == session.player_id)
+            .map(|a| a.name.clone())
+    }
+
+    /// Пока хранилище заблокировано или не загрузилось, запись остаётся только в памяти
+    pub fn record_switch(&mut self, entry: SessionSwitch) -> Result<(), String> {
+        self.storage.history.push(entry);
+        let excess = self.storage.history.len().saturating_sub(MAX_HISTORY);
+        self.storage.history.drain(..excess);
+        if self.locked || self.load_error.is_some() {
+            return Ok(());
+        }
+        self.save_accounts()
+    }
+
+    pub fn clear_history(&mut self) -> Result<(), String> {
+        self.storage.history.clear();
+        self.save_accounts()
+    }
+
+    pub fn set_favorite(&mut self, name: &str, favorite: bool) -> Result<(), String> {
+        let account = self.storage.accounts.get_mut(name).ok_or_else(|| "Account not found".to_string())?;
+        account.favorite = favorite;
+        self.save_accounts()
     }
 
     pub fn update_auth_data(&mut self, name: &str, access_token: String, profile: String) -> Result<(), String> {
@@ -229,6 +871,13 @@ impl AccountManager {
         }
     }
 
+    /// Только в памяти; на диск попадёт со следующим сохранением
+    pub fn mark_used(&mut self, name: &str) {
+        if let Some(account) = self.storage.accounts.get_mut(name) {
+            account.update_last_used();
+        }
+    }
+
     pub fn use_account(&mut self, name: &str) -> Result<SessionInfo, String> {
         if let Some(account) = self.storage.accounts.get_mut(name) {
             account.update_last_used();
@@ -253,25 +902,6 @@ impl AccountManager {
         accounts
     }
 
-    pub fn rename_account(&mut self, old_name: &str, new_name: String) -> Result<(), String> {
-        if old_name == new_name {
-            return Ok(());
-        }
-
-        if self.storage.accounts.contains_key(&new_name) {
-            return Err("Account with this name already exists".to_string());
-        }
-
-        if let Some(mut account) = self.storage.accounts.remove(old_name) {
-            account.name = new_name.clone();
-            self.storage.accounts.insert(new_name, account);
-            self.save_accounts()?;
-            Ok(())
-        } else {
-            Err("Account not found".to_string())
-        }
-    }
-
     pub fn export_to_clipboard(&self, name: &str) -> Result<String, String> {
         if let Some(account) = self.storage.accounts.get(name) {
             let json = serde_json::to_string_pretty(account)
@@ -282,6 +912,79 @@ impl AccountManager {
         }
     }
 
+    /// Свободное имя: `name`, `name (2)`, `name (3)`...
+    fn free_name(&self, name: &str) -> String {
+        if !self.storage.accounts.contains_key(name) {
+            return name.to_string();
+        }
+        (2..)
+            .map(|i| format!("{} ({})", name, i))
+            .find(|n| !self.storage.accounts.contains_key(n))
+            .unwrap_or_else(|| name.to_string())
+    }
+
+    /// Применяет план импорта одним сохранением. Возвращает (добавлено, обновлено, пропущено).
+    pub fn apply_import(&mut self, plan: &[ImportCandidate]) -> Result<(usize, usize, usize), String> {
+        if self.locked {
+            return Err("Account storage is locked".to_string());
+        }
+
+        let (mut added, mut merged, mut skipped) = (0, 0, 0);
+        for candidate in plan {
+            let incoming = &candidate.account;
+            match candidate.action {
+                ImportAction::Skip => skipped += 1,
+                ImportAction::Merge => {
+                    let target = candidate
+                        .conflict
+                        .as_ref()
+                        .and_then(|(name, _)| self.storage.accounts.get_mut(name));
+                    if let Some(account) = target {
+                        account.username = incoming.username.clone();
+                        account.player_id = incoming.player_id.clone();
+                        account.access_token = incoming.access_token.clone();
+                        account.session_type = incoming.session_type.clone();
+                        if !incoming.password.is_empty() {
+                            account.password = incoming.password.clone();
+                        }
+                        for tag in &incoming.tags {
+                            if !account.tags.contains(tag) {
+                                account.tags.push(tag.clone());
+                            }
+                        }
+                        if account.group.is_none() {
+                            account.group = incoming.group.clone();
+                        }
+                        if account.notes.is_empty() {
+                            account.notes = incoming.notes.clone();
+                        }
+                        account.favorite |= incoming.favorite;
+                        for server in &incoming.servers {
+                            if !account.servers.contains(server) {
+                                account.servers.push(server.clone());
+                            }
+                        }
+                        merged += 1;
+                    } else {
+                        skipped += 1;
+                    }
+                }
+                ImportAction::Add | ImportAction::Rename => {
+                    // Add тоже может упереться в имя, занятое строкой выше в том же плане
+                    let mut account = incoming.clone();
+                    account.name = self.free_name(&incoming.name);
+                    self.storage.accounts.insert(account.name.clone(), account);
+                    added += 1;
+                }
+            }
+        }
+
+        if added + merged > 0 {
+            self.save_accounts()?;
+        }
+        Ok((added, merged, skipped))
+    }
+
     pub fn get_account_count(&self) -> usize {
         self.storage.accounts.len()
     }
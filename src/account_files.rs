@@ -0,0 +1,130 @@
+use fs2::FileExt;
+use std::{
+    fs::{self, File, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+    thread,
+    time::Duration,
+};
+
+/// Сколько резервных копий держим, самые старые удаляются
+pub const MAX_BACKUPS: usize = 10;
+
+/// Ожидание чужой блокировки: 40 попыток по 50 мс
+const LOCK_ATTEMPTS: u32 = 40;
+const LOCK_RETRY: Duration = Duration::from_millis(50);
+
+/// Межпроцессная advisory-блокировка на `<file>.lock`; снимается при drop.
+pub struct StorageLock {
+    file: File,
+}
+
+impl StorageLock {
+    pub fn acquire(path: &str) -> Result<Self, String> {
+        let lock_path = format!("{}.lock", path);
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&lock_path)
+            .map_err(|e| format!("Failed to open lock file: {}", e))?;
+
+        for _ in 0..LOCK_ATTEMPTS {
+            if file.try_lock_exclusive().is_ok() {
+                return Ok(Self { file });
+            }
+            thread::sleep(LOCK_RETRY);
+        }
+        Err(format!("{} is locked by another game instance", path))
+    }
+}
+
+impl Drop for StorageLock {
+    fn drop(&mut self) {
+        let _ = FileExt::unlock(&self.file);
+    }
+}
+
+/// Пишет во временный файл рядом и переименовывает поверх: после сбоя остаётся
+/// либо старая, либо новая версия целиком.
+pub fn write_atomic(path: &str, bytes: &[u8]) -> Result<(), String> {
+    let tmp = format!("{}.tmp", path);
+    let mut file = File::create(&tmp).map_err(|e| format!("Failed to create temp file: {}", e))?;
+    file.write_all(bytes)
+        .and_then(|_| file.sync_all())
+        .map_err(|e| format!("Failed to write temp file: {}", e))?;
+    drop(file);
+
+    fs::rename(&tmp, path).map_err(|e| {
+        let _ = fs::remove_file(&tmp);
+        format!("Failed to replace {}: {}", path, e)
+    })
+}
+
+fn backup_dir(path: &str) -> PathBuf {
+    let stem = Path::new(path).file_stem().and_then(|s| s.to_str()).unwrap_or("sessions");
+    Path::new(path).with_file_name(format!("{}_backups", stem))
+}
+
+fn backup_prefix(path: &str) -> String {
+    let stem = Path::new(path).file_stem().and_then(|s| s.to_str()).unwrap_or("sessions");
+    format!("{}-", stem)
+}
+
+/// Копия текущего файла с отметкой времени; лишние старые копии удаляются.
+pub fn backup(path: &str, keep: usize) -> Result<(), String> {
+    if !Path::new(path).exists() {
+        return Ok(());
+    }
+
+    let dir = backup_dir(path);
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create backup directory: {}", e))?;
+    // миллисекунды в имени: две копии в одну секунду не затирают друг друга
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S-%3f");
+    let target = dir.join(format!("{}{}.json", backup_prefix(path), stamp));
+    fs::copy(path, &target).map_err(|e| format!("Failed to back up {}: {}", path, e))?;
+
+    for old in list_backups(path).into_iter().skip(keep) {
+        let _ = fs::remove_file(old);
+    }
+    Ok(())
+}
+
+/// Резервные копии, новые первыми. Имя содержит время, поэтому сортировки по имени достаточно.
+pub fn list_backups(path: &str) -> Vec<PathBuf> {
+    let prefix = backup_prefix(path);
+    let mut backups: Vec<PathBuf> = fs::read_dir(backup_dir(path))
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| {
+                    p.file_name()
+                        .and_then(|n| n.to_str())
+                        .is_some_and(|n| n.starts_with(&prefix) && n.ends_with(".json"))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    backups.sort();
+    backups.reverse();
+    backups
+}
+
+/// Откладывает нечитаемый файл в `<file>.broken-<время>`, чтобы его не затёрло восстановление
+pub fn set_aside(path: &str) -> Result<Option<PathBuf>, String> {
+    if !Path::new(path).exists() {
+        return Ok(None);
+    }
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    let target = PathBuf::from(format!("{}.broken-{}", path, stamp));
+    fs::rename(path, &target).map_err(|e| format!("Failed to move {} aside: {}", path, e))?;
+    Ok(Some(target))
+}
+
+/// Возвращает копию на место основного файла; текущий откладывается.
+pub fn restore_backup(path: &str, backup: &Path) -> Result<(), String> {
+    let bytes = fs::read(backup).map_err(|e| format!("Failed to read backup: {}", e))?;
+    set_aside(path)?;
+    write_atomic(path, &bytes)
+}
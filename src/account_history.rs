@@ -0,0 +1,81 @@
+use crate::account::{AccountManager, SessionSwitch, SwitchTrigger};
+use crate::async_runtime::ASYNC_RUNTIME;
+use crate::core::state::GlobalState;
+use crate::jvm::{get_jvm, SessionInfo};
+
+/// Единственный путь смены сессии игры: запоминает прежнюю сессию и пишет результат в журнал.
+pub fn switch_session(session: SessionInfo, trigger: SwitchTrigger) -> Result<(), String> {
+    let previous = get_jvm().get_current_session();
+    let lookup = session.clone();
+    let result = get_jvm().change_session(session);
+
+    if let Some(manager) = GlobalState::instance().get_account_manager().get() {
+        let mut manager = manager.lock();
+        let entry = history_entry(&manager, trigger, previous, &lookup, result.as_ref().err().cloned());
+        if let Err(e) = manager.record_switch(entry) {
+            tracing::warn!("Failed to save session history: {}", e);
+        }
+    }
+    result
+}
+
+/// Для потока netty: сессия меняется сразу, а журнал и `last_used` учётки `account`
+/// пишутся на диск в фоне — сохранение может ждать чужую блокировку файла.
+pub fn switch_session_deferred(session: SessionInfo, trigger: SwitchTrigger, account: Option<String>) -> Result<(), String> {
+    let previous = get_jvm().get_current_session();
+    let lookup = session.clone();
+    let result = get_jvm().change_session(session);
+    let error = result.as_ref().err().cloned();
+
+    ASYNC_RUNTIME.spawn_blocking(move || {
+        let Some(manager) = GlobalState::instance().get_account_manager().get() else { return };
+        let mut manager = manager.lock();
+        if let (None, Some(name)) = (&error, &account) {
+            manager.mark_used(name);
+        }
+        let entry = history_entry(&manager, trigger, previous, &lookup, error);
+        if let Err(e) = manager.record_switch(entry) {
+            tracing::warn!("Failed to save session history: {}", e);
+        }
+    });
+    result
+}
+
+fn history_entry(
+    manager: &AccountManager,
+    trigger: SwitchTrigger,
+    previous: SessionInfo,
+    session: &SessionInfo,
+    error: Option<String>,
+) -> SessionSwitch {
+    SessionSwitch {
+        timestamp: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+        trigger,
+        from_account: manager.account_name_for(&previous),
+        to_account: manager.account_name_for(session),
+        to_username: session.username.clone(),
+        previous,
+        error,
+    }
+}
+
+/// Сессия, которая была активна до последней удачной смены
+pub fn previous_session() -> Option<SessionInfo> {
+    let manager = GlobalState::instance().get_account_manager().get()?.lock();
+    manager
+        .history()
+        .iter()
+        .rev()
+        .find(|entry| entry.succeeded())
+        .map(|entry| entry.previous.clone())
+}
+
+/// Возвращает сессию до последней смены. Повторный вызов возвращает обратно.
+pub fn revert_to_previous() -> Result<SessionInfo, String> {
+    let previous = previous_session().ok_or_else(|| "No previous session in history".to_string())?;
+    switch_session(previous.clone(), SwitchTrigger::Revert)?;
+    Ok(previous)
+}
@@ -0,0 +1,425 @@
+use crate::account::{AccountStorage, StoredAccount, STORAGE_VERSION};
+use crate::vault::{VaultFile, VaultKey};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Относительные пути считаются отсюда: в папке игры лежат настоящие
+/// launcher_profiles.json и accounts.json, затирать их экспортом нельзя
+pub const TRANSFER_DIR: &str = "account_exports";
+
+pub fn resolve_transfer_path(path: &str) -> PathBuf {
+    let path = Path::new(path);
+    if path.is_absolute() { path.to_path_buf() } else { Path::new(TRANSFER_DIR).join(path) }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransferFormat {
+    /// `AccountStorage` целиком, как в sessions.json
+    Bundle,
+    /// То же, зашифрованное паролем тем же способом, что и sessions.json
+    EncryptedBundle,
+    Csv,
+    /// `launcher_profiles.json` ванильного лаунчера
+    LauncherProfiles,
+    /// `accounts.json` MultiMC / Prism Launcher
+    MultiMc,
+}
+
+impl TransferFormat {
+    pub const ALL: [TransferFormat; 5] = [
+        TransferFormat::Bundle,
+        TransferFormat::EncryptedBundle,
+        TransferFormat::Csv,
+        TransferFormat::LauncherProfiles,
+        TransferFormat::MultiMc,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            TransferFormat::Bundle => "JSON bundle",
+            TransferFormat::EncryptedBundle => "Encrypted JSON bundle",
+            TransferFormat::Csv => "CSV",
+            TransferFormat::LauncherProfiles => "launcher_profiles.json",
+            TransferFormat::MultiMc => "MultiMC/Prism accounts.json",
+        }
+    }
+
+    pub fn default_file(&self) -> &'static str {
+        match self {
+            TransferFormat::Bundle => "accounts_bundle.json",
+            TransferFormat::EncryptedBundle => "accounts_bundle.enc.json",
+            TransferFormat::Csv => "accounts.csv",
+            TransferFormat::LauncherProfiles => "launcher_profiles.json",
+            TransferFormat::MultiMc => "accounts.json",
+        }
+    }
+
+    pub fn needs_password(&self) -> bool {
+        matches!(self, TransferFormat::EncryptedBundle)
+    }
+
+    /// Пишет ли формат сохранённые пароли учёток
+    pub fn stores_passwords(&self) -> bool {
+        matches!(self, TransferFormat::Bundle | TransferFormat::EncryptedBundle | TransferFormat::Csv)
+    }
+
+    /// Угадывает формат по содержимому файла
+    pub fn detect(bytes: &[u8]) -> Option<TransferFormat> {
+        let Ok(value) = serde_json::from_slice::<Value>(bytes) else {
+            let text = std::str::from_utf8(bytes).ok()?;
+            return text.trim_start().starts_with("name,").then_some(TransferFormat::Csv);
+        };
+        if VaultFile::is_vault(&value) {
+            Some(TransferFormat::EncryptedBundle)
+        } else if value.get("authenticationDatabase").is_some() {
+            Some(TransferFormat::LauncherProfiles)
+        } else if value.get("formatVersion").is_some() && value.get("accounts").is_some_and(Value::is_array) {
+            Some(TransferFormat::MultiMc)
+        } else if value.get("accounts").is_some_and(Value::is_object) {
+            Some(TransferFormat::Bundle)
+        } else {
+            None
+        }
+    }
+}
+
+/// По какому полю входящий аккаунт совпал с сохранённым
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictKind {
+    Name,
+    Username,
+    PlayerId,
+}
+
+impl ConflictKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ConflictKind::Name => "same name",
+            ConflictKind::Username => "same username",
+            ConflictKind::PlayerId => "same player ID",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportAction {
+    /// Новый аккаунт без конфликтов
+    Add,
+    /// Обновить данные сессии у совпавшего аккаунта, имя остаётся его
+    Merge,
+    /// Добавить рядом под свободным именем
+    Rename,
+    Skip,
+}
+
+impl ImportAction {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ImportAction::Add => "Add",
+            ImportAction::Merge => "Merge",
+            ImportAction::Rename => "Rename",
+            ImportAction::Skip => "Skip",
+        }
+    }
+}
+
+/// Строка плана импорта: что пришло, с чем конфликтует и что с ним сделать
+#[derive(Debug, Clone)]
+pub struct ImportCandidate {
+    pub account: StoredAccount,
+    /// Имя сохранённого аккаунта и причина совпадения
+    pub conflict: Option<(String, ConflictKind)>,
+    pub action: ImportAction,
+}
+
+pub fn export_accounts(
+    accounts: &[StoredAccount],
+    format: TransferFormat,
+    password: &str,
+) -> Result<Vec<u8>, String> {
+    match format {
+        TransferFormat::Bundle => to_json(&bundle(accounts)),
+        TransferFormat::EncryptedBundle => {
+            let plain = serde_json::to_vec(&bundle(accounts))
+                .map_err(|e| format!("Failed to serialize accounts: {}", e))?;
+            let sealed = VaultKey::create(password)?.seal(&plain, 0)?;
+            to_json(&sealed)
+        }
+        TransferFormat::Csv => export_csv(accounts),
+        TransferFormat::LauncherProfiles => to_json(&export_launcher_profiles(accounts)),
+        TransferFormat::MultiMc => to_json(&export_multimc(accounts)),
+    }
+}
+
+pub fn import_accounts(bytes: &[u8], format: TransferFormat, password: &str) -> Result<Vec<StoredAccount>, String> {
+    match format {
+        TransferFormat::Bundle => parse_bundle(bytes),
+        TransferFormat::EncryptedBundle => {
+            let vault: VaultFile =
+                serde_json::from_slice(bytes).map_err(|e| format!("Failed to parse encrypted bundle: {}", e))?;
+            let key = VaultKey::derive(password, vault.kdf.clone())?;
+            parse_bundle(&key.open(&vault)?)
+        }
+        TransferFormat::Csv => import_csv(bytes),
+        TransferFormat::LauncherProfiles => import_launcher_profiles(&parse_json(bytes)?),
+        TransferFormat::MultiMc => import_multimc(&parse_json(bytes)?),
+    }
+}
+
+fn to_json<T: serde::Serialize>(value: &T) -> Result<Vec<u8>, String> {
+    serde_json::to_vec_pretty(value).map_err(|e| format!("Failed to serialize accounts: {}", e))
+}
+
+fn parse_json(bytes: &[u8]) -> Result<Value, String> {
+    serde_json::from_slice(bytes).map_err(|e| format!("Failed to parse file: {}", e))
+}
+
+fn bundle(accounts: &[StoredAccount]) -> AccountStorage {
+    AccountStorage {
+        accounts: accounts.iter().map(|a| (a.name.clone(), a.clone())).collect(),
+        version: STORAGE_VERSION,
+        // журнал хранит старые токены, наружу не отдаём
+        history: Vec::new(),
+    }
+}
+
+fn parse_bundle(bytes: &[u8]) -> Result<Vec<StoredAccount>, String> {
+    let storage: AccountStorage =
+        serde_json::from_slice(bytes).map_err(|e| format!("Failed to parse bundle: {}", e))?;
+    if storage.version > STORAGE_VERSION {
+        return Err(format!(
+            "Bundle version {} is newer than supported ({})",
+            storage.version, STORAGE_VERSION
+        ));
+    }
+    let mut accounts: Vec<StoredAccount> = storage.accounts.into_values().collect();
+    accounts.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(accounts)
+}
+
+const CSV_HEADER: [&str; 13] = [
+    "name",
+    "username",
+    "player_id",
+    "access_token",
+    "session_type",
+    "password",
+    "created_at",
+    "last_used",
+    "group",
+    "tags",
+    "notes",
+    "favorite",
+    "servers",
+];
+
+fn export_csv(accounts: &[StoredAccount]) -> Result<Vec<u8>, String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(CSV_HEADER).map_err(|e| format!("Failed to write CSV: {}", e))?;
+    for a in accounts {
+        writer
+            .write_record([
+                a.name.clone(),
+                a.username.clone(),
+                a.player_id.clone(),
+                a.access_token.clone(),
+                a.session_type.clone(),
+                a.password.clone(),
+                a.created_at.to_string(),
+                a.last_used.map(|t| t.to_string()).unwrap_or_default(),
+                a.group.clone().unwrap_or_default(),
+                a.tags.join(";"),
+                a.notes.clone(),
+                a.favorite.to_string(),
+                a.servers.join(";"),
+            ])
+            .map_err(|e| format!("Failed to write CSV: {}", e))?;
+    }
+    writer.into_inner().map_err(|e| format!("Failed to write CSV: {}", e))
+}
+
+/// Колонки ищутся по заголовку, так что порядок и лишние колонки не важны
+fn import_csv(bytes: &[u8]) -> Result<Vec<StoredAccount>, String> {
+    let mut reader = csv::Reader::from_reader(bytes);
+    let headers = reader.headers().map_err(|e| format!("Failed to read CSV header: {}", e))?.clone();
+    let column = |name: &str| headers.iter().position(|h| h.trim().eq_ignore_ascii_case(name));
+    let (Some(username), Some(player_id), Some(token)) = (column("username"), column("player_id"), column("access_token"))
+    else {
+        return Err("CSV needs username, player_id and access_token columns".to_string());
+    };
+    let name = column("name");
+    let session_type = column("session_type");
+    let password = column("password");
+    let created_at = column("created_at");
+    let last_used = column("last_used");
+    let group = column("group");
+    let tags = column("tags");
+    let notes = column("notes");
+    let favorite = column("favorite");
+    let servers = column("servers");
+
+    let mut accounts = Vec::new();
+    for (line, record) in reader.records().enumerate() {
+        let record = record.map_err(|e| format!("CSV row {}: {}", line + 2, e))?;
+        let get = |idx: Option<usize>| idx.and_then(|i| record.get(i)).unwrap_or("").trim().to_string();
+        let mut account = new_account(
+            get(name),
+            get(Some(username)),
+            get(Some(player_id)),
+            get(Some(token)),
+            get(session_type),
+        );
+        account.password = get(password);
+        if let Ok(t) = get(created_at).parse() {
+            account.created_at = t;
+        }
+        account.last_used = get(last_used).parse().ok();
+        account.group = Some(get(group)).filter(|g| !g.is_empty());
+        account.tags = split_list(&get(tags));
+        account.notes = get(notes);
+        account.favorite = get(favorite).eq_ignore_ascii_case("true");
+        account.servers = split_list(&get(servers));
+        accounts.push(account);
+    }
+    Ok(accounts)
+}
+
+/// Списки в одной ячейке CSV разделены `;`
+fn split_list(cell: &str) -> Vec<String> {
+    cell.split(';').map(str::trim).filter(|t| !t.is_empty()).map(str::to_string).collect()
+}
+
+fn new_account(name: String, username: String, player_id: String, access_token: String, session_type: String) -> StoredAccount {
+    let session = crate::jvm::SessionInfo {
+        username: username.clone(),
+        player_id,
+        access_token,
+        session_type: if session_type.is_empty() { "mojang".to_string() } else { session_type },
+    };
+    StoredAccount::new(if name.is_empty() { username } else { name }, session)
+}
+
+/// Лаунчеры пишут UUID без дефисов, игра принимает оба вида
+fn undashed(id: &str) -> String {
+    id.replace('-', "")
+}
+
+/// Формат лаунчера 2.x: учётка с вложенными профилями
+fn export_launcher_profiles(accounts: &[StoredAccount]) -> Value {
+    let mut db = Map::new();
+    for a in accounts {
+        let id = undashed(&a.player_id);
+        db.insert(
+            id.clone(),
+            json!({
+                "accessToken": a.access_token,
+                "username": a.username,
+                "profiles": { id: { "displayName": a.username } },
+                "properties": [],
+            }),
+        );
+    }
+    json!({ "profiles": {}, "authenticationDatabase": db })
+}
+
+/// Понимает и формат 2.x (с `profiles`), и старый плоский (`displayName`, `uuid`)
+fn import_launcher_profiles(value: &Value) -> Result<Vec<StoredAccount>, String> {
+    let db = value
+        .get("authenticationDatabase")
+        .and_then(Value::as_object)
+        .ok_or_else(|| "No authenticationDatabase in launcher profiles".to_string())?;
+
+    let mut accounts = Vec::new();
+    for (key, entry) in db {
+        let token = str_field(entry, "accessToken");
+        match entry.get("profiles").and_then(Value::as_object) {
+            Some(profiles) => {
+                for (id, profile) in profiles {
+                    let name = str_field(profile, "displayName");
+                    accounts.push(new_account(name.clone(), name, id.clone(), token.clone(), String::new()));
+                }
+            }
+            None => {
+                let name = str_field(entry, "displayName");
+                let uuid = match str_field(entry, "uuid") {
+                    u if u.is_empty() => key.clone(),
+                    u => u,
+                };
+                accounts.push(new_account(name.clone(), name, uuid, token, String::new()));
+            }
+        }
+    }
+    Ok(accounts)
+}
+
+fn export_multimc(accounts: &[StoredAccount]) -> Value {
+    let list: Vec<Value> = accounts
+        .iter()
+        .map(|a| {
+            // MSA у Prism требует блок `msa` с refresh-токеном, которого у нас нет; токен лаунчера
+            // или Yggdrasil — это учётка типа Mojang, ей хватает `ygg`
+            let kind = if a.session_type == "legacy" { "Offline" } else { "Mojang" };
+            json!({
+                "type": kind,
+                "ygg": {
+                    "token": a.access_token,
+                    "extra": { "userName": a.username, "clientToken": a.client_token.clone().unwrap_or_default() },
+                },
+                "profile": { "id": undashed(&a.player_id), "name": a.username, "capes": [] },
+            })
+        })
+        .collect();
+    json!({ "accounts": list, "formatVersion": 3 })
+}
+
+fn import_multimc(value: &Value) -> Result<Vec<StoredAccount>, String> {
+    let list = value
+        .get("accounts")
+        .and_then(Value::as_array)
+        .ok_or_else(|| "No accounts array in accounts.json".to_string())?;
+
+    let mut accounts = Vec::new();
+    for entry in list {
+        let profile = entry.get("profile").cloned().unwrap_or(Value::Null);
+        let name = str_field(&profile, "name");
+        let id = str_field(&profile, "id");
+        if name.is_empty() || id.is_empty() {
+            continue;
+        }
+        let token = entry.get("ygg").map(|y| str_field(y, "token")).unwrap_or_default();
+        let session_type = match entry.get("type").and_then(Value::as_str) {
+            Some("Offline") => "legacy",
+            _ => "mojang",
+        };
+        accounts.push(new_account(name.clone(), name, id, token, session_type.to_string()));
+    }
+    Ok(accounts)
+}
+
+fn str_field(value: &Value, key: &str) -> String {
+    value.get(key).and_then(Value::as_str).unwrap_or_default().to_string()
+}
+
+/// Сопоставляет входящие аккаунты с сохранёнными. Конфликт по имени важнее, чем по нику
+/// или UUID: без merge/rename его просто некуда записать.
+pub fn plan_import(incoming: Vec<StoredAccount>, existing: &[StoredAccount]) -> Vec<ImportCandidate> {
+    let by_name: HashMap<&str, &StoredAccount> = existing.iter().map(|a| (a.name.as_str(), a)).collect();
+    incoming
+        .into_iter()
+        .map(|account| {
+            let conflict = if by_name.contains_key(account.name.as_str()) {
+                Some((account.name.clone(), ConflictKind::Name))
+            } else if let Some(e) = existing.iter().find(|e| !e.username.is_empty() && e.username == account.username) {
+                Some((e.name.clone(), ConflictKind::Username))
+            } else {
+                existing
+                    .iter()
+                    .find(|e| !e.player_id.is_empty() && undashed(&e.player_id) == undashed(&account.player_id))
+                    .map(|e| (e.name.clone(), ConflictKind::PlayerId))
+            };
+            let action = if conflict.is_some() { ImportAction::Skip } else { ImportAction::Add };
+            ImportCandidate { account, conflict, action }
+        })
+        .collect()
+}
@@ -1,3 +1,4 @@
+use crate::auth::AuthProviderKind;
 use once_cell::sync::Lazy;
 use std::sync::Mutex;
 use tokio::runtime::Runtime;
@@ -37,7 +38,7 @@ pub fn take_auth_receiver() -> Option<AuthResultReceiver> {
     AUTH_CHANNEL.lock().unwrap().1.take()
 }
 
-pub fn spawn_auth_task(username: String, password: String, account_name: Option<String>) {
+pub fn spawn_auth_task(provider: AuthProviderKind, username: String, password: String, account_name: Option<String>) {
     let sender = get_auth_sender();
     let account_name_clone = account_name.clone();
     let username_clone = username.clone();
@@ -45,7 +46,7 @@ pub fn spawn_auth_task(username: String, password: String, account_name: Option<
     ASYNC_RUNTIME.spawn(async move {
         tracing::info!("Starting async auth for {}", username);
 
-        match crate::auth::auth(&username, &password).await {
+        match provider.provider().auth(&username, &password).await {
             Ok(auth_data) => {
                 let _ = sender.send(AuthResult::Success {
                     account_name: account_name_clone,
@@ -8,6 +8,7 @@ use std::time::Duration;
 use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
 use tokio::net::TcpStream;
 use uuid::Uuid;
+use super::{AuthData, AuthFuture, AuthProvider};
 
 const AUTH_URL: &str = "l.mcskill.net:7240";
 
@@ -26,10 +27,25 @@ d4d4799ad9f255d3cea77aa82067b37232e3298d2a970e93f1a0028ab6ec1f8aa7\
 64a18856782c4ff6661a7945416030ace7e02e95dd6a7d933e89b7157834ffd208\
 300ba1dd86e21e31ef0899e44d3839d0f2d8192ce0f27cdc5d0203010001";
 
-#[derive(Debug, Clone)]
-pub struct AuthData {
-    pub access_token: String,
-    pub profile: String,
+/// Протокол лаунчера MCSkill: TCP, RSA-шифрованный пароль. Обновления и проверки токена в нём нет.
+pub struct LauncherProvider;
+
+impl AuthProvider for LauncherProvider {
+    fn name(&self) -> &'static str {
+        "Launcher (MCSkill)"
+    }
+
+    fn auth<'a>(&'a self, username: &'a str, password: &'a str) -> AuthFuture<'a, AuthData> {
+        Box::pin(auth(username, password))
+    }
+
+    fn refresh<'a>(&'a self, _session: &'a AuthData) -> AuthFuture<'a, AuthData> {
+        Box::pin(async { Err(anyhow!("Launcher protocol has no token refresh, log in again")) })
+    }
+
+    fn validate<'a>(&'a self, _session: &'a AuthData) -> AuthFuture<'a, bool> {
+        Box::pin(async { Err(anyhow!("Launcher protocol has no token validation")) })
+    }
 }
 
 pub async fn auth(username: &str, password: &str) -> Result<AuthData> {
@@ -78,7 +94,7 @@ async fn auth_request(username: &str, encrypted_password: &[u8]) -> Result<AuthD
     }
 
     tracing::info!("Reading game profile...");
-    let (profile_uuid, _profile_name) = read_game_profile(&mut sock).await.context("Failed to read game profile")?;
+    let (profile_uuid, profile_name) = read_game_profile(&mut sock).await.context("Failed to read game profile")?;
     let selected_profile = profile_uuid.to_string().replace('-', "");
 
     tracing::info!("Reading token...");
@@ -89,6 +105,8 @@ async fn auth_request(username: &str, encrypted_password: &[u8]) -> Result<AuthD
     Ok(AuthData {
         access_token: token,
         profile: selected_profile,
+        username: profile_name,
+        client_token: None,
     })
 }
 
@@ -0,0 +1,67 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::pin::Pin;
+
+mod launcher;
+mod offline;
+mod yggdrasil;
+
+pub use launcher::LauncherProvider;
+pub use offline::{offline_session, OfflineProvider, OFFLINE_SESSION_TYPE};
+pub use yggdrasil::YggdrasilProvider;
+
+#[derive(Debug, Clone)]
+pub struct AuthData {
+    pub access_token: String,
+    /// UUID профиля без дефисов
+    pub profile: String,
+    /// Ник, который вернул сервер авторизации
+    pub username: String,
+    /// Нужен Yggdrasil для refresh/validate; у остальных `None`
+    pub client_token: Option<String>,
+}
+
+pub type AuthFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T>> + Send + 'a>>;
+
+/// Сервер авторизации. Методы возвращают boxed future, чтобы провайдер можно было держать как `dyn`.
+pub trait AuthProvider: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn auth<'a>(&'a self, username: &'a str, password: &'a str) -> AuthFuture<'a, AuthData>;
+    fn refresh<'a>(&'a self, session: &'a AuthData) -> AuthFuture<'a, AuthData>;
+    /// `Ok(false)` — токен отозван или истёк
+    fn validate<'a>(&'a self, session: &'a AuthData) -> AuthFuture<'a, bool>;
+}
+
+/// Какой провайдер у аккаунта; хранится в sessions.json
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AuthProviderKind {
+    #[default]
+    Launcher,
+    /// Yggdrasil-совместимый сервер (authlib-injector), `url` — корень API
+    Yggdrasil { url: String },
+    Offline,
+}
+
+impl AuthProviderKind {
+    pub fn provider(&self) -> Box<dyn AuthProvider> {
+        match self {
+            AuthProviderKind::Launcher => Box::new(LauncherProvider),
+            AuthProviderKind::Yggdrasil { url } => Box::new(YggdrasilProvider::new(url)),
+            AuthProviderKind::Offline => Box::new(OfflineProvider),
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            AuthProviderKind::Launcher => "Launcher",
+            AuthProviderKind::Yggdrasil { .. } => "Yggdrasil",
+            AuthProviderKind::Offline => "Offline",
+        }
+    }
+
+    pub fn needs_password(&self) -> bool {
+        !matches!(self, AuthProviderKind::Offline)
+    }
+}
@@ -0,0 +1,68 @@
+use super::{AuthData, AuthFuture, AuthProvider};
+use crate::jvm::SessionInfo;
+use anyhow::anyhow;
+use md5::{Digest, Md5};
+use uuid::Uuid;
+
+/// Токен-заглушка, offline-сервер его не проверяет
+pub const OFFLINE_ACCESS_TOKEN: &str = "0";
+
+/// `Session.Type` для ника без аккаунта; в 1.7.10 и 1.12.2 одинаковый
+pub const OFFLINE_SESSION_TYPE: &str = "legacy";
+
+/// Ограничение ника в LoginStart
+const MAX_USERNAME_LEN: usize = 16;
+
+/// Без сервера: UUID считается из ника так же, как это делает игра в offline-режиме.
+pub struct OfflineProvider;
+
+impl AuthProvider for OfflineProvider {
+    fn name(&self) -> &'static str {
+        "Offline"
+    }
+
+    fn auth<'a>(&'a self, username: &'a str, _password: &'a str) -> AuthFuture<'a, AuthData> {
+        Box::pin(async move {
+            let session = offline_session(username).map_err(|e| anyhow!(e))?;
+            Ok(AuthData {
+                access_token: session.access_token,
+                profile: session.player_id,
+                username: session.username,
+                client_token: None,
+            })
+        })
+    }
+
+    fn refresh<'a>(&'a self, session: &'a AuthData) -> AuthFuture<'a, AuthData> {
+        Box::pin(async move { Ok(session.clone()) })
+    }
+
+    fn validate<'a>(&'a self, _session: &'a AuthData) -> AuthFuture<'a, bool> {
+        Box::pin(async { Ok(true) })
+    }
+}
+
+/// `UUID.nameUUIDFromBytes("OfflinePlayer:" + name)`: MD5 без namespace с битами версии 3
+pub fn offline_uuid(username: &str) -> Uuid {
+    let mut bytes: [u8; 16] = Md5::digest(format!("OfflinePlayer:{}", username).as_bytes()).into();
+    bytes[6] = (bytes[6] & 0x0f) | 0x30;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    Uuid::from_bytes(bytes)
+}
+
+/// Готовая offline-сессия: UUID из ника, токен-заглушка и тип сессии под версию игры
+pub fn offline_session(username: &str) -> Result<SessionInfo, String> {
+    let username = username.trim();
+    if username.is_empty() {
+        return Err("Username is empty".to_string());
+    }
+    if username.chars().count() > MAX_USERNAME_LEN {
+        return Err(format!("Username is longer than {} characters", MAX_USERNAME_LEN));
+    }
+    Ok(SessionInfo {
+        username: username.to_string(),
+        player_id: offline_uuid(username).simple().to_string(),
+        access_token: OFFLINE_ACCESS_TOKEN.to_string(),
+        session_type: OFFLINE_SESSION_TYPE.to_string(),
+    })
+}
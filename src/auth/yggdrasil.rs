@@ -0,0 +1,144 @@
+use super::{AuthData, AuthFuture, AuthProvider};
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::time::Duration;
+
+const REQUEST_TIMEOUT_SECS: u64 = 15;
+/// authlib-injector: сайт может указать настоящий корень API в этом заголовке
+const API_LOCATION_HEADER: &str = "x-authlib-injector-api-location";
+
+/// Yggdrasil-совместимый сервер: Mojang-подобный authserver или authlib-injector.
+pub struct YggdrasilProvider {
+    url: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AuthResponse {
+    access_token: String,
+    client_token: Option<String>,
+    selected_profile: Option<Profile>,
+}
+
+#[derive(Deserialize)]
+struct Profile {
+    id: String,
+    name: String,
+}
+
+impl YggdrasilProvider {
+    pub fn new(url: &str) -> Self {
+        Self { url: url.trim().trim_end_matches('/').to_string() }
+    }
+
+    fn client() -> Result<reqwest::Client> {
+        reqwest::Client::builder()
+            .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+            .build()
+            .context("Failed to create HTTP client")
+    }
+
+    /// Корень API с учётом ALI-заголовка; относительный адрес считается от введённого URL
+    async fn api_root(&self, client: &reqwest::Client) -> Result<String> {
+        if self.url.is_empty() {
+            return Err(anyhow!("Yggdrasil server URL is empty"));
+        }
+        let response = client.get(&self.url).send().await.context("Failed to reach auth server")?;
+        let Some(location) = response.headers().get(API_LOCATION_HEADER).and_then(|v| v.to_str().ok()) else {
+            return Ok(self.url.clone());
+        };
+        let resolved = response.url().join(location).context("Invalid API location header")?;
+        Ok(resolved.as_str().trim_end_matches('/').to_string())
+    }
+
+    async fn post(&self, endpoint: &str, body: Value) -> Result<reqwest::Response> {
+        let client = Self::client()?;
+        let root = self.api_root(&client).await?;
+        client
+            .post(format!("{}/authserver/{}", root, endpoint))
+            .json(&body)
+            .send()
+            .await
+            .with_context(|| format!("{} request failed", endpoint))
+    }
+
+    async fn session_response(response: reqwest::Response) -> Result<AuthData> {
+        if !response.status().is_success() {
+            return Err(error_from(response).await);
+        }
+        let data: AuthResponse = response.json().await.context("Invalid auth server response")?;
+        let profile = data
+            .selected_profile
+            .ok_or_else(|| anyhow!("Account has no selected profile"))?;
+        Ok(AuthData {
+            access_token: data.access_token,
+            profile: profile.id.replace('-', ""),
+            username: profile.name,
+            client_token: data.client_token,
+        })
+    }
+
+    async fn authenticate(&self, username: &str, password: &str) -> Result<AuthData> {
+        tracing::info!("Begin Yggdrasil auth for {} at {}", username, self.url);
+        let body = json!({
+            "agent": { "name": "Minecraft", "version": 1 },
+            "username": username,
+            "password": password,
+            "clientToken": uuid::Uuid::new_v4().simple().to_string(),
+            "requestUser": false,
+        });
+        Self::session_response(self.post("authenticate", body).await?).await
+    }
+
+    async fn refresh_session(&self, session: &AuthData) -> Result<AuthData> {
+        let mut body = json!({ "accessToken": session.access_token });
+        if let Some(client_token) = &session.client_token {
+            body["clientToken"] = Value::from(client_token.as_str());
+        }
+        Self::session_response(self.post("refresh", body).await?).await
+    }
+
+    async fn validate_session(&self, session: &AuthData) -> Result<bool> {
+        let mut body = json!({ "accessToken": session.access_token });
+        if let Some(client_token) = &session.client_token {
+            body["clientToken"] = Value::from(client_token.as_str());
+        }
+        let response = self.post("validate", body).await?;
+        match response.status().as_u16() {
+            200..=299 => Ok(true),
+            403 => Ok(false),
+            _ => Err(error_from(response).await),
+        }
+    }
+}
+
+impl AuthProvider for YggdrasilProvider {
+    fn name(&self) -> &'static str {
+        "Yggdrasil"
+    }
+
+    fn auth<'a>(&'a self, username: &'a str, password: &'a str) -> AuthFuture<'a, AuthData> {
+        Box::pin(self.authenticate(username, password))
+    }
+
+    fn refresh<'a>(&'a self, session: &'a AuthData) -> AuthFuture<'a, AuthData> {
+        Box::pin(self.refresh_session(session))
+    }
+
+    fn validate<'a>(&'a self, session: &'a AuthData) -> AuthFuture<'a, bool> {
+        Box::pin(self.validate_session(session))
+    }
+}
+
+/// Yggdrasil отдаёт ошибки как `{"error": ..., "errorMessage": ...}`
+async fn error_from(response: reqwest::Response) -> anyhow::Error {
+    let status = response.status();
+    let body: Value = response.json().await.unwrap_or_default();
+    let message = body
+        .get("errorMessage")
+        .or_else(|| body.get("error"))
+        .and_then(Value::as_str)
+        .unwrap_or("no details");
+    anyhow!("Auth server returned {}: {}", status, message)
+}
@@ -0,0 +1,86 @@
+use crate::account::AccountSort;
+use crate::core::settings_file::SettingsFile;
+use crate::graphics::context::PayloadContext;
+use crate::ui::UiState;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Рядом с sessions.json. Отдельный файл: вид списка не шифруется и не попадает в резервные копии учёток.
+static SETTINGS: SettingsFile = SettingsFile::new("account_list.json", MIGRATIONS);
+
+/// Миграций пока нет; новые шаги дописываются сюда
+const MIGRATIONS: &[fn(&mut Value)] = &[];
+
+pub const SETTINGS_VERSION: u32 = MIGRATIONS.len() as u32 + 1;
+
+/// Сортировка и фильтры списка учёток. Строка поиска не сохраняется.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountListSettings {
+    pub version: u32,
+    pub sort: AccountSort,
+    pub sort_descending: bool,
+    pub group_by_folder: bool,
+    pub favorites_only: bool,
+    pub filter_tag: String,
+    /// `Some(true)` — только с паролем, `Some(false)` — только без
+    pub filter_password: Option<bool>,
+    /// Пустая строка — любой тип сессии
+    pub filter_session: String,
+}
+
+impl AccountListSettings {
+    pub fn from_ui(ui_state: &UiState) -> Self {
+        Self {
+            version: SETTINGS_VERSION,
+            sort: *ui_state.account_sort,
+            sort_descending: *ui_state.account_sort_desc,
+            group_by_folder: *ui_state.account_group_by_folder,
+            favorites_only: *ui_state.account_favorites_only,
+            filter_tag: ui_state.account_filter_tag.clone(),
+            filter_password: *ui_state.account_filter_password,
+            filter_session: ui_state.account_filter_session.clone(),
+        }
+    }
+
+    fn from_context(ctx: &PayloadContext) -> Self {
+        Self {
+            version: SETTINGS_VERSION,
+            sort: ctx.account_sort,
+            sort_descending: ctx.account_sort_desc,
+            group_by_folder: ctx.account_group_by_folder,
+            favorites_only: ctx.account_favorites_only,
+            filter_tag: ctx.account_filter_tag.clone(),
+            filter_password: ctx.account_filter_password,
+            filter_session: ctx.account_filter_session.clone(),
+        }
+    }
+
+    fn apply(self, ctx: &mut PayloadContext) {
+        ctx.account_sort = self.sort;
+        ctx.account_sort_desc = self.sort_descending;
+        ctx.account_group_by_folder = self.group_by_folder;
+        ctx.account_favorites_only = self.favorites_only;
+        ctx.account_filter_tag = self.filter_tag;
+        ctx.account_filter_password = self.filter_password;
+        ctx.account_filter_session = self.filter_session;
+    }
+}
+
+pub fn load_account_list_settings(ctx: &mut PayloadContext) {
+    match SETTINGS.load::<AccountListSettings>() {
+        Ok(loaded) => {
+            if let Some(settings) = loaded {
+                settings.apply(ctx);
+            }
+            SETTINGS.enable_saving(&AccountListSettings::from_context(ctx));
+        }
+        Err(e) => {
+            tracing::warn!("Failed to load account list settings, saving disabled: {}", e);
+        }
+    }
+}
+
+/// Вызывается каждый кадр вкладки; пишет файл только при изменениях.
+pub fn persist_account_list_settings(ui_state: &UiState) -> Result<(), String> {
+    SETTINGS.persist(|| AccountListSettings::from_ui(ui_state))
+}
@@ -0,0 +1,135 @@
+use crate::core::packet_triggers::PacketTrigger;
+use crate::core::settings_file::SettingsFile;
+use crate::core::state::GlobalState;
+use crate::graphics::context::{PacketFilterProfile, PayloadContext};
+use crate::ui::packet_columns::PacketColumn;
+use crate::ui::UiState;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Шаг `i` переводит файл из версии `i + 1` в `i + 2`. Новые шаги только дописываются в конец.
+const MIGRATIONS: &[fn(&mut Value)] = &[v1_add_columns, v2_add_split_rows];
+
+pub const SETTINGS_VERSION: u32 = MIGRATIONS.len() as u32 + 1;
+
+/// Рядом с sessions.json
+static SETTINGS: SettingsFile = SettingsFile::new("packet_analyzer.json", MIGRATIONS);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnalyzerSettings {
+    pub version: u32,
+    pub filter_profiles: Vec<PacketFilterProfile>,
+    pub triggers: Vec<PacketTrigger>,
+    pub limit_count: u32,
+    pub autoclear_oldest: bool,
+    pub export_limit: u32,
+    pub detached: bool,
+    pub window_open: bool,
+    pub columns: Vec<PacketColumn>,
+    /// Входящие слева, исходящие справа; иначе одна таблица на всю ширину
+    pub split_rows: bool,
+}
+
+impl AnalyzerSettings {
+    pub fn from_ui(ui_state: &UiState) -> Self {
+        Self {
+            version: SETTINGS_VERSION,
+            filter_profiles: ui_state.packet_filter_profiles.clone(),
+            triggers: saved_triggers(),
+            limit_count: *ui_state.packet_limit_count,
+            autoclear_oldest: *ui_state.packet_autoclear_oldest,
+            export_limit: *ui_state.packet_export_limit,
+            detached: *ui_state.packets_detached,
+            window_open: *ui_state.packets_window_open,
+            columns: ui_state.packet_columns.clone(),
+            split_rows: *ui_state.packet_split_rows,
+        }
+    }
+
+    fn from_context(ctx: &PayloadContext) -> Self {
+        Self {
+            version: SETTINGS_VERSION,
+            filter_profiles: ctx.packet_filter_profiles.clone(),
+            triggers: saved_triggers(),
+            limit_count: ctx.packet_limit_count,
+            autoclear_oldest: ctx.packet_autoclear_oldest,
+            export_limit: ctx.packet_export_limit,
+            detached: ctx.packets_detached,
+            window_open: ctx.packets_window_open,
+            columns: ctx.packet_columns.clone(),
+            split_rows: ctx.packet_split_rows,
+        }
+    }
+
+    /// Раскладывает настройки по контексту, движку триггеров и лимитам стора.
+    fn apply(self, ctx: &mut PayloadContext) {
+        ctx.packet_filter_profiles = self.filter_profiles;
+        ctx.packet_limit_count = self.limit_count;
+        ctx.packet_autoclear_oldest = self.autoclear_oldest;
+        ctx.packet_export_limit = self.export_limit;
+        ctx.packets_detached = self.detached;
+        ctx.packets_window_open = self.window_open;
+        ctx.packet_columns = self.columns;
+        ctx.packet_split_rows = self.split_rows;
+
+        if let Some(triggers) = GlobalState::instance().get_packet_triggers().get() {
+            let mut engine = triggers.lock();
+            for trigger in self.triggers {
+                let name = trigger.name.clone();
+                if let Err(e) = engine.add(trigger) {
+                    tracing::warn!("Skipping saved trigger '{}': {}", name, e);
+                }
+            }
+        }
+        if let Some(store) = GlobalState::instance().get_packet_store().get() {
+            let mut store = store.lock();
+            store.set_max_count((self.limit_count > 0).then_some(self.limit_count as usize));
+            store.set_autoclear_oldest(self.autoclear_oldest);
+        }
+    }
+}
+
+fn saved_triggers() -> Vec<PacketTrigger> {
+    GlobalState::instance()
+        .get_packet_triggers()
+        .get()
+        .map(|t| t.lock().triggers().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// v2: колонки таблицы, общие и у каждого профиля
+fn v1_add_columns(value: &mut Value) {
+    let columns = serde_json::to_value(PacketColumn::defaults()).unwrap_or_default();
+    if let Some(profiles) = value.get_mut("filter_profiles").and_then(Value::as_array_mut) {
+        for profile in profiles.iter_mut().filter_map(Value::as_object_mut) {
+            profile.insert("columns".to_string(), columns.clone());
+        }
+    }
+    value["columns"] = columns;
+}
+
+/// v3: раскладка строк, по умолчанию прежняя — входящие слева, исходящие справа
+fn v2_add_split_rows(value: &mut Value) {
+    value["split_rows"] = Value::from(true);
+}
+
+/// Загрузка при создании контекста. Стор и движок триггеров должны быть уже созданы.
+pub fn load_analyzer_settings(ctx: &mut PayloadContext) {
+    match SETTINGS.load::<AnalyzerSettings>() {
+        Ok(loaded) => {
+            if let Some(settings) = loaded {
+                settings.apply(ctx);
+                tracing::info!("Loaded packet analyzer settings");
+            }
+            SETTINGS.enable_saving(&AnalyzerSettings::from_context(ctx));
+        }
+        Err(e) => {
+            tracing::warn!("Failed to load packet analyzer settings, saving disabled: {}", e);
+        }
+    }
+}
+
+/// Вызывается каждый кадр; пишет файл только если что-то поменялось.
+pub fn persist_analyzer_settings(ui_state: &UiState) -> Result<(), String> {
+    SETTINGS.persist(|| AnalyzerSettings::from_ui(ui_state))
+}
@@ -0,0 +1,188 @@
+use crate::account_files;
+use crate::core::custom_payload::record_packet_id;
+use crate::core::packet_query::record_channel;
+use crate::graphics::netlog::{PacketDirection, PacketRecord};
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::BufReader,
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Annotation {
+    pub text: String,
+    pub updated_at: u64,
+    /// Имя пакета на момент записи — чтобы в файле было видно, о чём заметка
+    #[serde(default)]
+    pub label: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnnotationStorage {
+    /// Заметки на тип пакета: `channel#id`, либо канал, либо имя класса
+    pub types: HashMap<String, Annotation>,
+    /// Заметки на конкретную запись, по отпечатку содержимого
+    pub records: HashMap<String, Annotation>,
+    pub version: u32,
+}
+
+impl Default for AnnotationStorage {
+    fn default() -> Self {
+        Self {
+            types: HashMap::new(),
+            records: HashMap::new(),
+            version: 1,
+        }
+    }
+}
+
+/// Ключ типа: для custom payload — канал и дискриминатор, для остальных — класс пакета.
+pub fn type_key(rec: &PacketRecord) -> String {
+    let channel = record_channel(rec);
+    if channel.is_empty() {
+        return rec.name.clone();
+    }
+    match record_packet_id(rec) {
+        Some(id) => format!("{channel}#{id}"),
+        None => channel.to_string(),
+    }
+}
+
+/// Отпечаток записи: направление, класс и байты. id и время не входят,
+/// так что заметка находит ту же запись после импорта или в новой сессии.
+pub fn record_key(rec: &PacketRecord) -> String {
+    let mut hasher = Sha1::new();
+    hasher.update(match rec.dir {
+        PacketDirection::Inbound => b"in:".as_slice(),
+        PacketDirection::Outbound => b"out:".as_slice(),
+    });
+    hasher.update(rec.name.as_bytes());
+    hasher.update([0u8]);
+    hasher.update(&rec.data);
+    hex::encode(hasher.finalize())
+}
+
+#[derive(Debug, Clone)]
+pub struct AnnotationManager {
+    storage: AnnotationStorage,
+    config_path: String,
+}
+
+impl AnnotationManager {
+    pub fn new() -> Self {
+        // рядом с sessions.json
+        let config_path = "packet_annotations.json".to_string();
+        let mut manager = Self {
+            storage: AnnotationStorage::default(),
+            config_path,
+        };
+
+        if let Err(e) = manager.load() {
+            tracing::warn!("Failed to load annotations: {}", e);
+        }
+
+        manager
+    }
+
+    pub fn load(&mut self) -> Result<(), String> {
+        if !Path::new(&self.config_path).exists() {
+            return Ok(());
+        }
+
+        let file = File::open(&self.config_path)
+            .map_err(|e| format!("Failed to open annotations file: {}", e))?;
+
+        let reader = BufReader::new(file);
+        self.storage = serde_json::from_reader(reader)
+            .map_err(|e| format!("Failed to parse annotations file: {}", e))?;
+
+        tracing::info!(
+            "Loaded {} type and {} record annotations",
+            self.storage.types.len(),
+            self.storage.records.len()
+        );
+        Ok(())
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let bytes = serde_json::to_vec_pretty(&self.storage)
+            .map_err(|e| format!("Failed to serialize annotations: {}", e))?;
+        // через временный файл: падение посреди записи не теряет заметки
+        account_files::write_atomic(&self.config_path, &bytes)
+    }
+
+    pub fn type_note(&self, rec: &PacketRecord) -> Option<&Annotation> {
+        self.storage.types.get(&type_key(rec))
+    }
+
+    /// `key` — из `record_key`; считать его на каждый кадр дорого, вызывающий кэширует
+    pub fn record_note(&self, key: &str) -> Option<&Annotation> {
+        self.storage.records.get(key)
+    }
+
+    pub fn has_record_notes(&self) -> bool {
+        !self.storage.records.is_empty()
+    }
+
+    /// Пустой текст удаляет заметку.
+    pub fn set_type_note(&mut self, rec: &PacketRecord, text: &str) -> Result<(), String> {
+        Self::put(&mut self.storage.types, type_key(rec), rec, text);
+        self.save()
+    }
+
+    pub fn set_record_note(&mut self, rec: &PacketRecord, text: &str) -> Result<(), String> {
+        Self::put(&mut self.storage.records, record_key(rec), rec, text);
+        self.save()
+    }
+
+    fn put(map: &mut HashMap<String, Annotation>, key: String, rec: &PacketRecord, text: &str) {
+        let text = text.trim();
+        if text.is_empty() {
+            map.remove(&key);
+            return;
+        }
+        map.insert(
+            key,
+            Annotation {
+                text: text.to_string(),
+                updated_at: SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap()
+                    .as_secs(),
+                label: rec.name.rsplit('.').next().unwrap_or(&rec.name).to_string(),
+            },
+        );
+    }
+
+    pub fn export_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(&self.storage).map_err(|e| format!("Failed to serialize annotations: {}", e))
+    }
+
+    /// Слияние с чужим файлом заметок: при совпадении ключа побеждает более свежая.
+    pub fn import_json(&mut self, text: &str) -> Result<usize, String> {
+        let other: AnnotationStorage =
+            serde_json::from_str(text).map_err(|e| format!("Failed to parse annotations: {}", e))?;
+        let mut merged = 0;
+        for (mine, theirs) in [
+            (&mut self.storage.types, other.types),
+            (&mut self.storage.records, other.records),
+        ] {
+            for (key, note) in theirs {
+                if mine.get(&key).is_none_or(|m| m.updated_at < note.updated_at) {
+                    mine.insert(key, note);
+                    merged += 1;
+                }
+            }
+        }
+        self.save()?;
+        Ok(merged)
+    }
+
+    pub fn counts(&self) -> (usize, usize) {
+        (self.storage.types.len(), self.storage.records.len())
+    }
+}
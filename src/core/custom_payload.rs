@@ -3,8 +3,9 @@ use once_cell::sync::Lazy;
 use std::collections::HashMap;
 use std::sync::RwLock;
 
-use crate::core::packets::Bound;
+use crate::core::packets::{Bound, List, VarInt};
 use crate::core::packets::dwcity::register_mod_payload_decoders;
+use crate::graphics::netlog::{PacketDetails, PacketDirection, PacketRecord};
 use crate::core::packets::customnpcs::register_customnpcs_decoder;
 
 #[derive(Debug, Clone)]
@@ -48,6 +49,14 @@ macro_rules! impl_td_text {
 }
 impl_td_text!(bool, i8,i16,i32,i64,isize, u8,u16,u32,u64,usize, f32,f64, String);
 
+impl ToDecodedValue for VarInt {
+    fn to_decoded_value(&self) -> DecodedValue { DecodedValue::Text(self.0.to_string()) }
+}
+
+impl ToDecodedValue for uuid::Uuid {
+    fn to_decoded_value(&self) -> DecodedValue { DecodedValue::Text(self.to_string()) }
+}
+
 impl ToDecodedValue for &str {
     fn to_decoded_value(&self) -> DecodedValue { DecodedValue::Text((*self).to_string()) }
 }
@@ -63,6 +72,12 @@ impl ToDecodedValue for Vec<String> {
     }
 }
 
+impl<T: ToDecodedValue> ToDecodedValue for List<T> {
+    fn to_decoded_value(&self) -> DecodedValue {
+        DecodedValue::List(self.0.iter().map(|v| v.to_decoded_value()).collect())
+    }
+}
+
 // Option<T>
 impl<T: ToDecodedValue> ToDecodedValue for Option<T> {
     fn to_decoded_value(&self) -> DecodedValue {
@@ -102,23 +117,6 @@ fn read_varint_at(data: &[u8], off: &mut usize) -> Option<i32> {
 /// Возвращает срез **payload** (без шапки).
 fn slice_payload_from_full_custom_payload(full: &[u8]) -> Option<&[u8]> {
     // --- helpers ---
-    fn read_varint_at(data: &[u8], off: &mut usize) -> Option<i32> {
-        let mut num_read = 0u32;
-        let mut result: i32 = 0;
-        loop {
-            if *off >= data.len() { return None; }
-            let read = data[*off];
-            *off += 1;
-
-            let value = (read & 0x7F) as i32;
-            result |= value << (7 * num_read);
-            num_read += 1;
-
-            if num_read > 5 { return None; }
-            if (read & 0x80) == 0 { break; }
-        }
-        Some(result)
-    }
     #[inline]
     fn read_u16_be_at(data: &[u8], off: &mut usize) -> Option<usize> {
         if *off + 2 > data.len() { return None; }
@@ -217,6 +215,28 @@ pub fn decode_custom_payload(channel: &str, full_buf: &[u8], bound: Bound) -> Op
     map.get(channel).and_then(|d| d.try_decode(payload, bound))
 }
 
+/// Payload записи CustomPayload без шапки (канал + длина), как его видит декодер.
+pub fn record_payload(rec: &PacketRecord) -> Option<&[u8]> {
+    let Some(PacketDetails::CustomPayload { .. }) = &rec.details else { return None; };
+    slice_payload_from_full_custom_payload(&rec.data)
+}
+
+/// VarInt-дискриминатор пакета внутри payload
+pub fn record_packet_id(rec: &PacketRecord) -> Option<i32> {
+    let payload = record_payload(rec)?;
+    read_varint_at(payload, &mut 0)
+}
+
+/// Декодирование записи лога: канал и направление берутся из самой записи.
+pub fn decode_record(rec: &PacketRecord) -> Option<DecodedStruct> {
+    let Some(PacketDetails::CustomPayload { channel, .. }) = &rec.details else { return None; };
+    let bound = match rec.dir {
+        PacketDirection::Inbound => Bound::Server,
+        PacketDirection::Outbound => Bound::Client,
+    };
+    decode_custom_payload(channel, &rec.data, bound)
+}
+
 pub fn init_default_decoders() {
     register_mod_payload_decoders();
     register_customnpcs_decoder();
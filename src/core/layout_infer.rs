@@ -0,0 +1,382 @@
+use crate::core::custom_payload::record_payload;
+use crate::core::packets::Bound;
+use crate::graphics::netlog::{PacketDetails, PacketDirection, PacketRecord};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// Дальше не разбираем — такой длинный черновик всё равно придётся чистить руками
+const MAX_FIELDS: usize = 96;
+/// Сколько примеров значений показывать у поля
+const EXAMPLES: usize = 3;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldKind {
+    Bool,
+    U8,
+    I16,
+    I32,
+    I64,
+    F32,
+    F64,
+    VarInt,
+    /// VarInt длина + UTF-8, как читает `String`
+    StringVarInt,
+    /// u16 длина + UTF-8 (DataOutput.writeUTF)
+    StringU16,
+    /// u16 число символов + UTF-16BE
+    StringUtf16,
+    Uuid,
+    /// Одинаковые во всех примерах байты
+    Const(Vec<u8>),
+    /// Хвост разной длины, который не удалось разобрать
+    Trailing { min: usize, max: usize },
+}
+
+impl FieldKind {
+    /// Тип для `mod_packets!`; `None` — читателя под такой формат нет
+    pub fn rust_type(&self) -> Option<&'static str> {
+        Some(match self {
+            FieldKind::Bool => "bool",
+            FieldKind::U8 => "u8",
+            FieldKind::I16 => "i16",
+            FieldKind::I32 => "i32",
+            FieldKind::I64 => "i64",
+            FieldKind::F32 => "f32",
+            FieldKind::F64 => "f64",
+            FieldKind::VarInt => "crate::core::packets::VarInt",
+            FieldKind::StringVarInt => "String",
+            FieldKind::Uuid => "uuid::Uuid",
+            FieldKind::Const(b) => match b.len() {
+                1 => "u8",
+                2 => "i16",
+                4 => "i32",
+                8 => "i64",
+                _ => return None,
+            },
+            FieldKind::StringU16 | FieldKind::StringUtf16 | FieldKind::Trailing { .. } => return None,
+        })
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            FieldKind::StringVarInt => "string (VarInt len)".to_string(),
+            FieldKind::StringU16 => "string (u16 len, UTF-8)".to_string(),
+            FieldKind::StringUtf16 => "string (u16 len, UTF-16)".to_string(),
+            FieldKind::Const(b) => format!("const {}", hex_bytes(b)),
+            FieldKind::Trailing { min, max } => format!("trailing {min}..{max} bytes"),
+            k => k.rust_type().unwrap_or("?").rsplit("::").next().unwrap_or("?").to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct InferredField {
+    pub kind: FieldKind,
+    /// Смещение в первом примере (после ID пакета)
+    pub offset: usize,
+    pub examples: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct InferredPacket {
+    pub id: i32,
+    pub bound: Bound,
+    pub samples: usize,
+    pub fields: Vec<InferredField>,
+}
+
+/// Все записи канала, разложенные по (ID, направление), с выведенной раскладкой.
+pub fn infer_channel(channel: &str, records: &[PacketRecord]) -> Vec<InferredPacket> {
+    let mut groups: BTreeMap<(i32, u8), Vec<&[u8]>> = BTreeMap::new();
+    for rec in records {
+        let Some(PacketDetails::CustomPayload { channel: ch, .. }) = &rec.details else { continue };
+        if ch != channel {
+            continue;
+        }
+        let Some(payload) = record_payload(rec) else { continue };
+        let Some((id, n)) = varint_at(payload) else { continue };
+        let dir = match rec.dir {
+            PacketDirection::Inbound => 0,
+            PacketDirection::Outbound => 1,
+        };
+        groups.entry((id, dir)).or_default().push(&payload[n..]);
+    }
+
+    groups
+        .into_iter()
+        .map(|((id, dir), samples)| InferredPacket {
+            id,
+            bound: if dir == 0 { Bound::Server } else { Bound::Client },
+            samples: samples.len(),
+            fields: infer_layout(&samples),
+        })
+        .collect()
+}
+
+/// Разбор полей по нескольким примерам одного пакета. У каждого примера свой курсор,
+/// поэтому поля после строк переменной длины тоже выравниваются.
+pub fn infer_layout(samples: &[&[u8]]) -> Vec<InferredField> {
+    let mut fields = Vec::new();
+    if samples.is_empty() {
+        return fields;
+    }
+    let mut pos = vec![0usize; samples.len()];
+
+    while fields.len() < MAX_FIELDS {
+        let rems: Vec<&[u8]> = samples.iter().zip(&pos).map(|(s, p)| &s[*p..]).collect();
+        let min_rem = rems.iter().map(|r| r.len()).min().unwrap_or(0);
+        let max_rem = rems.iter().map(|r| r.len()).max().unwrap_or(0);
+        if max_rem == 0 {
+            break;
+        }
+        if min_rem == 0 {
+            fields.push(InferredField {
+                kind: FieldKind::Trailing { min: min_rem, max: max_rem },
+                offset: pos[0],
+                examples: rems.iter().take(EXAMPLES).map(|r| hex_bytes(&r[..r.len().min(16)])).collect(),
+            });
+            break;
+        }
+
+        let (kind, used) = next_field(&rems, min_rem);
+        fields.push(InferredField {
+            offset: pos[0],
+            examples: rems
+                .iter()
+                .zip(&used)
+                .take(EXAMPLES)
+                .map(|(r, n)| format_value(&kind, &r[..*n]))
+                .collect(),
+            kind,
+        });
+        for (p, n) in pos.iter_mut().zip(&used) {
+            *p += *n;
+        }
+    }
+    fields
+}
+
+fn next_field(rems: &[&[u8]], min_rem: usize) -> (FieldKind, Vec<usize>) {
+    if let Some(used) = try_string_varint(rems) {
+        return (FieldKind::StringVarInt, used);
+    }
+    if let Some(used) = try_string_u16(rems) {
+        return (FieldKind::StringU16, used);
+    }
+    if let Some(used) = try_string_utf16(rems) {
+        return (FieldKind::StringUtf16, used);
+    }
+    let same = |n: usize| vec![n; rems.len()];
+    if min_rem >= 16 && rems.iter().all(|r| looks_like_uuid(&r[..16])) {
+        return (FieldKind::Uuid, same(16));
+    }
+    if min_rem >= 8 && rems.iter().all(|r| plausible_f64(&r[..8]) && !plausible_f32(&r[4..8])) {
+        return (FieldKind::F64, same(8));
+    }
+    if min_rem >= 4 && rems.iter().all(|r| plausible_f32(&r[..4])) {
+        return (FieldKind::F32, same(4));
+    }
+
+    let run = const_run(rems, min_rem);
+    if run > 0 {
+        // 00 00 00 05 / FF FF FF FE: старшие байты совпадают, младшие меняются — это целое
+        if matches!(rems[0][0], 0 | 0xFF) {
+            for (w, kind) in [(2, FieldKind::I16), (4, FieldKind::I32), (8, FieldKind::I64)] {
+                if run < w && w <= min_rem {
+                    return (kind, same(w));
+                }
+            }
+        }
+        // хвост из 00/FF перед меняющимся байтом оставляем следующему целому
+        let mut keep = run;
+        if run < min_rem {
+            while keep > 0 && run - keep < 7 && matches!(rems[0][keep - 1], 0 | 0xFF) {
+                keep -= 1;
+            }
+        }
+        if keep == 0 {
+            keep = run;
+        }
+        let w = [8, 4, 2, 1].into_iter().find(|w| *w <= keep).unwrap_or(1);
+        return (FieldKind::Const(rems[0][..w].to_vec()), same(w));
+    }
+
+    if rems.iter().all(|r| r[0] <= 1) {
+        return (FieldKind::Bool, same(1));
+    }
+    let varints: Option<Vec<usize>> = rems.iter().map(|r| varint_at(r).map(|(_, n)| n)).collect();
+    if let Some(used) = varints {
+        if used.iter().any(|n| *n > 1) {
+            return (FieldKind::VarInt, used);
+        }
+    }
+    (FieldKind::U8, same(1))
+}
+
+fn const_run(rems: &[&[u8]], min_rem: usize) -> usize {
+    // один пример — «константами» считать нечего
+    if rems.len() < 2 {
+        return 0;
+    }
+    (0..min_rem)
+        .take_while(|i| rems.iter().all(|r| r[*i] == rems[0][*i]))
+        .count()
+}
+
+fn printable(s: &str) -> bool {
+    s.chars().all(|c| !c.is_control() || c == '\n' || c == '\t')
+}
+
+fn try_string_varint(rems: &[&[u8]]) -> Option<Vec<usize>> {
+    let mut longest = 0;
+    let used = rems
+        .iter()
+        .map(|r| {
+            let (len, n) = varint_at(r)?;
+            let len = usize::try_from(len).ok().filter(|l| *l <= 32767 && n + *l <= r.len())?;
+            let s = std::str::from_utf8(&r[n..n + len]).ok().filter(|s| printable(s))?;
+            longest = longest.max(s.len());
+            Some(n + len)
+        })
+        .collect::<Option<Vec<_>>>()?;
+    (longest >= 2).then_some(used)
+}
+
+fn try_string_u16(rems: &[&[u8]]) -> Option<Vec<usize>> {
+    let mut longest = 0;
+    let used = rems
+        .iter()
+        .map(|r| {
+            let len = u16::from_be_bytes([*r.first()?, *r.get(1)?]) as usize;
+            let s = std::str::from_utf8(r.get(2..2 + len)?).ok().filter(|s| printable(s))?;
+            longest = longest.max(s.len());
+            Some(2 + len)
+        })
+        .collect::<Option<Vec<_>>>()?;
+    (longest >= 2).then_some(used)
+}
+
+fn try_string_utf16(rems: &[&[u8]]) -> Option<Vec<usize>> {
+    let mut longest = 0;
+    let used = rems
+        .iter()
+        .map(|r| {
+            let chars = u16::from_be_bytes([*r.first()?, *r.get(1)?]) as usize;
+            let units: Vec<u16> = r
+                .get(2..2 + chars * 2)?
+                .chunks(2)
+                .map(|c| u16::from_be_bytes([c[0], c[1]]))
+                .collect();
+            let s = String::from_utf16(&units).ok().filter(|s| printable(s))?;
+            longest = longest.max(s.chars().count());
+            Some(2 + chars * 2)
+        })
+        .collect::<Option<Vec<_>>>()?;
+    (longest >= 2).then_some(used)
+}
+
+/// v3 (offline, MD5 ника) или v4 (лицензия), вариант RFC 4122
+fn looks_like_uuid(b: &[u8]) -> bool {
+    matches!(b[6] >> 4, 3 | 4) && (b[8] & 0xC0) == 0x80
+}
+
+fn plausible(v: f64, max: f64) -> bool {
+    v.is_finite() && (v == 0.0 || (v.abs() >= 1e-6 && v.abs() <= max))
+}
+
+fn plausible_f32(b: &[u8]) -> bool {
+    let v = f32::from_be_bytes([b[0], b[1], b[2], b[3]]);
+    // нули — скорее целое, поплавком считаем только ненулевое
+    v != 0.0 && plausible(v as f64, 1e7)
+}
+
+fn plausible_f64(b: &[u8]) -> bool {
+    let v = f64::from_be_bytes(b[..8].try_into().unwrap_or([0; 8]));
+    v != 0.0 && plausible(v, 1e12)
+}
+
+fn varint_at(data: &[u8]) -> Option<(i32, usize)> {
+    let mut result = 0i32;
+    for (i, b) in data.iter().take(5).enumerate() {
+        result |= ((b & 0x7F) as i32) << (7 * i);
+        if b & 0x80 == 0 {
+            return Some((result, i + 1));
+        }
+    }
+    None
+}
+
+fn hex_bytes(b: &[u8]) -> String {
+    b.iter().map(|x| format!("{x:02X}")).collect::<Vec<_>>().join(" ")
+}
+
+fn format_value(kind: &FieldKind, b: &[u8]) -> String {
+    let be = |n: usize| b.iter().take(n).fold(0u64, |acc, x| (acc << 8) | *x as u64);
+    match kind {
+        FieldKind::Bool => (b[0] != 0).to_string(),
+        FieldKind::U8 => b[0].to_string(),
+        FieldKind::I16 => (be(2) as u16 as i16).to_string(),
+        FieldKind::I32 => (be(4) as u32 as i32).to_string(),
+        FieldKind::I64 => (be(8) as i64).to_string(),
+        FieldKind::F32 => f32::from_bits(be(4) as u32).to_string(),
+        FieldKind::F64 => f64::from_bits(be(8)).to_string(),
+        FieldKind::VarInt => varint_at(b).map(|(v, _)| v.to_string()).unwrap_or_default(),
+        FieldKind::StringVarInt => {
+            let n = varint_at(b).map(|(_, n)| n).unwrap_or(0);
+            format!("{:?}", String::from_utf8_lossy(&b[n..]))
+        }
+        FieldKind::StringU16 => format!("{:?}", String::from_utf8_lossy(&b[2..])),
+        FieldKind::StringUtf16 => {
+            let units: Vec<u16> = b[2..].chunks(2).map(|c| u16::from_be_bytes([c[0], c[1]])).collect();
+            format!("{:?}", String::from_utf16_lossy(&units))
+        }
+        FieldKind::Uuid => uuid::Uuid::from_slice(b).map(|u| u.to_string()).unwrap_or_default(),
+        FieldKind::Const(_) | FieldKind::Trailing { .. } => hex_bytes(b),
+    }
+}
+
+/// Черновик `mod_packets!` для копирования в `core/packets/<channel>.rs`.
+pub fn draft_declaration(channel: &str, packets: &[InferredPacket]) -> String {
+    let ident: String = channel
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' })
+        .collect();
+    let ident = if ident.starts_with(|c: char| c.is_ascii_digit()) { format!("_{ident}") } else { ident };
+
+    let mut out = String::new();
+    if ident != channel {
+        let _ = writeln!(out, "// канал \"{channel}\": CHANNEL берётся из идентификатора, поправьте имя");
+    }
+    let _ = writeln!(out, "mod_packets! {{");
+    let _ = writeln!(out, "    channel: {ident},");
+    let _ = writeln!(out, "    packets {{");
+    for (pi, p) in packets.iter().enumerate() {
+        let (b, suffix) = match p.bound {
+            Bound::Server => ("S", "Response"),
+            Bound::Client => ("C", "Request"),
+        };
+        let _ = writeln!(out, "        // {} samples", p.samples);
+        let _ = writeln!(out, "        Packet{}{suffix}({}, {b}) {{", p.id, p.id);
+        let last = p.fields.len().saturating_sub(1);
+        for (i, f) in p.fields.iter().enumerate() {
+            let comma = if i == last { "" } else { "," };
+            let note = match &f.kind {
+                FieldKind::Const(bytes) => format!("  // const {}", hex_bytes(bytes)),
+                _ => String::new(),
+            };
+            match f.kind.rust_type() {
+                Some(ty) => {
+                    let _ = writeln!(out, "            field_{i}: {ty}{comma}{note}");
+                }
+                None => {
+                    let _ = writeln!(out, "            // field_{i} @{}: {} — нет читателя, нужен свой тип", f.offset, f.kind.label());
+                }
+            }
+        }
+        let close = if pi + 1 == packets.len() { "}" } else { "}," };
+        let _ = writeln!(out, "        {close}");
+    }
+    let _ = writeln!(out, "    }}");
+    let _ = writeln!(out, "}}");
+    out
+}
@@ -3,6 +3,18 @@ pub mod logging;
 pub mod cleanup;
 pub mod custom_payload;
 pub mod packets;
+pub mod packet_query;
+pub mod packet_search;
+pub mod packet_diff;
+pub mod layout_infer;
+pub mod packet_correlation;
+pub mod annotations;
+pub mod packet_triggers;
+pub mod packet_builder;
+pub mod packet_snapshots;
+pub mod settings_file;
+pub mod analyzer_settings;
+pub mod account_list_settings;
 pub mod sound;
 pub mod jvm_analyzer;
 pub mod hwid;
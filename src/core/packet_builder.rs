@@ -0,0 +1,275 @@
+use crate::core::packet_query::parse_hex_bytes;
+use crate::core::packets::writer::ModPacketWriter;
+use crate::core::packets::{Bound, List, VarInt};
+use once_cell::sync::Lazy;
+use std::sync::RwLock;
+use uuid::Uuid;
+
+/// Схема поля для формы конструктора
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldKind {
+    /// Одно значение; строка — имя Rust-типа
+    Scalar(&'static str),
+    List(Box<FieldKind>),
+    Struct(Vec<FieldSpec>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldSpec {
+    pub name: &'static str,
+    pub kind: FieldKind,
+}
+
+/// Введённые значения, по форме повторяют `FieldKind`
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldInput {
+    Scalar(String),
+    List(Vec<FieldInput>),
+    Struct(Vec<(String, FieldInput)>),
+}
+
+impl FieldInput {
+    pub fn empty(kind: &FieldKind) -> Self {
+        match kind {
+            FieldKind::Scalar(_) => FieldInput::Scalar(String::new()),
+            FieldKind::List(_) => FieldInput::List(Vec::new()),
+            FieldKind::Struct(fields) => FieldInput::Struct(
+                fields
+                    .iter()
+                    .map(|f| (f.name.to_string(), FieldInput::empty(&f.kind)))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldError {
+    /// Путь как в `field.<path>`
+    pub path: String,
+    pub message: String,
+}
+
+/// Тип, который можно собрать из формы. Реализован для скаляров, `Vec<u8>`, `Vec<String>`, `List<T>`
+/// и генерируется `mod_packets!` для каждого пакета; пакет можно объявить полем другого, так что структуры вкладываются.
+pub trait BuildField: Sized {
+    fn kind() -> FieldKind;
+    /// Ошибки пишутся в `errors` по пути поля; `None`, если хоть одна есть.
+    fn from_input(input: &FieldInput, path: &str, errors: &mut Vec<FieldError>) -> Option<Self>;
+}
+
+pub fn field_path(prefix: &str, seg: &str) -> String {
+    if prefix.is_empty() { seg.to_string() } else { format!("{prefix}.{seg}") }
+}
+
+fn scalar<'a>(input: &'a FieldInput, path: &str, errors: &mut Vec<FieldError>) -> Option<&'a str> {
+    match input {
+        FieldInput::Scalar(s) => Some(s.as_str()),
+        _ => {
+            errors.push(FieldError { path: path.to_string(), message: "expected a single value".to_string() });
+            None
+        }
+    }
+}
+
+fn parse_scalar<T: std::str::FromStr>(
+    input: &FieldInput,
+    path: &str,
+    type_name: &str,
+    errors: &mut Vec<FieldError>,
+) -> Option<T> {
+    let text = scalar(input, path, errors)?.trim();
+    match text.parse::<T>() {
+        Ok(v) => Some(v),
+        Err(_) => {
+            let message = if text.is_empty() {
+                format!("{type_name} is required")
+            } else {
+                format!("'{text}' is not a valid {type_name}")
+            };
+            errors.push(FieldError { path: path.to_string(), message });
+            None
+        }
+    }
+}
+
+macro_rules! impl_build_number {
+    ($($t:ty),+ $(,)?) => {
+        $(impl BuildField for $t {
+            fn kind() -> FieldKind { FieldKind::Scalar(stringify!($t)) }
+            fn from_input(input: &FieldInput, path: &str, errors: &mut Vec<FieldError>) -> Option<Self> {
+                parse_scalar::<$t>(input, path, stringify!($t), errors)
+            }
+        })+
+    }
+}
+impl_build_number!(u8, i8, u16, i16, u32, i32, u64, i64, f32, f64);
+
+impl BuildField for bool {
+    fn kind() -> FieldKind { FieldKind::Scalar("bool") }
+    fn from_input(input: &FieldInput, path: &str, errors: &mut Vec<FieldError>) -> Option<Self> {
+        match scalar(input, path, errors)?.trim() {
+            "true" | "1" => Some(true),
+            "false" | "0" => Some(false),
+            other => {
+                errors.push(FieldError { path: path.to_string(), message: format!("'{other}' is not true/false") });
+                None
+            }
+        }
+    }
+}
+
+impl BuildField for String {
+    fn kind() -> FieldKind { FieldKind::Scalar("String") }
+    fn from_input(input: &FieldInput, path: &str, errors: &mut Vec<FieldError>) -> Option<Self> {
+        let text = scalar(input, path, errors)?;
+        // длина строки в протоколе — VarInt, но ванильный читатель режет на 32767 символах
+        if text.chars().count() > 32767 {
+            errors.push(FieldError { path: path.to_string(), message: "String is longer than 32767 chars".to_string() });
+            return None;
+        }
+        Some(text.to_string())
+    }
+}
+
+impl BuildField for VarInt {
+    fn kind() -> FieldKind { FieldKind::Scalar("VarInt") }
+    fn from_input(input: &FieldInput, path: &str, errors: &mut Vec<FieldError>) -> Option<Self> {
+        parse_scalar::<i32>(input, path, "VarInt", errors).map(VarInt)
+    }
+}
+
+impl BuildField for Uuid {
+    fn kind() -> FieldKind { FieldKind::Scalar("Uuid") }
+    fn from_input(input: &FieldInput, path: &str, errors: &mut Vec<FieldError>) -> Option<Self> {
+        parse_scalar::<Uuid>(input, path, "Uuid", errors)
+    }
+}
+
+/// Байты вводятся одной строкой hex, как в фильтре `hex:`
+impl BuildField for Vec<u8> {
+    fn kind() -> FieldKind { FieldKind::Scalar("bytes") }
+    fn from_input(input: &FieldInput, path: &str, errors: &mut Vec<FieldError>) -> Option<Self> {
+        let text = scalar(input, path, errors)?;
+        if text.trim().is_empty() {
+            return Some(Vec::new());
+        }
+        match parse_hex_bytes(text) {
+            Ok(bytes) => Some(bytes),
+            Err(e) => {
+                errors.push(FieldError { path: path.to_string(), message: e });
+                None
+            }
+        }
+    }
+}
+
+impl BuildField for Vec<String> {
+    fn kind() -> FieldKind { FieldKind::List(Box::new(String::kind())) }
+    fn from_input(input: &FieldInput, path: &str, errors: &mut Vec<FieldError>) -> Option<Self> {
+        list_items(input, path, errors)
+    }
+}
+
+impl<T: BuildField> BuildField for List<T> {
+    fn kind() -> FieldKind { FieldKind::List(Box::new(T::kind())) }
+    fn from_input(input: &FieldInput, path: &str, errors: &mut Vec<FieldError>) -> Option<Self> {
+        list_items(input, path, errors).map(List)
+    }
+}
+
+/// Элементы списка; ошибки собираются по всем элементам, а не до первой
+fn list_items<T: BuildField>(input: &FieldInput, path: &str, errors: &mut Vec<FieldError>) -> Option<Vec<T>> {
+    let FieldInput::List(items) = input else {
+        errors.push(FieldError { path: path.to_string(), message: "expected a list".to_string() });
+        return None;
+    };
+    let parsed: Vec<Option<T>> = items
+        .iter()
+        .enumerate()
+        .map(|(i, item)| T::from_input(item, &field_path(path, &i.to_string()), errors))
+        .collect();
+    parsed.into_iter().collect()
+}
+
+/// Поле структуры по имени; для `mod_packets!`
+pub fn struct_field<T: BuildField>(
+    input: &FieldInput,
+    path: &str,
+    name: &str,
+    errors: &mut Vec<FieldError>,
+) -> Option<T> {
+    let full = field_path(path, name);
+    let value = match input {
+        FieldInput::Struct(fields) => fields.iter().find(|(n, _)| n == name).map(|(_, v)| v),
+        _ => None,
+    };
+    match value {
+        Some(v) => T::from_input(v, &full, errors),
+        None => {
+            errors.push(FieldError { path: full, message: "missing field".to_string() });
+            None
+        }
+    }
+}
+
+/// Сборка payload пакета из формы через его `encode()`
+pub type EncodeFn = fn(&FieldInput, &mut Vec<FieldError>) -> Option<Vec<u8>>;
+
+/// Пакет канала, известный конструктору
+#[derive(Clone)]
+pub struct BuilderPacket {
+    pub channel: &'static str,
+    pub name: &'static str,
+    pub id: i32,
+    pub bound: Bound,
+    pub fields: FieldKind,
+    /// `encode()` генерируется только для Client-bound пакетов
+    pub encode: Option<EncodeFn>,
+}
+
+impl BuilderPacket {
+    /// Полный буфер CustomPayload (канал, длина, payload), как его пишет хук в лог
+    pub fn build(&self, input: &FieldInput) -> Result<Vec<u8>, Vec<FieldError>> {
+        let Some(encode) = self.encode else {
+            return Err(vec![FieldError {
+                path: String::new(),
+                message: format!("{} has no encode() (server-bound)", self.name),
+            }]);
+        };
+        let mut errors = Vec::new();
+        match encode(input, &mut errors) {
+            Some(payload) if errors.is_empty() => wrap_custom_payload(self.channel, &payload).map_err(|e| vec![e]),
+            _ => Err(errors),
+        }
+    }
+}
+
+static BUILDERS: Lazy<RwLock<Vec<BuilderPacket>>> = Lazy::new(|| RwLock::new(Vec::new()));
+
+/// Регистрирует пакеты канала; повторная регистрация канала заменяет старые.
+pub fn register_builder_packets(packets: Vec<BuilderPacket>) {
+    let mut all = BUILDERS.write().unwrap();
+    for p in &packets {
+        all.retain(|old| old.channel != p.channel);
+    }
+    all.extend(packets);
+}
+
+pub fn builder_packets() -> Vec<BuilderPacket> {
+    BUILDERS.read().unwrap().clone()
+}
+
+/// Длина payload в CustomPayload — u16, больше 65535 байт не влезает
+pub fn wrap_custom_payload(channel: &str, payload: &[u8]) -> Result<Vec<u8>, FieldError> {
+    let len = u16::try_from(payload.len()).map_err(|_| FieldError {
+        path: String::new(),
+        message: format!("payload is {} bytes, CustomPayload allows at most {}", payload.len(), u16::MAX),
+    })?;
+    let mut w = ModPacketWriter::new();
+    let _ = w.write_string_be_len(channel);
+    w.write_u16_be(len);
+    let mut out = w.into_bytes();
+    out.extend_from_slice(payload);
+    Ok(out)
+}
@@ -0,0 +1,100 @@
+use crate::core::custom_payload::{DecodedStruct, DecodedValue, record_packet_id};
+use crate::core::packet_query::{lookup_field, record_channel};
+use crate::graphics::netlog::{PacketDirection, PacketRecord};
+use std::collections::{HashMap, VecDeque};
+
+/// Ключ сопоставления для канала: кроме канала и ID совпадать должно ещё и это поле
+#[derive(Clone, Debug, PartialEq)]
+pub struct CorrelationRule {
+    pub channel: String,
+    /// Путь как в `field.<path>` фильтра, например `username`
+    pub key_field: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CorrelatedPair {
+    pub request_id: u64,
+    pub response_id: u64,
+    pub latency_ms: u64,
+}
+
+impl CorrelatedPair {
+    /// Вторая запись пары относительно `id`
+    pub fn other(&self, id: u64) -> u64 {
+        if id == self.request_id { self.response_id } else { self.request_id }
+    }
+}
+
+#[derive(Hash, PartialEq, Eq)]
+struct PairKey {
+    channel: String,
+    packet_id: i32,
+    key: Option<String>,
+}
+
+/// Связывает исходящий запрос со следующим входящим ответом с тем же каналом и ID
+/// (и тем же значением ключевого поля, если для канала задано правило).
+/// Возвращает пары по id обеих записей.
+pub fn correlate(
+    records: &[PacketRecord],
+    rules: &[CorrelationRule],
+    decode: &mut dyn FnMut(&PacketRecord) -> Option<DecodedStruct>,
+) -> HashMap<u64, CorrelatedPair> {
+    let mut pending: HashMap<PairKey, VecDeque<&PacketRecord>> = HashMap::new();
+    let mut out = HashMap::new();
+
+    let mut ordered: Vec<&PacketRecord> = records.iter().collect();
+    ordered.sort_by_key(|r| r.id);
+
+    for rec in ordered {
+        let Some(packet_id) = record_packet_id(rec) else { continue };
+        let channel = record_channel(rec);
+        let key = rules
+            .iter()
+            .find(|r| r.channel.eq_ignore_ascii_case(channel))
+            .and_then(|rule| key_value(rec, &rule.key_field, decode));
+        let pk = PairKey { channel: channel.to_string(), packet_id, key };
+
+        match rec.dir {
+            PacketDirection::Outbound => pending.entry(pk).or_default().push_back(rec),
+            PacketDirection::Inbound => {
+                // без ключа у одной из сторон — откатываемся на канал + ID
+                let req = match pending.get_mut(&pk).and_then(|q| q.pop_front()) {
+                    Some(r) => Some(r),
+                    None if pk.key.is_some() => pending
+                        .get_mut(&PairKey { key: None, ..pk })
+                        .and_then(|q| q.pop_front()),
+                    // ответ без ключа: самый ранний запрос с тем же каналом и ID, с любым ключом
+                    None => pending
+                        .iter_mut()
+                        .filter(|(k, q)| k.channel == pk.channel && k.packet_id == pk.packet_id && !q.is_empty())
+                        .min_by_key(|(_, q)| q.front().map(|r| r.id))
+                        .and_then(|(_, q)| q.pop_front()),
+                };
+                if let Some(req) = req {
+                    let pair = CorrelatedPair {
+                        request_id: req.id,
+                        response_id: rec.id,
+                        latency_ms: rec.ts_millis.saturating_sub(req.ts_millis),
+                    };
+                    out.insert(req.id, pair);
+                    out.insert(rec.id, pair);
+                }
+            }
+        }
+    }
+    out
+}
+
+fn key_value(
+    rec: &PacketRecord,
+    key_field: &str,
+    decode: &mut dyn FnMut(&PacketRecord) -> Option<DecodedStruct>,
+) -> Option<String> {
+    let path: Vec<String> = key_field.split('.').map(|s| s.trim().to_string()).collect();
+    let ds = decode(rec)?;
+    match lookup_field(&ds, &path)? {
+        DecodedValue::Text(t) => Some(t.clone()),
+        _ => None,
+    }
+}
@@ -0,0 +1,219 @@
+use crate::core::custom_payload::{DecodedStruct, DecodedValue};
+use std::ops::Range;
+
+/// Выше этого числа клеток (n * m) LCS не строим, середина сравнивается побайтно
+const LCS_MAX_CELLS: usize = 4_000_000;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldChange {
+    /// Поле есть только во втором пакете
+    Added(String),
+    /// Поле есть только в первом пакете
+    Removed(String),
+    Changed { old: String, new: String },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldDiff {
+    /// Путь в формате `lookup_field`: `a.b.0.c`
+    pub path: String,
+    pub change: FieldChange,
+}
+
+/// Сравнение расшифрованных деревьев поле за полем. Порядок — как в `a`, потом новые из `b`.
+pub fn diff_structs(a: &DecodedStruct, b: &DecodedStruct) -> Vec<FieldDiff> {
+    let mut out = Vec::new();
+    if a.name != b.name {
+        out.push(FieldDiff {
+            path: String::new(),
+            change: FieldChange::Changed { old: a.name.clone(), new: b.name.clone() },
+        });
+    }
+    diff_fields(a, b, "", &mut out);
+    out
+}
+
+fn join(prefix: &str, seg: &str) -> String {
+    if prefix.is_empty() { seg.to_string() } else { format!("{prefix}.{seg}") }
+}
+
+fn diff_fields(a: &DecodedStruct, b: &DecodedStruct, prefix: &str, out: &mut Vec<FieldDiff>) {
+    for fa in &a.fields {
+        let path = join(prefix, &fa.name);
+        match b.fields.iter().find(|fb| fb.name == fa.name) {
+            Some(fb) => diff_values(&fa.value, &fb.value, &path, out),
+            None => out.push(FieldDiff { path, change: FieldChange::Removed(value_summary(&fa.value)) }),
+        }
+    }
+    for fb in &b.fields {
+        if !a.fields.iter().any(|fa| fa.name == fb.name) {
+            out.push(FieldDiff {
+                path: join(prefix, &fb.name),
+                change: FieldChange::Added(value_summary(&fb.value)),
+            });
+        }
+    }
+}
+
+fn diff_values(a: &DecodedValue, b: &DecodedValue, path: &str, out: &mut Vec<FieldDiff>) {
+    match (a, b) {
+        (DecodedValue::Struct(sa), DecodedValue::Struct(sb)) => diff_fields(sa, sb, path, out),
+        (DecodedValue::List(la), DecodedValue::List(lb)) => {
+            for i in 0..la.len().max(lb.len()) {
+                let p = join(path, &i.to_string());
+                match (la.get(i), lb.get(i)) {
+                    (Some(x), Some(y)) => diff_values(x, y, &p, out),
+                    (Some(x), None) => out.push(FieldDiff { path: p, change: FieldChange::Removed(value_summary(x)) }),
+                    (None, Some(y)) => out.push(FieldDiff { path: p, change: FieldChange::Added(value_summary(y)) }),
+                    (None, None) => {}
+                }
+            }
+        }
+        (DecodedValue::Text(x), DecodedValue::Text(y)) if x == y => {}
+        (DecodedValue::Bytes(x), DecodedValue::Bytes(y)) if x == y => {}
+        (DecodedValue::Null, DecodedValue::Null) => {}
+        _ => out.push(FieldDiff {
+            path: path.to_string(),
+            change: FieldChange::Changed { old: value_summary(a), new: value_summary(b) },
+        }),
+    }
+}
+
+pub fn value_summary(v: &DecodedValue) -> String {
+    match v {
+        DecodedValue::Text(t) => t.clone(),
+        DecodedValue::Bytes(b) => {
+            let head: String = b.iter().take(16).map(|x| format!("{x:02X}")).collect();
+            if b.len() > 16 { format!("<{} bytes> {head}..", b.len()) } else { format!("<{} bytes> {head}", b.len()) }
+        }
+        DecodedValue::List(items) => format!("[{}]", items.len()),
+        DecodedValue::Struct(s) => format!("{} {{..}}", s.name),
+        DecodedValue::Null => "null".to_string(),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ByteOpKind {
+    Equal,
+    /// Байты `a` заменены байтами `b`
+    Replace,
+    /// Есть только в `b`
+    Insert,
+    /// Есть только в `a`
+    Delete,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ByteOp {
+    pub kind: ByteOpKind,
+    pub a: Range<usize>,
+    pub b: Range<usize>,
+}
+
+/// Выравнивание двух буферов по LCS: вставки и удаления не сдвигают остальное сравнение.
+/// Общие начало и конец отрезаются заранее, так что типичный пакет с парой изменённых полей дешёвый.
+pub fn diff_bytes(a: &[u8], b: &[u8]) -> Vec<ByteOp> {
+    let prefix = a.iter().zip(b).take_while(|(x, y)| x == y).count();
+    let suffix = a[prefix..]
+        .iter()
+        .rev()
+        .zip(b[prefix..].iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+    let (ma, mb) = (&a[prefix..a.len() - suffix], &b[prefix..b.len() - suffix]);
+
+    let mut ops = Vec::new();
+    push_op(&mut ops, ByteOpKind::Equal, 0..prefix, 0..prefix);
+    if ma.len().saturating_mul(mb.len()) <= LCS_MAX_CELLS {
+        lcs_ops(ma, mb, prefix, &mut ops);
+    } else {
+        positional_ops(ma, mb, prefix, &mut ops);
+    }
+    push_op(&mut ops, ByteOpKind::Equal, a.len() - suffix..a.len(), b.len() - suffix..b.len());
+    ops
+}
+
+fn lcs_ops(a: &[u8], b: &[u8], base: usize, ops: &mut Vec<ByteOp>) {
+    let (n, m) = (a.len(), b.len());
+    // dp[i][j] — LCS суффиксов a[i..], b[j..]
+    let mut dp = vec![0u32; (n + 1) * (m + 1)];
+    let at = |i: usize, j: usize| i * (m + 1) + j;
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            dp[at(i, j)] = if a[i] == b[j] {
+                dp[at(i + 1, j + 1)] + 1
+            } else {
+                dp[at(i + 1, j)].max(dp[at(i, j + 1)])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && a[i] == b[j] {
+            push_op(ops, ByteOpKind::Equal, base + i..base + i + 1, base + j..base + j + 1);
+            i += 1;
+            j += 1;
+        } else if j < m && (i == n || dp[at(i, j + 1)] >= dp[at(i + 1, j)]) {
+            push_op(ops, ByteOpKind::Insert, base + i..base + i, base + j..base + j + 1);
+            j += 1;
+        } else {
+            push_op(ops, ByteOpKind::Delete, base + i..base + i + 1, base + j..base + j);
+            i += 1;
+        }
+    }
+}
+
+fn positional_ops(a: &[u8], b: &[u8], base: usize, ops: &mut Vec<ByteOp>) {
+    let common = a.len().min(b.len());
+    for k in 0..common {
+        let kind = if a[k] == b[k] { ByteOpKind::Equal } else { ByteOpKind::Replace };
+        push_op(ops, kind, base + k..base + k + 1, base + k..base + k + 1);
+    }
+    if a.len() > common {
+        push_op(ops, ByteOpKind::Delete, base + common..base + a.len(), base + common..base + common);
+    }
+    if b.len() > common {
+        push_op(ops, ByteOpKind::Insert, base + a.len()..base + a.len(), base + common..base + b.len());
+    }
+}
+
+/// Склеивает соседние операции одного вида; удаление рядом со вставкой становится заменой.
+fn push_op(ops: &mut Vec<ByteOp>, kind: ByteOpKind, a: Range<usize>, b: Range<usize>) {
+    if a.is_empty() && b.is_empty() {
+        return;
+    }
+    if let Some(last) = ops.last_mut() {
+        let merged = match (last.kind, kind) {
+            (x, y) if x == y => Some(x),
+            (ByteOpKind::Equal, _) | (_, ByteOpKind::Equal) => None,
+            _ => Some(ByteOpKind::Replace),
+        };
+        if let Some(merged) = merged.filter(|_| last.a.end == a.start && last.b.end == b.start) {
+            last.kind = merged;
+            last.a.end = a.end;
+            last.b.end = b.end;
+            return;
+        }
+    }
+    ops.push(ByteOp { kind, a, b });
+}
+
+/// Разворачивает операции в пары байтов для вывода в две строки; `None` — пропуск.
+pub fn align_pairs(a: &[u8], b: &[u8], ops: &[ByteOp]) -> Vec<(Option<u8>, Option<u8>, ByteOpKind)> {
+    let mut out = Vec::new();
+    for op in ops {
+        let len = op.a.len().max(op.b.len());
+        for k in 0..len {
+            let x = (k < op.a.len()).then(|| a[op.a.start + k]);
+            let y = (k < op.b.len()).then(|| b[op.b.start + k]);
+            let kind = match (op.kind, x, y) {
+                (ByteOpKind::Replace, Some(_), None) => ByteOpKind::Delete,
+                (ByteOpKind::Replace, None, Some(_)) => ByteOpKind::Insert,
+                (k, _, _) => k,
+            };
+            out.push((x, y, kind));
+        }
+    }
+    out
+}
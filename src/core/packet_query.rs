@@ -0,0 +1,553 @@
+use crate::core::custom_payload::{DecodedStruct, DecodedValue};
+use crate::graphics::netlog::{PacketDetails, PacketDirection, PacketRecord};
+use regex::{Regex, RegexBuilder};
+use std::fmt;
+
+/// Ошибка разбора запроса фильтра; `pos` — смещение в символах от начала строки.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryError {
+    pub pos: usize,
+    pub message: String,
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "at {}: {}", self.pos, self.message)
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CmpOp {
+    /// `:` — подстрока без учёта регистра (для чисел — равенство)
+    Contains,
+    /// `=`
+    Eq,
+    /// `!=`
+    Ne,
+    /// `~` — регулярное выражение
+    Match,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+}
+
+#[derive(Debug, Clone)]
+pub enum QueryValue {
+    Text(String),
+    Regex(Regex),
+}
+
+#[derive(Debug, Clone)]
+pub enum Predicate {
+    /// Голое слово: старое поведение фильтра (имя, канал, группа, hex, теги).
+    /// Хранится уже в нижнем регистре.
+    Any(String),
+    Dir(PacketDirection),
+    Name(CmpOp, QueryValue),
+    Channel(CmpOp, QueryValue),
+    Group(CmpOp, QueryValue),
+    Tag(CmpOp, QueryValue),
+    Len(CmpOp, u64),
+    Hex(Vec<u8>),
+    /// `field.a.b op value` по расшифрованному дереву
+    Field(Vec<String>, CmpOp, QueryValue),
+}
+
+#[derive(Debug, Clone)]
+pub enum Query {
+    And(Box<Query>, Box<Query>),
+    Or(Box<Query>, Box<Query>),
+    Not(Box<Query>),
+    Pred(Predicate),
+}
+
+impl Query {
+    pub fn parse(input: &str) -> Result<Query, QueryError> {
+        let tokens = tokenize(input)?;
+        let mut parser = Parser { tokens, pos: 0, end: input.chars().count() };
+        let q = parser.parse_or()?;
+        if let Some(tok) = parser.peek() {
+            return Err(QueryError { pos: tok.pos, message: format!("unexpected {}", tok.kind) });
+        }
+        Ok(q)
+    }
+
+    /// `decode` вызывается лениво и только если в запросе есть `field.*`.
+    pub fn matches(
+        &self,
+        rec: &PacketRecord,
+        decode: &mut dyn FnMut(&PacketRecord) -> Option<DecodedStruct>,
+    ) -> bool {
+        let mut decoded: Option<Option<DecodedStruct>> = None;
+        self.eval(rec, &mut |r| decoded.get_or_insert_with(|| decode(r)).clone())
+    }
+
+    fn eval(
+        &self,
+        rec: &PacketRecord,
+        decode: &mut dyn FnMut(&PacketRecord) -> Option<DecodedStruct>,
+    ) -> bool {
+        match self {
+            Query::And(a, b) => a.eval(rec, decode) && b.eval(rec, decode),
+            Query::Or(a, b) => a.eval(rec, decode) || b.eval(rec, decode),
+            Query::Not(q) => !q.eval(rec, decode),
+            Query::Pred(p) => eval_predicate(p, rec, decode),
+        }
+    }
+}
+
+fn eval_predicate(
+    p: &Predicate,
+    rec: &PacketRecord,
+    decode: &mut dyn FnMut(&PacketRecord) -> Option<DecodedStruct>,
+) -> bool {
+    match p {
+        Predicate::Any(needle_lc) => {
+            contains_ci(&rec.name, needle_lc)
+                || contains_ci(record_channel(rec), needle_lc)
+                || contains_ci(rec.group.as_deref().unwrap_or(""), needle_lc)
+                || rec.tags.iter().any(|t| contains_ci(t, needle_lc))
+                || contains_ci(&head_hex(&rec.data, 64), needle_lc)
+        }
+        Predicate::Dir(d) => rec.dir == *d,
+        Predicate::Name(op, v) => compare_text(&rec.name, *op, v),
+        Predicate::Channel(op, v) => compare_text(record_channel(rec), *op, v),
+        Predicate::Group(op, v) => compare_text(rec.group.as_deref().unwrap_or(""), *op, v),
+        Predicate::Tag(op, v) => match op {
+            // `tag!=x` — ни один тег не равен x
+            CmpOp::Ne => !rec.tags.iter().any(|t| compare_text(t, CmpOp::Eq, v)),
+            _ => rec.tags.iter().any(|t| compare_text(t, *op, v)),
+        },
+        Predicate::Len(op, n) => compare_num(rec.len as f64, *op, *n as f64),
+        Predicate::Hex(pattern) => {
+            pattern.is_empty() || rec.data.windows(pattern.len()).any(|w| w == pattern.as_slice())
+        }
+        Predicate::Field(path, op, v) => {
+            let Some(ds) = decode(rec) else { return false; };
+            let Some(value) = lookup_field(&ds, path) else { return false; };
+            match value {
+                DecodedValue::Text(t) => compare_value(t, *op, v),
+                DecodedValue::Null => compare_value("null", *op, v),
+                DecodedValue::List(items) => compare_num(items.len() as f64, *op, value_as_num(v)),
+                DecodedValue::Bytes(b) => compare_num(b.len() as f64, *op, value_as_num(v)),
+                DecodedValue::Struct(s) => compare_text(&s.name, *op, v),
+            }
+        }
+    }
+}
+
+pub fn record_channel(rec: &PacketRecord) -> &str {
+    match &rec.details {
+        Some(PacketDetails::CustomPayload { channel, .. }) => channel.as_str(),
+        _ => "",
+    }
+}
+
+/// Путь вида `player.stats.0.kills`; числовой сегмент индексирует список.
+pub fn lookup_field<'a>(ds: &'a DecodedStruct, path: &[String]) -> Option<&'a DecodedValue> {
+    let (first, rest) = path.split_first()?;
+    let mut cur = &ds.fields.iter().find(|f| f.name.eq_ignore_ascii_case(first))?.value;
+    for seg in rest {
+        cur = match cur {
+            DecodedValue::Struct(s) => &s.fields.iter().find(|f| f.name.eq_ignore_ascii_case(seg))?.value,
+            DecodedValue::List(items) => items.get(seg.trim_start_matches('#').parse::<usize>().ok()?)?,
+            _ => return None,
+        };
+    }
+    Some(cur)
+}
+
+fn value_as_num(v: &QueryValue) -> f64 {
+    match v {
+        QueryValue::Text(t) => t.parse::<f64>().unwrap_or(f64::NAN),
+        QueryValue::Regex(_) => f64::NAN,
+    }
+}
+
+fn compare_value(actual: &str, op: CmpOp, v: &QueryValue) -> bool {
+    if let (Ok(a), QueryValue::Text(t)) = (actual.trim().parse::<f64>(), v) {
+        if let Ok(b) = t.parse::<f64>() {
+            return compare_num(a, op, b);
+        }
+    }
+    compare_text(actual, op, v)
+}
+
+fn compare_text(actual: &str, op: CmpOp, v: &QueryValue) -> bool {
+    match (op, v) {
+        (_, QueryValue::Regex(re)) => re.is_match(actual),
+        (CmpOp::Contains, QueryValue::Text(t)) => contains_ci(actual, &t.to_ascii_lowercase()),
+        (CmpOp::Eq, QueryValue::Text(t)) => actual.eq_ignore_ascii_case(t),
+        (CmpOp::Ne, QueryValue::Text(t)) => !actual.eq_ignore_ascii_case(t),
+        (CmpOp::Gt, QueryValue::Text(t)) => actual.to_ascii_lowercase() > t.to_ascii_lowercase(),
+        (CmpOp::Ge, QueryValue::Text(t)) => actual.to_ascii_lowercase() >= t.to_ascii_lowercase(),
+        (CmpOp::Lt, QueryValue::Text(t)) => actual.to_ascii_lowercase() < t.to_ascii_lowercase(),
+        (CmpOp::Le, QueryValue::Text(t)) => actual.to_ascii_lowercase() <= t.to_ascii_lowercase(),
+        (CmpOp::Match, QueryValue::Text(t)) => contains_ci(actual, &t.to_ascii_lowercase()),
+    }
+}
+
+fn compare_num(a: f64, op: CmpOp, b: f64) -> bool {
+    match op {
+        CmpOp::Contains | CmpOp::Eq | CmpOp::Match => a == b,
+        CmpOp::Ne => a != b,
+        CmpOp::Gt => a > b,
+        CmpOp::Ge => a >= b,
+        CmpOp::Lt => a < b,
+        CmpOp::Le => a <= b,
+    }
+}
+
+fn contains_ci(hay: &str, needle_lc: &str) -> bool {
+    if needle_lc.is_empty() {
+        return true;
+    }
+    let n = needle_lc.as_bytes();
+    let hb = hay.as_bytes();
+    n.len() <= hb.len() && hb.windows(n.len()).any(|w| w.eq_ignore_ascii_case(n))
+}
+
+fn head_hex(data: &[u8], n: usize) -> String {
+    const DIGITS: &[u8; 16] = b"0123456789ABCDEF";
+    let mut out = String::with_capacity(n.min(data.len()) * 2);
+    for b in data.iter().take(n) {
+        out.push(DIGITS[(b >> 4) as usize] as char);
+        out.push(DIGITS[(b & 0x0F) as usize] as char);
+    }
+    out
+}
+
+/// Разбирает `"0a 0b"`, `0A0B`, `0x0a,0x0b` в байты.
+pub fn parse_hex_bytes(s: &str) -> Result<Vec<u8>, String> {
+    let clean: String = s
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .map(|part| part.trim_start_matches("0x").trim_start_matches("0X"))
+        .collect();
+    if let Some(c) = clean.chars().find(|c| !c.is_ascii_hexdigit()) {
+        return Err(format!("invalid hex character '{}'", c));
+    }
+    if clean.len() % 2 != 0 {
+        return Err("odd number of hex digits".to_string());
+    }
+    (0..clean.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&clean[i..i + 2], 16)
+                .map_err(|_| format!("invalid hex byte '{}'", &clean[i..i + 2]))
+        })
+        .collect()
+}
+
+/* -------- лексер -------- */
+
+#[derive(Debug, Clone, PartialEq)]
+enum TokenKind {
+    Word(String),
+    Quoted(String),
+    Op(CmpOp),
+    LParen,
+    RParen,
+    And,
+    Or,
+    Not,
+}
+
+impl fmt::Display for TokenKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TokenKind::Word(w) => write!(f, "'{w}'"),
+            TokenKind::Quoted(q) => write!(f, "\"{q}\""),
+            TokenKind::Op(op) => write!(f, "operator {}", op_str(*op)),
+            TokenKind::LParen => write!(f, "'('"),
+            TokenKind::RParen => write!(f, "')'"),
+            TokenKind::And => write!(f, "'and'"),
+            TokenKind::Or => write!(f, "'or'"),
+            TokenKind::Not => write!(f, "'not'"),
+        }
+    }
+}
+
+fn op_str(op: CmpOp) -> &'static str {
+    match op {
+        CmpOp::Contains => ":",
+        CmpOp::Eq => "=",
+        CmpOp::Ne => "!=",
+        CmpOp::Match => "~",
+        CmpOp::Gt => ">",
+        CmpOp::Ge => ">=",
+        CmpOp::Lt => "<",
+        CmpOp::Le => "<=",
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    kind: TokenKind,
+    pos: usize,
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, QueryError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut out = Vec::new();
+    let mut i = 0usize;
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+        match c {
+            c if c.is_whitespace() => {
+                i += 1;
+            }
+            '(' => {
+                out.push(Token { kind: TokenKind::LParen, pos: start });
+                i += 1;
+            }
+            ')' => {
+                out.push(Token { kind: TokenKind::RParen, pos: start });
+                i += 1;
+            }
+            '"' | '\'' => {
+                let quote = c;
+                i += 1;
+                let mut s = String::new();
+                loop {
+                    match chars.get(i) {
+                        None => {
+                            return Err(QueryError { pos: start, message: "unterminated string".to_string() });
+                        }
+                        Some('\\') if chars.get(i + 1).is_some() => {
+                            s.push(chars[i + 1]);
+                            i += 2;
+                        }
+                        Some(ch) if *ch == quote => {
+                            i += 1;
+                            break;
+                        }
+                        Some(ch) => {
+                            s.push(*ch);
+                            i += 1;
+                        }
+                    }
+                }
+                out.push(Token { kind: TokenKind::Quoted(s), pos: start });
+            }
+            ':' | '~' | '=' => {
+                let op = match c {
+                    ':' => CmpOp::Contains,
+                    '~' => CmpOp::Match,
+                    _ => CmpOp::Eq,
+                };
+                i += if c == '=' && chars.get(i + 1) == Some(&'=') { 2 } else { 1 };
+                out.push(Token { kind: TokenKind::Op(op), pos: start });
+            }
+            '>' | '<' => {
+                let eq = chars.get(i + 1) == Some(&'=');
+                let op = match (c, eq) {
+                    ('>', false) => CmpOp::Gt,
+                    ('>', true) => CmpOp::Ge,
+                    ('<', false) => CmpOp::Lt,
+                    _ => CmpOp::Le,
+                };
+                i += if eq { 2 } else { 1 };
+                out.push(Token { kind: TokenKind::Op(op), pos: start });
+            }
+            '!' => {
+                if chars.get(i + 1) == Some(&'=') {
+                    out.push(Token { kind: TokenKind::Op(CmpOp::Ne), pos: start });
+                    i += 2;
+                } else {
+                    out.push(Token { kind: TokenKind::Not, pos: start });
+                    i += 1;
+                }
+            }
+            '&' if chars.get(i + 1) == Some(&'&') => {
+                out.push(Token { kind: TokenKind::And, pos: start });
+                i += 2;
+            }
+            '|' if chars.get(i + 1) == Some(&'|') => {
+                out.push(Token { kind: TokenKind::Or, pos: start });
+                i += 2;
+            }
+            _ => {
+                let mut s = String::new();
+                while let Some(&ch) = chars.get(i) {
+                    if ch.is_whitespace() || matches!(ch, '(' | ')' | '"' | ':' | '~' | '=' | '>' | '<' | '!') {
+                        break;
+                    }
+                    s.push(ch);
+                    i += 1;
+                }
+                let kind = match s.to_ascii_lowercase().as_str() {
+                    "and" => TokenKind::And,
+                    "or" => TokenKind::Or,
+                    "not" => TokenKind::Not,
+                    _ => TokenKind::Word(s),
+                };
+                out.push(Token { kind, pos: start });
+            }
+        }
+    }
+    Ok(out)
+}
+
+/* -------- парсер -------- */
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    end: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let t = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        t
+    }
+
+    fn err_here(&self, message: impl Into<String>) -> QueryError {
+        let pos = self.peek().map(|t| t.pos).unwrap_or(self.end);
+        QueryError { pos, message: message.into() }
+    }
+
+    // or_expr := and_expr ( "or" and_expr )*
+    fn parse_or(&mut self) -> Result<Query, QueryError> {
+        let mut lhs = self.parse_and()?;
+        while matches!(self.peek().map(|t| &t.kind), Some(TokenKind::Or)) {
+            self.next();
+            let rhs = self.parse_and()?;
+            lhs = Query::Or(Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    // and_expr := unary ( ["and"] unary )*   — соседние термы объединяются через AND
+    fn parse_and(&mut self) -> Result<Query, QueryError> {
+        let mut lhs = self.parse_unary()?;
+        loop {
+            match self.peek().map(|t| &t.kind) {
+                Some(TokenKind::And) => {
+                    self.next();
+                }
+                Some(TokenKind::Word(_) | TokenKind::Quoted(_) | TokenKind::Not | TokenKind::LParen) => {}
+                _ => break,
+            }
+            let rhs = self.parse_unary()?;
+            lhs = Query::And(Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_unary(&mut self) -> Result<Query, QueryError> {
+        match self.peek().map(|t| t.kind.clone()) {
+            Some(TokenKind::Not) => {
+                self.next();
+                Ok(Query::Not(Box::new(self.parse_unary()?)))
+            }
+            Some(TokenKind::LParen) => {
+                let open = self.next().map(|t| t.pos).unwrap_or(0);
+                let inner = self.parse_or()?;
+                match self.next() {
+                    Some(Token { kind: TokenKind::RParen, .. }) => Ok(inner),
+                    _ => Err(QueryError { pos: open, message: "unclosed '('".to_string() }),
+                }
+            }
+            Some(_) => self.parse_term(),
+            None => Err(self.err_here("expected expression")),
+        }
+    }
+
+    fn parse_term(&mut self) -> Result<Query, QueryError> {
+        let tok = self.next().ok_or_else(|| self.err_here("expected expression"))?;
+        let key = match tok.kind {
+            TokenKind::Word(w) => w,
+            TokenKind::Quoted(q) => return Ok(Query::Pred(Predicate::Any(q.to_ascii_lowercase()))),
+            other => return Err(QueryError { pos: tok.pos, message: format!("unexpected {other}") }),
+        };
+
+        let op = match self.peek() {
+            Some(Token { kind: TokenKind::Op(op), .. }) => *op,
+            _ => return Ok(Query::Pred(Predicate::Any(key.to_ascii_lowercase()))),
+        };
+        self.next();
+
+        let value_tok = self
+            .next()
+            .ok_or_else(|| QueryError { pos: self.end, message: format!("expected value after '{key}{}'", op_str(op)) })?;
+        let raw = match value_tok.kind {
+            TokenKind::Word(w) | TokenKind::Quoted(w) => w,
+            other => {
+                return Err(QueryError { pos: value_tok.pos, message: format!("expected value, found {other}") });
+            }
+        };
+
+        Ok(Query::Pred(build_predicate(&key, tok.pos, op, raw, value_tok.pos)?))
+    }
+}
+
+fn build_predicate(key: &str, key_pos: usize, op: CmpOp, raw: String, value_pos: usize) -> Result<Predicate, QueryError> {
+    let at_key = |message: String| QueryError { pos: key_pos, message };
+    let at_value = |message: String| QueryError { pos: value_pos, message };
+    let value = || -> Result<QueryValue, QueryError> {
+        if op == CmpOp::Match {
+            RegexBuilder::new(&raw)
+                .case_insensitive(true)
+                .build()
+                .map(QueryValue::Regex)
+                .map_err(|e| {
+                    let detail = e.to_string();
+                    let detail = detail.lines().last().unwrap_or_default().trim().trim_start_matches("error: ");
+                    at_value(format!("bad regex: {detail}"))
+                })
+        } else {
+            Ok(QueryValue::Text(raw.clone()))
+        }
+    };
+
+    let key_lc = key.to_ascii_lowercase();
+    if let Some(path) = key_lc.strip_prefix("field.") {
+        let path: Vec<String> = path.split('.').filter(|s| !s.is_empty()).map(str::to_string).collect();
+        if path.is_empty() {
+            return Err(at_key("empty field path".to_string()));
+        }
+        return Ok(Predicate::Field(path, op, value()?));
+    }
+
+    match key_lc.as_str() {
+        "dir" | "direction" => {
+            if !matches!(op, CmpOp::Contains | CmpOp::Eq) {
+                return Err(at_key("dir supports only ':' or '='".to_string()));
+            }
+            match raw.to_ascii_lowercase().as_str() {
+                "in" | "inbound" | "s2c" | "server" => Ok(Predicate::Dir(PacketDirection::Inbound)),
+                "out" | "outbound" | "c2s" | "client" => Ok(Predicate::Dir(PacketDirection::Outbound)),
+                other => Err(at_value(format!("unknown direction '{other}', use in/out"))),
+            }
+        }
+        "name" => Ok(Predicate::Name(op, value()?)),
+        "channel" | "ch" => Ok(Predicate::Channel(op, value()?)),
+        "group" => Ok(Predicate::Group(op, value()?)),
+        "tag" | "tags" => Ok(Predicate::Tag(op, value()?)),
+        "len" | "size" => {
+            if op == CmpOp::Match {
+                return Err(at_key("len does not support '~'".to_string()));
+            }
+            raw.parse::<u64>()
+                .map(|n| Predicate::Len(op, n))
+                .map_err(|_| at_value(format!("len expects a number, got '{raw}'")))
+        }
+        "hex" => {
+            if !matches!(op, CmpOp::Contains | CmpOp::Eq) {
+                return Err(at_key("hex supports only ':'".to_string()));
+            }
+            parse_hex_bytes(&raw).map(Predicate::Hex).map_err(at_value)
+        }
+        _ => Err(at_key(format!("unknown key '{key}'"))),
+    }
+}
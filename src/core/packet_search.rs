@@ -0,0 +1,166 @@
+use crate::core::custom_payload::{DecodedStruct, DecodedValue};
+use crate::graphics::netlog::PacketRecord;
+use regex::bytes::Regex as BytesRegex;
+use regex::{Regex, RegexBuilder};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SearchMode {
+    /// `0A ?? FF` — байты с подстановочными `??`
+    #[default]
+    Bytes,
+    /// Регулярка по текстовым полям расшифрованного пакета
+    FieldRegex,
+    /// Регулярка по сырому payload (байты как текст)
+    RawRegex,
+}
+
+impl SearchMode {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SearchMode::Bytes => "Bytes",
+            SearchMode::FieldRegex => "Regex (fields)",
+            SearchMode::RawRegex => "Regex (raw)",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum SearchPattern {
+    Bytes(Vec<Option<u8>>),
+    FieldRegex(Regex),
+    RawRegex(BytesRegex),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum HitLocation {
+    Bytes { offset: usize, len: usize },
+    Field { path: String },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchHit {
+    pub record_id: u64,
+    pub location: HitLocation,
+}
+
+impl SearchPattern {
+    pub fn compile(mode: SearchMode, text: &str) -> Result<SearchPattern, String> {
+        let text = text.trim();
+        if text.is_empty() {
+            return Err("empty pattern".to_string());
+        }
+        match mode {
+            SearchMode::Bytes => parse_byte_pattern(text).map(SearchPattern::Bytes),
+            SearchMode::FieldRegex => RegexBuilder::new(text)
+                .case_insensitive(true)
+                .build()
+                .map(SearchPattern::FieldRegex)
+                .map_err(|e| e.to_string()),
+            SearchMode::RawRegex => regex::bytes::RegexBuilder::new(text)
+                .case_insensitive(true)
+                .unicode(false)
+                .build()
+                .map(SearchPattern::RawRegex)
+                .map_err(|e| e.to_string()),
+        }
+    }
+
+    /// Все совпадения в записи, по порядку. Для `FieldRegex` нужен декодер.
+    pub fn find_all(
+        &self,
+        rec: &PacketRecord,
+        decode: &mut dyn FnMut(&PacketRecord) -> Option<DecodedStruct>,
+    ) -> Vec<HitLocation> {
+        match self {
+            SearchPattern::Bytes(pattern) => find_byte_pattern(&rec.data, pattern)
+                .into_iter()
+                .map(|offset| HitLocation::Bytes { offset, len: pattern.len() })
+                .collect(),
+            SearchPattern::RawRegex(re) => re
+                .find_iter(&rec.data)
+                .filter(|m| !m.is_empty())
+                .map(|m| HitLocation::Bytes { offset: m.start(), len: m.len() })
+                .collect(),
+            SearchPattern::FieldRegex(re) => {
+                let mut out = Vec::new();
+                if let Some(ds) = decode(rec) {
+                    collect_field_hits(&ds, "", re, &mut out);
+                }
+                out
+            }
+        }
+    }
+}
+
+/// `0A ?? FF`, `0a??ff`, `0A ? FF` — пробелы необязательны, `?`/`??` — любой байт.
+pub fn parse_byte_pattern(text: &str) -> Result<Vec<Option<u8>>, String> {
+    let mut out = Vec::new();
+    for token in text.split(|c: char| c.is_whitespace() || c == ',') {
+        let token = token.trim_start_matches("0x").trim_start_matches("0X");
+        if token.is_empty() {
+            continue;
+        }
+        if token == "?" {
+            out.push(None);
+            continue;
+        }
+        if token.len() % 2 != 0 {
+            return Err(format!("'{token}': odd number of hex digits"));
+        }
+        for i in (0..token.len()).step_by(2) {
+            let pair = token.get(i..i + 2).ok_or_else(|| format!("'{token}': invalid characters"))?;
+            if pair == "??" {
+                out.push(None);
+            } else {
+                // from_str_radix принимает ведущий `+`, поэтому цифры проверяем сами
+                if !pair.chars().all(|c| c.is_ascii_hexdigit()) {
+                    return Err(format!("'{pair}' is not a hex byte"));
+                }
+                let b = u8::from_str_radix(pair, 16).map_err(|_| format!("'{pair}' is not a hex byte"))?;
+                out.push(Some(b));
+            }
+        }
+    }
+    if out.is_empty() {
+        return Err("empty pattern".to_string());
+    }
+    if out.iter().all(Option::is_none) {
+        return Err("pattern needs at least one concrete byte".to_string());
+    }
+    Ok(out)
+}
+
+pub fn find_byte_pattern(data: &[u8], pattern: &[Option<u8>]) -> Vec<usize> {
+    if pattern.is_empty() || pattern.len() > data.len() {
+        return Vec::new();
+    }
+    data.windows(pattern.len())
+        .enumerate()
+        .filter(|(_, w)| w.iter().zip(pattern).all(|(b, p)| p.is_none_or(|p| p == *b)))
+        .map(|(i, _)| i)
+        .collect()
+}
+
+fn collect_field_hits(ds: &DecodedStruct, prefix: &str, re: &Regex, out: &mut Vec<HitLocation>) {
+    for f in &ds.fields {
+        let path = if prefix.is_empty() { f.name.clone() } else { format!("{prefix}.{}", f.name) };
+        collect_value_hits(&f.value, &path, re, out);
+    }
+}
+
+fn collect_value_hits(value: &DecodedValue, path: &str, re: &Regex, out: &mut Vec<HitLocation>) {
+    match value {
+        DecodedValue::Text(t) => {
+            if re.is_match(t) {
+                out.push(HitLocation::Field { path: path.to_string() });
+            }
+        }
+        DecodedValue::Struct(s) => collect_field_hits(s, path, re, out),
+        DecodedValue::List(items) => {
+            for (i, it) in items.iter().enumerate() {
+                collect_value_hits(it, &format!("{path}.{i}"), re, out);
+            }
+        }
+        DecodedValue::Bytes(_) | DecodedValue::Null => {}
+    }
+}
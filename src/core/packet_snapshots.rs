@@ -0,0 +1,159 @@
+use crate::graphics::netlog::PacketRecord;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, File},
+    io::{BufReader, BufWriter},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Каталог для файлов снапшотов, рядом с sessions.json
+const SNAPSHOT_DIR: &str = "packet_snapshots";
+const SNAPSHOT_FILE_VERSION: u32 = 1;
+
+/// Замороженный набор записей. Живёт отдельно от стора, вытеснение его не трогает.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PacketSnapshot {
+    pub name: String,
+    pub created_at: u64,
+    /// Фильтр, с которым снимали, для справки
+    pub filter: String,
+    pub records: Vec<PacketRecord>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SnapshotFile {
+    version: u32,
+    snapshots: Vec<PacketSnapshot>,
+}
+
+#[derive(Default)]
+pub struct SnapshotManager {
+    snapshots: Vec<PacketSnapshot>,
+}
+
+impl SnapshotManager {
+    pub fn snapshots(&self) -> &[PacketSnapshot] {
+        &self.snapshots
+    }
+
+    pub fn get(&self, name: &str) -> Option<&PacketSnapshot> {
+        self.snapshots.iter().find(|s| s.name == name)
+    }
+
+    /// Записи сортируются по id, чтобы порядок не зависел от сортировки таблицы.
+    pub fn add(&mut self, name: &str, filter: &str, mut records: Vec<PacketRecord>) -> Result<(), String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("Snapshot name is empty".to_string());
+        }
+        if self.get(name).is_some() {
+            return Err(format!("Snapshot '{name}' already exists"));
+        }
+        records.sort_by_key(|r| r.id);
+        self.snapshots.push(PacketSnapshot {
+            name: name.to_string(),
+            created_at: now_millis(),
+            filter: filter.to_string(),
+            records,
+        });
+        Ok(())
+    }
+
+    pub fn remove(&mut self, name: &str) {
+        self.snapshots.retain(|s| s.name != name);
+    }
+
+    pub fn rename(&mut self, old: &str, new: &str) -> Result<(), String> {
+        let new = new.trim();
+        if new.is_empty() {
+            return Err("Snapshot name is empty".to_string());
+        }
+        if new != old && self.get(new).is_some() {
+            return Err(format!("Snapshot '{new}' already exists"));
+        }
+        let snap = self
+            .snapshots
+            .iter_mut()
+            .find(|s| s.name == old)
+            .ok_or_else(|| format!("Snapshot '{old}' not found"))?;
+        snap.name = new.to_string();
+        Ok(())
+    }
+
+    /// Пишет выбранные снапшоты (или все при `None`) в `packet_snapshots/<file>`.
+    pub fn export_to_file(&self, names: Option<&[String]>, file: &str) -> Result<PathBuf, String> {
+        let snapshots: Vec<PacketSnapshot> = self
+            .snapshots
+            .iter()
+            .filter(|s| names.is_none_or(|n| n.contains(&s.name)))
+            .cloned()
+            .collect();
+        if snapshots.is_empty() {
+            return Err("Nothing to export".to_string());
+        }
+
+        let path = snapshot_path(file);
+        fs::create_dir_all(SNAPSHOT_DIR).map_err(|e| format!("Failed to create {SNAPSHOT_DIR}: {e}"))?;
+        let out = File::create(&path).map_err(|e| format!("Failed to create {}: {e}", path.display()))?;
+        let data = SnapshotFile { version: SNAPSHOT_FILE_VERSION, snapshots };
+        serde_json::to_writer_pretty(BufWriter::new(out), &data)
+            .map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+        Ok(path)
+    }
+
+    /// Имя без каталога ищется в `packet_snapshots/`. Совпадающие имена получают суффикс.
+    pub fn import_from_file(&mut self, file: &str) -> Result<usize, String> {
+        let file = file.trim();
+        let path = if Path::new(file).exists() { PathBuf::from(file) } else { snapshot_path(file) };
+        let input = File::open(&path).map_err(|e| format!("Failed to open {}: {e}", path.display()))?;
+        let data: SnapshotFile = serde_json::from_reader(BufReader::new(input))
+            .map_err(|e| format!("Failed to parse {}: {e}", path.display()))?;
+        if data.version > SNAPSHOT_FILE_VERSION {
+            return Err(format!(
+                "Snapshot file version {} is newer than supported ({})",
+                data.version, SNAPSHOT_FILE_VERSION
+            ));
+        }
+
+        let count = data.snapshots.len();
+        for mut snap in data.snapshots {
+            snap.name = self.free_name(&snap.name);
+            self.snapshots.push(snap);
+        }
+        Ok(count)
+    }
+
+    fn free_name(&self, name: &str) -> String {
+        if self.get(name).is_none() {
+            return name.to_string();
+        }
+        (2..)
+            .map(|i| format!("{name} ({i})"))
+            .find(|n| self.get(n).is_none())
+            .unwrap_or_else(|| name.to_string())
+    }
+}
+
+/// Путь файла снапшотов; из имени выкидывается всё, кроме букв, цифр, `-`, `_` и `.`
+pub fn snapshot_path(name: &str) -> PathBuf {
+    let mut file: String = name
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        .collect();
+    file = file.trim_matches('.').to_string();
+    if file.is_empty() {
+        file = "snapshots".to_string();
+    }
+    if !file.contains('.') {
+        file.push_str(".json");
+    }
+    PathBuf::from(SNAPSHOT_DIR).join(file)
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
@@ -0,0 +1,210 @@
+use crate::async_runtime::ASYNC_RUNTIME;
+use crate::core::custom_payload::decode_record;
+use crate::core::packet_query::Query;
+use crate::core::state::GlobalState;
+use crate::graphics::netlog::PacketRecord;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::VecDeque,
+    fs::{self, OpenOptions},
+    io::Write,
+    path::PathBuf,
+    sync::atomic::{AtomicBool, Ordering},
+};
+use tokio::sync::mpsc;
+
+/// Каталог для файлов действия «дописать в экспорт», рядом с sessions.json
+const EXPORT_DIR: &str = "packet_exports";
+/// Сколько несработавших в UI событий держим, если вкладку долго не открывают
+const MAX_PENDING_EVENTS: usize = 256;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PacketTrigger {
+    pub name: String,
+    /// Запрос в синтаксисе фильтра
+    pub query: String,
+    pub enabled: bool,
+    pub highlight: Option<[u8; 3]>,
+    pub pin: bool,
+    pub notify: bool,
+    pub pause: bool,
+    /// Имя файла в `packet_exports/`, куда дописывается запись (JSON Lines)
+    pub export_file: Option<String>,
+    /// Счётчик срабатываний за время работы, в настройки не сохраняется
+    #[serde(skip)]
+    pub hits: u64,
+}
+
+#[derive(Clone, Debug)]
+pub enum TriggerEvent {
+    Notify { trigger: String, summary: String },
+    Paused { trigger: String },
+    ExportFailed { trigger: String, error: String },
+}
+
+/// Триггеры проверяются в потоке хука при записи пакета, UI только забирает события.
+#[derive(Default)]
+pub struct TriggerEngine {
+    triggers: Vec<(PacketTrigger, Query)>,
+    events: VecDeque<TriggerEvent>,
+}
+
+impl TriggerEngine {
+    pub fn triggers(&self) -> impl Iterator<Item = &PacketTrigger> {
+        self.triggers.iter().map(|(t, _)| t)
+    }
+
+    pub fn add(&mut self, trigger: PacketTrigger) -> Result<(), String> {
+        let query = Query::parse(&trigger.query).map_err(|e| format!("Invalid trigger query: {e}"))?;
+        self.triggers.push((trigger, query));
+        Ok(())
+    }
+
+    pub fn remove(&mut self, idx: usize) {
+        if idx < self.triggers.len() {
+            self.triggers.remove(idx);
+        }
+    }
+
+    pub fn set_enabled(&mut self, idx: usize, enabled: bool) {
+        if let Some((t, _)) = self.triggers.get_mut(idx) {
+            t.enabled = enabled;
+        }
+    }
+
+    pub fn reset_counters(&mut self) {
+        for (t, _) in &mut self.triggers {
+            t.hits = 0;
+        }
+    }
+
+    pub fn drain_events(&mut self) -> Vec<TriggerEvent> {
+        self.events.drain(..).collect()
+    }
+
+    /// Применяет действия сработавших триггеров к записи до того, как она попадёт в стор.
+    /// Возвращает true, если захват нужно поставить на паузу.
+    pub fn apply(&mut self, rec: &mut PacketRecord) -> bool {
+        let mut pause = false;
+        let mut decoded = None;
+        for (trig, query) in &mut self.triggers {
+            if !trig.enabled {
+                continue;
+            }
+            // расшифровываем только если запросу нужны поля, и не больше одного раза
+            let hit = query.matches(rec, &mut |r| decoded.get_or_insert_with(|| decode_record(r)).clone());
+            if !hit {
+                continue;
+            }
+
+            trig.hits += 1;
+            if trig.pin {
+                rec.pinned = true;
+            }
+            if rec.color.is_none() {
+                rec.color = trig.highlight;
+            }
+            if trig.notify {
+                let short = rec.name.rsplit('.').next().unwrap_or(&rec.name);
+                Self::push_event(
+                    &mut self.events,
+                    TriggerEvent::Notify {
+                        trigger: trig.name.clone(),
+                        summary: format!("#{} {short} ({} bytes)", rec.id, rec.len),
+                    },
+                );
+            }
+            if let Some(file) = &trig.export_file {
+                match serde_json::to_string(rec) {
+                    Ok(line) => queue_export(ExportLine { trigger: trig.name.clone(), file: file.clone(), line }),
+                    Err(e) => Self::push_event(
+                        &mut self.events,
+                        TriggerEvent::ExportFailed { trigger: trig.name.clone(), error: format!("Failed to serialize packet: {e}") },
+                    ),
+                }
+            }
+            if trig.pause && !pause {
+                pause = true;
+                Self::push_event(&mut self.events, TriggerEvent::Paused { trigger: trig.name.clone() });
+            }
+        }
+        pause
+    }
+
+    fn push_event(events: &mut VecDeque<TriggerEvent>, event: TriggerEvent) {
+        if events.len() >= MAX_PENDING_EVENTS {
+            events.pop_front();
+        }
+        events.push_back(event);
+    }
+}
+
+/// Путь файла экспорта; из имени выкидывается всё, кроме букв, цифр, `-`, `_` и `.`
+pub fn export_path(name: &str) -> PathBuf {
+    let mut file: String = name
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        .collect();
+    file = file.trim_matches('.').to_string();
+    if file.is_empty() {
+        file = "trigger".to_string();
+    }
+    if !file.contains('.') {
+        file.push_str(".jsonl");
+    }
+    PathBuf::from(EXPORT_DIR).join(file)
+}
+
+/// Строка для файла экспорта; пишется в фоне, чтобы поток netty не ждал диск
+struct ExportLine {
+    trigger: String,
+    file: String,
+    line: String,
+}
+
+static EXPORT_QUEUE: Lazy<(mpsc::UnboundedSender<ExportLine>, Mutex<mpsc::UnboundedReceiver<ExportLine>>)> =
+    Lazy::new(|| {
+        let (tx, rx) = mpsc::unbounded_channel();
+        (tx, Mutex::new(rx))
+    });
+/// Разборщик очереди уже запущен
+static EXPORT_DRAINING: AtomicBool = AtomicBool::new(false);
+
+fn queue_export(job: ExportLine) {
+    let _ = EXPORT_QUEUE.0.send(job);
+    if !EXPORT_DRAINING.swap(true, Ordering::AcqRel) {
+        ASYNC_RUNTIME.spawn_blocking(drain_exports);
+    }
+}
+
+fn drain_exports() {
+    loop {
+        loop {
+            let Ok(job) = EXPORT_QUEUE.1.lock().try_recv() else { break };
+            if let Err(error) = append_export(&job.file, &job.line) {
+                if let Some(engine) = GlobalState::instance().get_packet_triggers().get() {
+                    let mut engine = engine.lock();
+                    TriggerEngine::push_event(&mut engine.events, TriggerEvent::ExportFailed { trigger: job.trigger, error });
+                }
+            }
+        }
+        EXPORT_DRAINING.store(false, Ordering::Release);
+        // строка могла прийти между пустой очередью и сбросом флага
+        if EXPORT_QUEUE.1.lock().is_empty() || EXPORT_DRAINING.swap(true, Ordering::AcqRel) {
+            return;
+        }
+    }
+}
+
+fn append_export(name: &str, line: &str) -> Result<(), String> {
+    let path = export_path(name);
+    fs::create_dir_all(EXPORT_DIR).map_err(|e| format!("Failed to create {EXPORT_DIR}: {e}"))?;
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .and_then(|mut f| writeln!(f, "{line}"))
+        .map_err(|e| format!("Failed to append to {}: {e}", path.display()))
+}
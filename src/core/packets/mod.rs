@@ -13,6 +13,17 @@ pub enum Bound {
     Server,
 }
 
+/// VarInt-поле в декларации `mod_packets!` (обычные i32 читаются как 4 байта BE)
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct VarInt(pub i32);
+
+/// Список любых полей, в том числе пакетов-структур: u32 BE длина, затем элементы.
+/// `Vec<u8>` и `Vec<String>` остаются отдельными типами со своим вводом в конструкторе.
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct List<T>(pub Vec<T>);
+
 #[macro_export]
 macro_rules! bound_from_ident {
     (C) => { $crate::core::packets::Bound::Client };
@@ -64,12 +75,82 @@ macro_rules! mod_packets {
                     pub fn parse_from_reader(reader: &mut ModPacketReader) -> io::Result<Self> {
                         Ok(Self { $( $field_name: reader.read::<$field_type>()?, )* })
                     }
+
+                    pub fn decoded(&self) -> DecodedStruct {
+                        DecodedStruct {
+                            name: stringify!($packet_name).to_string(),
+                            fields: vec![
+                                $( DecodedField {
+                                    name: stringify!($field_name).to_string(),
+                                    value: (&self.$field_name).to_decoded_value(),
+                                }, )*
+                            ],
+                        }
+                    }
+                }
+
+                // чтение/запись без VarInt-дискриминатора: так пакет вкладывается полем в другой
+                impl $crate::core::packets::reader::ModReadable for $packet_name {
+                    fn read_from(reader: &mut ModPacketReader) -> io::Result<Self> {
+                        Self::parse_from_reader(reader)
+                    }
+                }
+
+                impl $crate::core::packets::writer::ModWritable for $packet_name {
+                    #[allow(unused_variables)]
+                    fn write_to(&self, writer: &mut ModPacketWriter) {
+                        $( writer.write(&self.$field_name); )*
+                    }
+                }
+
+                impl ToDecodedValue for $packet_name {
+                    fn to_decoded_value(&self) -> $crate::core::custom_payload::DecodedValue {
+                        $crate::core::custom_payload::DecodedValue::Struct(self.decoded())
+                    }
                 }
 
                 // encode генерим только для Client-bound (C)
                 $crate::mod_packets!(@gen_encode_impl $packet_name, [$($field_name : $field_type),*], $bound);
+
+                impl $crate::core::packet_builder::BuildField for $packet_name {
+                    fn kind() -> $crate::core::packet_builder::FieldKind {
+                        $crate::core::packet_builder::FieldKind::Struct(vec![
+                            $( $crate::core::packet_builder::FieldSpec {
+                                name: stringify!($field_name),
+                                kind: <$field_type as $crate::core::packet_builder::BuildField>::kind(),
+                            }, )*
+                        ])
+                    }
+
+                    #[allow(unused_variables)]
+                    fn from_input(
+                        input: &$crate::core::packet_builder::FieldInput,
+                        path: &str,
+                        errors: &mut Vec<$crate::core::packet_builder::FieldError>,
+                    ) -> Option<Self> {
+                        // сначала все поля, чтобы ошибки собрались разом, а не до первой
+                        $( let $field_name = $crate::core::packet_builder::struct_field::<$field_type>(
+                            input, path, stringify!($field_name), errors,
+                        ); )*
+                        Some(Self { $( $field_name: $field_name?, )* })
+                    }
+                }
             )*
 
+            /// Пакеты канала для конструктора
+            pub fn builder_packets() -> Vec<$crate::core::packet_builder::BuilderPacket> {
+                vec![
+                    $( $crate::core::packet_builder::BuilderPacket {
+                        channel: CHANNEL,
+                        name: stringify!($packet_name),
+                        id: <$packet_name>::PACKET_ID,
+                        bound: <$packet_name>::PACKET_BOUND,
+                        fields: <$packet_name as $crate::core::packet_builder::BuildField>::kind(),
+                        encode: $crate::mod_packets!(@builder_encode $packet_name, $bound),
+                    }, )*
+                ]
+            }
+
             /// Парсер payload -> enum Packet (может не использоваться в проекте)
             #[allow(dead_code)]
             pub fn parse_packet(payload: &[u8]) -> io::Result<Packet> {
@@ -104,16 +185,7 @@ macro_rules! mod_packets {
                            && bound == <$packet_name>::PACKET_BOUND =>
                         {
                             let pkt = $packet_name::parse_from_reader(&mut reader).ok()?;
-                            let fields = vec![
-                                $( DecodedField {
-                                    name: stringify!($field_name).to_string(),
-                                    value: (&pkt.$field_name).to_decoded_value(),
-                                }, )*
-                            ];
-                            Some(DecodedStruct {
-                                name: stringify!($packet_name).to_string(),
-                                fields,
-                            })
+                            Some(pkt.decoded())
                         }
                     ),*
                     _ => None
@@ -128,12 +200,20 @@ macro_rules! mod_packets {
             pub fn encode(&self) -> Vec<u8> {
                 let mut writer = ModPacketWriter::new();
                 writer.write_varint(Self::PACKET_ID);
-                $( writer.write(&self.$field_name); )*
+                writer.write(self);
                 writer.into_bytes()
             }
         }
     };
     (@gen_encode_impl $packet_name:ident, [$($field_name:ident : $field_ty:ty),*], S) => {};
+
+    (@builder_encode $packet_name:ident, C) => {
+        Some(|input, errors| {
+            <$packet_name as $crate::core::packet_builder::BuildField>::from_input(input, "", errors)
+                .map(|p| p.encode())
+        })
+    };
+    (@builder_encode $packet_name:ident, S) => { None };
 }
 
 #[macro_export]
@@ -171,6 +251,7 @@ macro_rules! generate_mod_payload_decoders {
             pub fn register_mod_payload_decoders() {
                 $(
                     $crate::core::custom_payload::register_decoder::<[<$module:camel PayloadDecoder>]>();
+                    $crate::core::packet_builder::register_builder_packets($module::builder_packets());
                 )*
             }
         }
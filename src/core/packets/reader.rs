@@ -1,5 +1,6 @@
 use std::io;
 use uuid::Uuid;
+use crate::core::packets::{List, VarInt};
 
 pub struct ModPacketReader<'a> {
     data: &'a [u8],
@@ -238,4 +239,23 @@ impl ModReadable for Uuid {
     fn read_from(r: &mut ModPacketReader) -> io::Result<Self> {
         r.read_uuid()
     }
+}
+
+impl<T: ModReadable> ModReadable for List<T> {
+    fn read_from(r: &mut ModPacketReader) -> io::Result<Self> {
+        let len = r.read_u32_be()? as usize;
+        // каждый элемент хотя бы байт: мусорная длина не раздует аллокацию
+        r.ensure_available(len)?;
+        let mut items = Vec::with_capacity(len);
+        for _ in 0..len {
+            items.push(T::read_from(r)?);
+        }
+        Ok(List(items))
+    }
+}
+
+impl ModReadable for VarInt {
+    fn read_from(r: &mut ModPacketReader) -> io::Result<Self> {
+        r.read_varint().map(VarInt)
+    }
 }
\ No newline at end of file
@@ -1,5 +1,6 @@
 use std::io;
 use uuid::Uuid;
+use crate::core::packets::{List, VarInt};
 
 pub struct ModPacketWriter {
     data: Vec<u8>,
@@ -184,3 +185,18 @@ impl ModWritable for Uuid {
         w.write_uuid(self);
     }
 }
+
+impl<T: ModWritable> ModWritable for List<T> {
+    fn write_to(&self, w: &mut ModPacketWriter) {
+        w.write_u32_be(self.0.len() as u32);
+        for item in &self.0 {
+            w.write(item);
+        }
+    }
+}
+
+impl ModWritable for VarInt {
+    fn write_to(&self, w: &mut ModPacketWriter) {
+        w.write_varint(self.0);
+    }
+}
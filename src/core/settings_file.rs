@@ -0,0 +1,76 @@
+use crate::account_files;
+use parking_lot::Mutex;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::{fs, path::Path};
+
+/// Файл настроек UI с версией и миграциями. Пишется только при изменениях и атомарно.
+pub struct SettingsFile {
+    path: &'static str,
+    /// Шаг `i` переводит файл из версии `i + 1` в `i + 2`. Новые шаги только дописываются в конец.
+    migrations: &'static [fn(&mut Value)],
+    /// Последний записанный на диск JSON. `None` — сохранение выключено:
+    /// файл не прочитался, и перезаписывать его значениями по умолчанию нельзя.
+    last_saved: Mutex<Option<String>>,
+}
+
+impl SettingsFile {
+    pub const fn new(path: &'static str, migrations: &'static [fn(&mut Value)]) -> Self {
+        Self { path, migrations, last_saved: parking_lot::const_mutex(None) }
+    }
+
+    fn version(&self) -> u32 {
+        self.migrations.len() as u32 + 1
+    }
+
+    /// `Ok(None)` — файла ещё нет
+    pub fn load<T: DeserializeOwned>(&self) -> Result<Option<T>, String> {
+        if !Path::new(self.path).exists() {
+            return Ok(None);
+        }
+        let bytes = fs::read(self.path).map_err(|e| format!("Failed to read {}: {}", self.path, e))?;
+        let raw: Value = serde_json::from_slice(&bytes).map_err(|e| format!("Failed to parse {}: {}", self.path, e))?;
+        let settings = serde_json::from_value(self.migrate(raw)?)
+            .map_err(|e| format!("Failed to read settings from {}: {}", self.path, e))?;
+        Ok(Some(settings))
+    }
+
+    /// После удачной загрузки: `current` считается уже записанным, дальше пишутся только изменения
+    pub fn enable_saving<T: Serialize>(&self, current: &T) {
+        *self.last_saved.lock() = serde_json::to_string_pretty(current).ok();
+    }
+
+    /// Вызывается каждый кадр; пишет файл только если что-то поменялось.
+    pub fn persist<T: Serialize>(&self, current: impl FnOnce() -> T) -> Result<(), String> {
+        let mut last = self.last_saved.lock();
+        let Some(saved) = last.as_mut() else { return Ok(()) };
+        let text = serde_json::to_string_pretty(&current())
+            .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+        if *saved == text {
+            return Ok(());
+        }
+        // при ошибке не повторяем запись каждый кадр, ждём следующего изменения
+        *saved = text;
+        account_files::write_atomic(self.path, saved.as_bytes())
+    }
+
+    fn migrate(&self, mut value: Value) -> Result<Value, String> {
+        let version = value
+            .get("version")
+            .and_then(Value::as_u64)
+            .ok_or_else(|| "Missing settings version".to_string())? as u32;
+        if version == 0 || version > self.version() {
+            return Err(format!(
+                "Settings version {} is not supported (expected 1..={})",
+                version,
+                self.version()
+            ));
+        }
+        for step in &self.migrations[version as usize - 1..] {
+            step(&mut value);
+        }
+        value["version"] = Value::from(self.version());
+        Ok(value)
+    }
+}
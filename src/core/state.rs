@@ -1,5 +1,9 @@
 use crate::account::AccountManager;
+use crate::core::annotations::AnnotationManager;
+use crate::core::packet_snapshots::SnapshotManager;
+use crate::core::packet_triggers::TriggerEngine;
 use crate::graphics::context::PayloadContext;
+use crate::server_binding::ServerBindings;
 use parking_lot::Mutex;
 use std::{
     sync::atomic::{AtomicBool, AtomicIsize, AtomicU32, AtomicUsize, Ordering},
@@ -16,7 +20,11 @@ pub struct GlobalState {
     current_window: AtomicIsize,
     context: OnceLock<Mutex<Option<PayloadContext>>>,
     account_manager: OnceLock<Mutex<AccountManager>>,
+    server_bindings: OnceLock<Mutex<ServerBindings>>,
     packet_store: OnceLock<Mutex<PacketStore>>,
+    annotations: OnceLock<Mutex<AnnotationManager>>,
+    packet_triggers: OnceLock<Mutex<TriggerEngine>>,
+    packet_snapshots: OnceLock<Mutex<SnapshotManager>>,
     packet_paused: AtomicBool,
 
 }
@@ -32,7 +40,11 @@ impl GlobalState {
             current_window: AtomicIsize::new(0),
             context: OnceLock::new(),
             account_manager: OnceLock::new(),
+            server_bindings: OnceLock::new(),
             packet_store: OnceLock::new(),
+            annotations: OnceLock::new(),
+            packet_triggers: OnceLock::new(),
+            packet_snapshots: OnceLock::new(),
             packet_paused: AtomicBool::new(false),
         }
     }
@@ -96,6 +108,11 @@ impl GlobalState {
 
     pub fn initialize_account_manager(&self) {
         self.account_manager.get_or_init(|| Mutex::new(AccountManager::new()));
+        self.server_bindings.get_or_init(|| Mutex::new(ServerBindings::default()));
+    }
+
+    pub fn get_server_bindings(&self) -> &OnceLock<Mutex<ServerBindings>> {
+        &self.server_bindings
     }
 
     pub fn get_packet_store(&self) -> &OnceLock<Mutex<PacketStore>> {
@@ -103,6 +120,23 @@ impl GlobalState {
     }
     pub fn initialize_packet_store(&self) {
         self.packet_store.get_or_init(|| Mutex::new(PacketStore::new(500)));
+        self.packet_triggers.get_or_init(|| Mutex::new(TriggerEngine::default()));
+        self.packet_snapshots.get_or_init(|| Mutex::new(SnapshotManager::default()));
+    }
+
+    pub fn get_packet_triggers(&self) -> &OnceLock<Mutex<TriggerEngine>> {
+        &self.packet_triggers
+    }
+
+    pub fn get_packet_snapshots(&self) -> &OnceLock<Mutex<SnapshotManager>> {
+        &self.packet_snapshots
+    }
+
+    pub fn get_annotations(&self) -> &OnceLock<Mutex<AnnotationManager>> {
+        &self.annotations
+    }
+    pub fn initialize_annotations(&self) {
+        self.annotations.get_or_init(|| Mutex::new(AnnotationManager::new()));
     }
 
     pub fn is_packet_paused(&self) -> bool {
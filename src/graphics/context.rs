@@ -3,11 +3,22 @@ use crate::utils::SafeGLContext;
 use egui::{Context, Event};
 use egui_glow::Painter;
 use glow::Context as GlowContext;
+use serde::{Deserialize, Serialize};
 use std::time::Instant;
 use winapi::shared::windef::HDC;
 use crate::graphics::svg_icons::SvgIconManager;
 use crate::ui::notification_manager::NotificationManager;
-use crate::graphics::netlog::PacketDirection;
+use crate::core::layout_infer::InferredPacket;
+use crate::core::packet_correlation::CorrelationRule;
+use crate::core::packet_search::{SearchHit, SearchMode};
+use crate::graphics::netstats::StatsKind;
+use crate::ui::packet_timeline::TimelineLanes;
+use crate::ui::packet_columns::PacketColumn;
+use crate::account::AccountSort;
+use crate::auth::AuthProviderKind;
+use crate::account_transfer::{ImportCandidate, TransferFormat};
+use crate::core::packet_builder::FieldInput;
+use crate::ui::packet_hex_editor::HexEditor;
 
 pub struct PayloadContext {
     pub painter: Painter,
@@ -49,6 +60,28 @@ pub struct PayloadContext {
     pub edit_session_type: String,
     pub edit_password: String,
     pub edit_original_name: String,
+    pub edit_group: String,
+    pub edit_tags: String,
+    pub edit_notes: String,
+    pub edit_servers: String,
+    pub edit_provider: AuthProviderKind,
+    pub account_group_by_folder: bool,
+    pub account_filter_tag: String,
+    pub account_favorites_only: bool,
+    pub account_search: String,
+    pub account_sort: AccountSort,
+    pub account_sort_desc: bool,
+    pub account_filter_password: Option<bool>,
+    pub account_filter_session: String,
+    pub vault_password_input: String,
+    pub vault_new_password: String,
+    pub vault_confirm_password: String,
+    pub vault_busy: bool,
+    pub account_transfer_format: TransferFormat,
+    pub account_transfer_path: String,
+    pub account_transfer_password: String,
+    pub account_export_confirm: Option<String>,
+    pub account_import_plan: Vec<ImportCandidate>,
     pub auth_in_progress: bool,
 
     pub packet_filter: String,
@@ -68,12 +101,48 @@ pub struct PayloadContext {
     pub packet_show_only_new: bool,
     pub packet_last_seen_id: u64,
     pub packet_secondary_selected_id: Option<u64>,
-    pub packet_triggers: Vec<PacketTrigger>,
     pub packet_trigger_input: String,
+    pub packet_trigger_pin: bool,
+    pub packet_trigger_notify: bool,
+    pub packet_trigger_pause: bool,
+    pub packet_trigger_export: String,
     pub packet_tag_editor: String,
     pub packet_color_hex: String,
     pub packet_export_limit: u32,
     pub packet_import_buffer: String,
+    pub packet_search_input: String,
+    pub packet_search_mode: SearchMode,
+    pub packet_search_hit: Option<SearchHit>,
+    pub packet_search_scroll_to: Option<u64>,
+    pub packet_stats_top_n: u32,
+    pub packet_stats_by_bytes: bool,
+    pub packet_stats_selected: Option<(StatsKind, String)>,
+    pub packet_timeline_lanes: TimelineLanes,
+    pub packet_timeline_view: Option<(u64, u64)>,
+    pub packet_time_range: Option<(u64, u64)>,
+    pub packet_infer_channel: String,
+    pub packet_infer_result: Vec<InferredPacket>,
+    pub packet_infer_draft: String,
+    pub packet_correlation_rules: Vec<CorrelationRule>,
+    pub packet_correlation_channel_input: String,
+    pub packet_correlation_key_input: String,
+    pub packet_session_filter: Option<u32>,
+    pub packet_note_type_input: String,
+    pub packet_note_record_input: String,
+    pub packet_note_loaded_for: Option<u64>,
+    pub packet_columns: Vec<PacketColumn>,
+    pub packet_split_rows: bool,
+    pub packet_sort: Option<(PacketColumn, bool)>,
+    pub packet_column_field_input: String,
+    pub packet_hex_editor: Option<HexEditor>,
+    pub packet_builder_channel: String,
+    pub packet_builder_packet: String,
+    pub packet_builder_input: FieldInput,
+    pub packet_snapshots_open: bool,
+    pub packet_snapshot_view: Option<String>,
+    pub packet_snapshot_name: String,
+    pub packet_snapshot_rename: Option<(String, String)>,
+    pub packet_snapshot_file: String,
 
     pub search_query: String,
     pub selected_class: Option<String>,
@@ -91,6 +160,10 @@ pub struct PayloadContext {
     pub auth_tab_result_profile: String,
     pub auth_tab_in_progress: bool,
     pub auth_tab_error: Option<String>,
+    pub auth_tab_provider: AuthProviderKind,
+    pub auth_tab_result_username: String,
+    pub auth_tab_client_token: String,
+    pub auth_tab_account_name: String,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -118,20 +191,13 @@ impl PayloadContext {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct PacketFilterProfile {
     pub name: String,
     pub query: String,
     pub show_inbound: bool,
     pub show_outbound: bool,
     pub only_pinned: bool,
+    pub columns: Vec<PacketColumn>,
 }
 
-#[derive(Clone, Debug)]
-pub struct PacketTrigger {
-    pub name: String,
-    pub needle: String,
-    pub dir: Option<PacketDirection>,
-    pub highlight: [u8; 3],
-    pub pin: bool,
-}
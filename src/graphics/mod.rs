@@ -4,3 +4,4 @@ pub mod renderer;
 pub mod icon_renderer;
 pub mod svg_icons;
 pub mod netlog;
+pub mod netstats;
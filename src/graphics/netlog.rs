@@ -1,3 +1,5 @@
+use crate::graphics::netstats::PacketStats;
+use crate::mappings::classes;
 use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
 use std::sync::atomic::{AtomicU64, Ordering};
@@ -32,12 +34,83 @@ pub struct PacketRecord {
     pub tags: Vec<String>,
     pub color: Option<[u8; 3]>,
     pub group: Option<String>,
+    /// Сессия подключения, в которой пришёл пакет
+    #[serde(default)]
+    pub session: Option<u32>,
+}
+
+/// Старых сессий храним не больше этого, записи на них при этом не трогаем
+const MAX_SESSIONS: usize = 64;
+
+/// Одно подключение к серверу: от handshake/login до disconnect (или следующего handshake)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PacketSession {
+    pub id: u32,
+    pub started_millis: u64,
+    pub ended_millis: Option<u64>,
+    /// host:port из handshake
+    pub server: Option<String>,
+    /// Ник активной сессии игры на момент подключения
+    pub account: Option<String>,
+    pub packets: u64,
+    pub bytes: u64,
+}
+
+impl PacketSession {
+    pub fn is_open(&self) -> bool {
+        self.ended_millis.is_none()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SessionEvent {
+    /// Исходящий handshake, адрес сервера из пакета
+    Connect { server: Option<String> },
+    /// Login start без handshake перед ним (например, после переподключения)
+    Login,
+    Disconnect,
+}
+
+/// next state в handshake: 1 — status (пинг), 2 — login
+const HANDSHAKE_STATE_LOGIN: i32 = 2;
+
+/// Какое событие подключения несёт запись, по имени класса пакета.
+pub fn session_event(rec: &PacketRecord) -> Option<SessionEvent> {
+    match rec.name.as_str() {
+        n if n == classes::HANDSHAKE => match parse_handshake(&rec.data) {
+            // пинг из списка серверов (status) сессией не считается
+            Some((_, state)) if state != HANDSHAKE_STATE_LOGIN => None,
+            Some((server, _)) => Some(SessionEvent::Connect { server: Some(server) }),
+            None => Some(SessionEvent::Connect { server: None }),
+        },
+        n if n == classes::LOGIN_START => Some(SessionEvent::Login),
+        n if n == classes::DISCONNECT_PLAY || n == classes::DISCONNECT_LOGIN => Some(SessionEvent::Disconnect),
+        _ => None,
+    }
+}
+
+/// C00Handshake: VarInt protocol, String host, u16 port, VarInt state.
+/// FML дописывает к хосту `\0FML\0`, отрезаем.
+fn parse_handshake(data: &[u8]) -> Option<(String, i32)> {
+    let (_, mut off) = read_varint(data)?;
+    let (len, used) = read_varint(data.get(off..)?)?;
+    off += used;
+    let host_bytes = data.get(off..off + usize::try_from(len).ok()?)?;
+    off += host_bytes.len();
+    let host = std::str::from_utf8(host_bytes).ok()?;
+    let host = host.split('\0').next().unwrap_or(host);
+    let port = u16::from_be_bytes([*data.get(off)?, *data.get(off + 1)?]);
+    let (state, _) = read_varint(data.get(off + 2..)?)?;
+    Some((format!("{host}:{port}"), state))
 }
 
 pub struct PacketStore {
     buf: VecDeque<PacketRecord>,
     current_bytes: usize,
     limits: Limits,
+    traffic: PacketStats,
+    sessions: Vec<PacketSession>,
+    next_session_id: u32,
 }
 
 #[derive(Clone, Debug)]
@@ -58,11 +131,20 @@ impl PacketStore {
                 max_bytes: None,
                 autoclear_oldest: true,
             },
+            traffic: PacketStats::default(),
+            sessions: Vec::new(),
+            next_session_id: 1,
         }
     }
 
-    pub fn push(&mut self, rec: PacketRecord) {
+    pub fn push(&mut self, mut rec: PacketRecord) {
         let added = rec.data.len();
+        if let Some(session) = self.sessions.last_mut().filter(|s| s.is_open()) {
+            session.packets += 1;
+            session.bytes += added as u64;
+            rec.session = Some(session.id);
+        }
+        self.traffic.record(&rec);
         self.buf.push_back(rec);
         self.current_bytes = self.current_bytes.saturating_add(added);
         self.trim_to_limits();
@@ -74,6 +156,47 @@ impl PacketStore {
     pub fn clear(&mut self) {
         self.buf.clear();
         self.current_bytes = 0;
+        self.traffic.clear();
+        // открытую сессию оставляем — подключение ещё живо
+        self.sessions.retain(|s| s.is_open());
+        for s in &mut self.sessions {
+            s.packets = 0;
+            s.bytes = 0;
+        }
+    }
+
+    /// Начинает новую сессию, закрывая предыдущую открытую.
+    pub fn begin_session(&mut self, server: Option<String>, account: Option<String>) -> u32 {
+        self.end_session();
+        let id = self.next_session_id;
+        self.next_session_id += 1;
+        self.sessions.push(PacketSession {
+            id,
+            started_millis: now_millis(),
+            ended_millis: None,
+            server,
+            account,
+            packets: 0,
+            bytes: 0,
+        });
+        if self.sessions.len() > MAX_SESSIONS {
+            self.sessions.remove(0);
+        }
+        id
+    }
+
+    pub fn end_session(&mut self) {
+        if let Some(s) = self.sessions.last_mut().filter(|s| s.is_open()) {
+            s.ended_millis = Some(now_millis());
+        }
+    }
+
+    pub fn current_session(&self) -> Option<&PacketSession> {
+        self.sessions.last().filter(|s| s.is_open())
+    }
+
+    pub fn sessions(&self) -> &[PacketSession] {
+        &self.sessions
     }
 
     pub fn set_max_count(&mut self, max: Option<usize>) {
@@ -131,6 +254,11 @@ impl PacketStore {
     pub fn stats(&self) -> (usize, usize) {
         (self.buf.len(), self.current_bytes)
     }
+
+    /// Статистика по всем пакетам с последней очистки, включая вытесненные
+    pub fn traffic_stats(&self) -> &PacketStats {
+        &self.traffic
+    }
 }
 
 fn now_millis() -> u64 {
@@ -173,9 +301,10 @@ pub fn try_decode_custom_payload(bytes: &[u8]) -> Option<(String, usize)> {
     Some((channel, used1 + u))
 }
 
-pub fn make_record(dir: PacketDirection, name: String, data: Vec<u8>) -> PacketRecord {
-    let details = if name.contains("CustomPayload") {
-        if let Some((channel, channel_len)) = try_decode_custom_payload(&data) {
+/// Канал и превью для custom payload; для остальных пакетов `None`.
+pub fn record_details(name: &str, data: &[u8]) -> Option<PacketDetails> {
+    if name.contains("CustomPayload") {
+        if let Some((channel, channel_len)) = try_decode_custom_payload(data) {
             let rest = &data.get(channel_len..).unwrap_or(&[]);
             let mut preview: Option<String> = None;
             if !rest.is_empty() {
@@ -191,7 +320,11 @@ pub fn make_record(dir: PacketDirection, name: String, data: Vec<u8>) -> PacketR
             }
             Some(PacketDetails::CustomPayload { channel, channel_len, preview })
         } else { None }
-    } else { None };
+    } else { None }
+}
+
+pub fn make_record(dir: PacketDirection, name: String, data: Vec<u8>) -> PacketRecord {
+    let details = record_details(&name, &data);
     let details_clone = details.clone();
 
     let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
@@ -206,6 +339,7 @@ pub fn make_record(dir: PacketDirection, name: String, data: Vec<u8>) -> PacketR
         pinned: false,
         tags: Vec::new(),
         color: None,
+        session: None,
         group: details_clone.as_ref().and_then(|d| match d {
             PacketDetails::CustomPayload { channel, .. } => Some(channel.clone()),
         }).or_else(|| Some(name)),
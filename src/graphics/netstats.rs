@@ -0,0 +1,224 @@
+use crate::graphics::netlog::{PacketDetails, PacketDirection, PacketRecord};
+use std::collections::{HashMap, VecDeque};
+
+/// Сколько секунд истории хранить для графика скорости
+pub const RATE_HISTORY_SECS: usize = 120;
+/// Сколько последних интервалов между пакетами одного типа держим
+pub const INTER_ARRIVAL_KEEP: usize = 256;
+/// Корзины размера: 0, 1, 2-3, 4-7, ... 2^20+
+pub const SIZE_BUCKETS: usize = 22;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum StatsKind {
+    Class,
+    Channel,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RateBucket {
+    pub sec: u64,
+    pub packets: u32,
+    pub bytes: u64,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct DirectionStats {
+    pub packets: u64,
+    pub bytes: u64,
+    buckets: VecDeque<RateBucket>,
+}
+
+impl DirectionStats {
+    fn record(&mut self, ts_millis: u64, len: usize) {
+        self.packets += 1;
+        self.bytes += len as u64;
+
+        let sec = ts_millis / 1000;
+        match self.buckets.back_mut() {
+            Some(b) if b.sec == sec => {
+                b.packets += 1;
+                b.bytes += len as u64;
+            }
+            // часы могли уйти назад — кладём в последнюю корзину, чтобы не ломать порядок
+            Some(b) if b.sec > sec => {
+                b.packets += 1;
+                b.bytes += len as u64;
+            }
+            _ => self.buckets.push_back(RateBucket { sec, packets: 1, bytes: len as u64 }),
+        }
+        while self
+            .buckets
+            .front()
+            .is_some_and(|f| f.sec + (RATE_HISTORY_SECS as u64) <= sec)
+        {
+            self.buckets.pop_front();
+        }
+    }
+
+    /// Последние `len` секунд до `now_sec` (не включая), пустые секунды заполнены нулями.
+    pub fn series(&self, now_sec: u64, len: usize) -> Vec<RateBucket> {
+        let from = now_sec.saturating_sub(len as u64);
+        let mut out: Vec<RateBucket> = (from..now_sec)
+            .map(|sec| RateBucket { sec, ..Default::default() })
+            .collect();
+        for b in &self.buckets {
+            if b.sec >= from && b.sec < now_sec {
+                out[(b.sec - from) as usize] = *b;
+            }
+        }
+        out
+    }
+
+    /// Скорость за последнюю полную секунду
+    pub fn last_second(&self, now_sec: u64) -> RateBucket {
+        let sec = now_sec.saturating_sub(1);
+        self.buckets
+            .iter()
+            .rev()
+            .find(|b| b.sec == sec)
+            .copied()
+            .unwrap_or(RateBucket { sec, ..Default::default() })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct TypeStats {
+    pub count: u64,
+    pub bytes: u64,
+    pub min_len: usize,
+    pub max_len: usize,
+    pub size_hist: [u64; SIZE_BUCKETS],
+    pub last_ts: Option<u64>,
+    /// Интервалы в мс, от старых к новым
+    pub inter_arrival: VecDeque<u64>,
+}
+
+impl Default for TypeStats {
+    fn default() -> Self {
+        Self {
+            count: 0,
+            bytes: 0,
+            min_len: usize::MAX,
+            max_len: 0,
+            size_hist: [0; SIZE_BUCKETS],
+            last_ts: None,
+            inter_arrival: VecDeque::new(),
+        }
+    }
+}
+
+impl TypeStats {
+    fn record(&mut self, ts_millis: u64, len: usize) {
+        self.count += 1;
+        self.bytes += len as u64;
+        self.min_len = self.min_len.min(len);
+        self.max_len = self.max_len.max(len);
+        self.size_hist[size_bucket(len)] += 1;
+        if let Some(prev) = self.last_ts {
+            if self.inter_arrival.len() >= INTER_ARRIVAL_KEEP {
+                self.inter_arrival.pop_front();
+            }
+            self.inter_arrival.push_back(ts_millis.saturating_sub(prev));
+        }
+        self.last_ts = Some(ts_millis);
+    }
+
+    pub fn avg_len(&self) -> f64 {
+        if self.count == 0 { 0.0 } else { self.bytes as f64 / self.count as f64 }
+    }
+
+    pub fn avg_interval(&self) -> Option<f64> {
+        if self.inter_arrival.is_empty() {
+            return None;
+        }
+        Some(self.inter_arrival.iter().sum::<u64>() as f64 / self.inter_arrival.len() as f64)
+    }
+}
+
+pub fn size_bucket(len: usize) -> usize {
+    if len == 0 {
+        0
+    } else {
+        ((usize::BITS - len.leading_zeros()) as usize).min(SIZE_BUCKETS - 1)
+    }
+}
+
+/// Подпись корзины, например "4-7" или "1M+"
+pub fn size_bucket_label(idx: usize) -> String {
+    fn short(n: usize) -> String {
+        if n >= 1 << 20 {
+            format!("{}M", n >> 20)
+        } else if n >= 1 << 10 {
+            format!("{}K", n >> 10)
+        } else {
+            n.to_string()
+        }
+    }
+    match idx {
+        0 => "0".to_string(),
+        1 => "1".to_string(),
+        i if i == SIZE_BUCKETS - 1 => format!("{}+", short(1 << (i - 1))),
+        i => format!("{}-{}", short(1 << (i - 1)), short((1 << i) - 1)),
+    }
+}
+
+/// Накопительная статистика, обновляется в `PacketStore::push`, не зависит от вытеснения записей.
+#[derive(Clone, Debug, Default)]
+pub struct PacketStats {
+    pub inbound: DirectionStats,
+    pub outbound: DirectionStats,
+    pub classes: HashMap<String, TypeStats>,
+    pub channels: HashMap<String, TypeStats>,
+}
+
+impl PacketStats {
+    pub fn record(&mut self, rec: &PacketRecord) {
+        let len = rec.data.len();
+        self.direction_mut(rec.dir).record(rec.ts_millis, len);
+        self.classes.entry(rec.name.clone()).or_default().record(rec.ts_millis, len);
+        if let Some(PacketDetails::CustomPayload { channel, .. }) = &rec.details {
+            self.channels.entry(channel.clone()).or_default().record(rec.ts_millis, len);
+        }
+    }
+
+    pub fn direction(&self, dir: PacketDirection) -> &DirectionStats {
+        match dir {
+            PacketDirection::Inbound => &self.inbound,
+            PacketDirection::Outbound => &self.outbound,
+        }
+    }
+
+    fn direction_mut(&mut self, dir: PacketDirection) -> &mut DirectionStats {
+        match dir {
+            PacketDirection::Inbound => &mut self.inbound,
+            PacketDirection::Outbound => &mut self.outbound,
+        }
+    }
+
+    pub fn types(&self, kind: StatsKind) -> &HashMap<String, TypeStats> {
+        match kind {
+            StatsKind::Class => &self.classes,
+            StatsKind::Channel => &self.channels,
+        }
+    }
+
+    /// Топ по количеству или по объёму: (ключ, count, bytes)
+    pub fn top(&self, kind: StatsKind, n: usize, by_bytes: bool) -> Vec<(String, u64, u64)> {
+        let mut v: Vec<(String, u64, u64)> = self
+            .types(kind)
+            .iter()
+            .map(|(k, s)| (k.clone(), s.count, s.bytes))
+            .collect();
+        if by_bytes {
+            v.sort_by(|a, b| b.2.cmp(&a.2).then_with(|| a.0.cmp(&b.0)));
+        } else {
+            v.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        }
+        v.truncate(n);
+        v
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
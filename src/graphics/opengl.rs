@@ -1,5 +1,15 @@
 use crate::{
     core::state::GlobalState,
+    core::analyzer_settings::load_analyzer_settings,
+    core::account_list_settings::load_account_list_settings,
+    core::packet_correlation::CorrelationRule,
+    core::packet_search::SearchMode,
+    core::packet_builder::FieldInput,
+    account::AccountSort,
+    auth::AuthProviderKind,
+    account_transfer::TransferFormat,
+    ui::packet_timeline::TimelineLanes,
+    ui::packet_columns::PacketColumn,
     graphics::context::{AppTab, PayloadContext},
     graphics::svg_icons::SvgIconManager,
     input::clipboard::ClipboardManager,
@@ -29,6 +39,7 @@ pub unsafe fn create_render_context(hdc: HDC) -> Result<PayloadContext, String>
 
     GlobalState::instance().set_current_window(window as isize);
     GlobalState::instance().initialize_packet_store();
+    GlobalState::instance().initialize_annotations();
 
     let mut dimensions = winapi::shared::windef::RECT::default();
     GetClientRect(window, &mut dimensions);
@@ -81,7 +92,7 @@ pub unsafe fn create_render_context(hdc: HDC) -> Result<PayloadContext, String>
 
     GlobalState::instance().initialize_account_manager();
 
-    Ok(PayloadContext {
+    let mut context = PayloadContext {
         painter,
         egui_ctx,
         dimensions: [width, height],
@@ -117,6 +128,28 @@ pub unsafe fn create_render_context(hdc: HDC) -> Result<PayloadContext, String>
         edit_session_type: String::new(),
         edit_password: String::new(),
         edit_original_name: String::new(),
+        edit_group: String::new(),
+        edit_tags: String::new(),
+        edit_notes: String::new(),
+        edit_servers: String::new(),
+        edit_provider: AuthProviderKind::default(),
+        account_group_by_folder: true,
+        account_filter_tag: String::new(),
+        account_favorites_only: false,
+        account_search: String::new(),
+        account_sort: AccountSort::LastUsed,
+        account_sort_desc: true,
+        account_filter_password: None,
+        account_filter_session: String::new(),
+        vault_password_input: String::new(),
+        vault_new_password: String::new(),
+        vault_confirm_password: String::new(),
+        vault_busy: false,
+        account_transfer_format: TransferFormat::Bundle,
+        account_transfer_path: String::new(),
+        account_transfer_password: String::new(),
+        account_export_confirm: None,
+        account_import_plan: Vec::new(),
         auth_in_progress: false,
 
         packet_filter: String::new(),
@@ -136,12 +169,51 @@ pub unsafe fn create_render_context(hdc: HDC) -> Result<PayloadContext, String>
         packet_show_only_new: false,
         packet_last_seen_id: 0,
         packet_secondary_selected_id: None,
-        packet_triggers: Vec::new(),
         packet_trigger_input: String::new(),
+        packet_trigger_pin: false,
+        packet_trigger_notify: true,
+        packet_trigger_pause: false,
+        packet_trigger_export: String::new(),
         packet_tag_editor: String::new(),
         packet_color_hex: String::from("#ffaa00"),
         packet_export_limit: 500,
         packet_import_buffer: String::new(),
+        packet_search_input: String::new(),
+        packet_search_mode: SearchMode::default(),
+        packet_search_hit: None,
+        packet_search_scroll_to: None,
+        packet_stats_top_n: 10,
+        packet_stats_by_bytes: false,
+        packet_stats_selected: None,
+        packet_timeline_lanes: TimelineLanes::default(),
+        packet_timeline_view: None,
+        packet_time_range: None,
+        packet_infer_channel: String::new(),
+        packet_infer_result: Vec::new(),
+        packet_infer_draft: String::new(),
+        packet_correlation_rules: vec![CorrelationRule {
+            channel: "dwcity".to_string(),
+            key_field: "username".to_string(),
+        }],
+        packet_correlation_channel_input: String::new(),
+        packet_correlation_key_input: String::new(),
+        packet_session_filter: None,
+        packet_note_type_input: String::new(),
+        packet_note_record_input: String::new(),
+        packet_note_loaded_for: None,
+        packet_columns: PacketColumn::defaults(),
+        packet_split_rows: true,
+        packet_sort: None,
+        packet_column_field_input: String::new(),
+        packet_hex_editor: None,
+        packet_builder_channel: String::new(),
+        packet_builder_packet: String::new(),
+        packet_builder_input: FieldInput::Struct(Vec::new()),
+        packet_snapshots_open: false,
+        packet_snapshot_view: None,
+        packet_snapshot_name: String::new(),
+        packet_snapshot_rename: None,
+        packet_snapshot_file: String::new(),
 
         search_query: String::new(),
         selected_class: None,
@@ -159,7 +231,15 @@ pub unsafe fn create_render_context(hdc: HDC) -> Result<PayloadContext, String>
         auth_tab_result_profile: String::new(),
         auth_tab_in_progress: false,
         auth_tab_error: None,
-    })
+        auth_tab_provider: AuthProviderKind::default(),
+        auth_tab_result_username: String::new(),
+        auth_tab_client_token: String::new(),
+        auth_tab_account_name: String::new(),
+    };
+    load_analyzer_settings(&mut context);
+    load_account_list_settings(&mut context);
+
+    Ok(context)
 }
 
 unsafe fn create_glow_context() -> Result<GlowContext, String> {
@@ -117,6 +117,28 @@ fn render_egui_frame(context: &mut PayloadContext, width: u32, height: u32) -> R
         edit_session_type: &mut context.edit_session_type,
         edit_password: &mut context.edit_password,
         edit_original_name: &mut context.edit_original_name,
+        edit_group: &mut context.edit_group,
+        edit_tags: &mut context.edit_tags,
+        edit_notes: &mut context.edit_notes,
+        edit_servers: &mut context.edit_servers,
+        edit_provider: &mut context.edit_provider,
+        account_group_by_folder: &mut context.account_group_by_folder,
+        account_filter_tag: &mut context.account_filter_tag,
+        account_favorites_only: &mut context.account_favorites_only,
+        account_search: &mut context.account_search,
+        account_sort: &mut context.account_sort,
+        account_sort_desc: &mut context.account_sort_desc,
+        account_filter_password: &mut context.account_filter_password,
+        account_filter_session: &mut context.account_filter_session,
+        vault_password_input: &mut context.vault_password_input,
+        vault_new_password: &mut context.vault_new_password,
+        vault_confirm_password: &mut context.vault_confirm_password,
+        vault_busy: &mut context.vault_busy,
+        account_transfer_format: &mut context.account_transfer_format,
+        account_transfer_path: &mut context.account_transfer_path,
+        account_transfer_password: &mut context.account_transfer_password,
+        account_export_confirm: &mut context.account_export_confirm,
+        account_import_plan: &mut context.account_import_plan,
         auth_in_progress: &mut context.auth_in_progress,
         packet_filter: &mut context.packet_filter,
         packet_show_inbound: &mut context.packet_show_inbound,
@@ -135,12 +157,48 @@ fn render_egui_frame(context: &mut PayloadContext, width: u32, height: u32) -> R
         packet_show_only_new: &mut context.packet_show_only_new,
         packet_last_seen_id: &mut context.packet_last_seen_id,
         packet_secondary_selected_id: &mut context.packet_secondary_selected_id,
-        packet_triggers: &mut context.packet_triggers,
         packet_trigger_input: &mut context.packet_trigger_input,
+        packet_trigger_pin: &mut context.packet_trigger_pin,
+        packet_trigger_notify: &mut context.packet_trigger_notify,
+        packet_trigger_pause: &mut context.packet_trigger_pause,
+        packet_trigger_export: &mut context.packet_trigger_export,
         packet_tag_editor: &mut context.packet_tag_editor,
         packet_color_hex: &mut context.packet_color_hex,
         packet_export_limit: &mut context.packet_export_limit,
         packet_import_buffer: &mut context.packet_import_buffer,
+        packet_search_input: &mut context.packet_search_input,
+        packet_search_mode: &mut context.packet_search_mode,
+        packet_search_hit: &mut context.packet_search_hit,
+        packet_search_scroll_to: &mut context.packet_search_scroll_to,
+        packet_stats_top_n: &mut context.packet_stats_top_n,
+        packet_stats_by_bytes: &mut context.packet_stats_by_bytes,
+        packet_stats_selected: &mut context.packet_stats_selected,
+        packet_timeline_lanes: &mut context.packet_timeline_lanes,
+        packet_timeline_view: &mut context.packet_timeline_view,
+        packet_time_range: &mut context.packet_time_range,
+        packet_infer_channel: &mut context.packet_infer_channel,
+        packet_infer_result: &mut context.packet_infer_result,
+        packet_infer_draft: &mut context.packet_infer_draft,
+        packet_correlation_rules: &mut context.packet_correlation_rules,
+        packet_correlation_channel_input: &mut context.packet_correlation_channel_input,
+        packet_correlation_key_input: &mut context.packet_correlation_key_input,
+        packet_session_filter: &mut context.packet_session_filter,
+        packet_note_type_input: &mut context.packet_note_type_input,
+        packet_note_record_input: &mut context.packet_note_record_input,
+        packet_note_loaded_for: &mut context.packet_note_loaded_for,
+        packet_columns: &mut context.packet_columns,
+        packet_split_rows: &mut context.packet_split_rows,
+        packet_sort: &mut context.packet_sort,
+        packet_column_field_input: &mut context.packet_column_field_input,
+        packet_hex_editor: &mut context.packet_hex_editor,
+        packet_builder_channel: &mut context.packet_builder_channel,
+        packet_builder_packet: &mut context.packet_builder_packet,
+        packet_builder_input: &mut context.packet_builder_input,
+        packet_snapshots_open: &mut context.packet_snapshots_open,
+        packet_snapshot_view: &mut context.packet_snapshot_view,
+        packet_snapshot_name: &mut context.packet_snapshot_name,
+        packet_snapshot_rename: &mut context.packet_snapshot_rename,
+        packet_snapshot_file: &mut context.packet_snapshot_file,
 
         search_query: &mut context.search_query,
         selected_class: &mut context.selected_class,
@@ -158,6 +216,10 @@ fn render_egui_frame(context: &mut PayloadContext, width: u32, height: u32) -> R
         auth_tab_result_profile: &mut context.auth_tab_result_profile,
         auth_tab_in_progress: &mut context.auth_tab_in_progress,
         auth_tab_error: &mut context.auth_tab_error,
+        auth_tab_provider: &mut context.auth_tab_provider,
+        auth_tab_result_username: &mut context.auth_tab_result_username,
+        auth_tab_client_token: &mut context.auth_tab_client_token,
+        auth_tab_account_name: &mut context.auth_tab_account_name,
     };
 
     let mut icon_manager = std::mem::take(&mut context.icon_manager);
@@ -147,6 +147,7 @@ unsafe extern "system" fn hk_swap_buffers(hdc: HDC) -> i32 {
 
     ensure_window_hook(hdc);
     handle_input();
+    enforce_account_auto_lock();
 
     let current_context = wglGetCurrentContext();
     if current_context.is_null() {
@@ -182,6 +183,15 @@ fn handle_input() {
     }
 }
 
+/// Автоблокировка не должна зависеть от того, открыто ли меню
+fn enforce_account_auto_lock() {
+    let Some(manager) = GlobalState::instance().get_account_manager().get() else { return };
+    // занят netty или UI — проверим на следующем кадре
+    if let Some(mut manager) = manager.try_lock() {
+        manager.enforce_auto_lock();
+    }
+}
+
 fn ensure_window_hook(hdc: HDC) {
     let state = GlobalState::instance();
 
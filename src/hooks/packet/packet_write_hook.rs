@@ -3,7 +3,8 @@ use crate::hooks::jhook::{HookCallback, HookDecision};
 use crate::hooks::packet::utils::{
     new_packet_buffer, packet_class_name, push_packet_log, read_all_bytes,
 };
-use crate::mappings::{classes, methods, signatures};
+use crate::core::state::GlobalState;
+use crate::mappings::{classes, fields, methods, signatures};
 use jni::objects::{JClass, JMethodID, JObject, JString, JValue};
 use jni::sys::jvalue;
 use jni::JNIEnv;
@@ -78,6 +79,47 @@ impl PacketWriteHook {
         }
     }
 
+    /// LoginStart собран до смены сессии по привязке, поэтому профиль в нём ещё старый.
+    /// Подменяем его на профиль применённой учётки до сериализации.
+    unsafe fn patch_login_profile(&self, env: &mut JNIEnv, packet: &JObject) -> anyhow::Result<()> {
+        let Some(bindings) = GlobalState::instance().get_server_bindings().get() else { return Ok(()) };
+        let Some(profile) = bindings.lock().take_pending_profile() else { return Ok(()) };
+
+        let old_profile = env
+            .get_field(packet, fields::LOGIN_START_PROFILE, signatures::GAME_PROFILE)?
+            .l()?;
+        if old_profile.is_null() {
+            return Ok(());
+        }
+        // класс берём у старого профиля: authlib может быть не виден загрузчику потока netty
+        let profile_class = env.get_object_class(&old_profile)?;
+
+        let uuid = dashed_uuid(&profile.player_id);
+        let uuid_str = env.new_string(&uuid)?;
+        let uuid_obj = env
+            .call_static_method(
+                "java/util/UUID",
+                "fromString",
+                "(Ljava/lang/String;)Ljava/util/UUID;",
+                &[JValue::Object(&uuid_str)],
+            )?
+            .l()?;
+        let name = env.new_string(&profile.username)?;
+        let new_profile = env.new_object(
+            profile_class,
+            signatures::GAME_PROFILE_INIT,
+            &[JValue::Object(&uuid_obj), JValue::Object(&name)],
+        )?;
+        env.set_field(
+            packet,
+            fields::LOGIN_START_PROFILE,
+            signatures::GAME_PROFILE,
+            JValue::Object(&new_profile),
+        )?;
+        tracing::info!("[LOGIN] profile replaced with {}", profile.username);
+        Ok(())
+    }
+
     unsafe fn log_pre_encode(
         &self,
         env: &mut JNIEnv,
@@ -96,6 +138,11 @@ impl PacketWriteHook {
             let _ = env.pop_local_frame(&JObject::null());
             return Ok(());
         }
+        if name_str == classes::LOGIN_START {
+            if let Err(e) = self.patch_login_profile(env, packet) {
+                tracing::error!("LoginStart profile patch failed: {e}");
+            }
+        }
 
         let packet_buffer = new_packet_buffer(env, 256)?;
 
@@ -122,3 +169,11 @@ impl PacketWriteHook {
         Ok(())
     }
 }
+
+/// UUID.fromString требует дефисы, лаунчеры часто хранят без них
+fn dashed_uuid(id: &str) -> String {
+    if id.len() != 32 || !id.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return id.to_string();
+    }
+    format!("{}-{}-{}-{}-{}", &id[..8], &id[8..12], &id[12..16], &id[16..20], &id[20..])
+}
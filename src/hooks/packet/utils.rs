@@ -1,5 +1,7 @@
+use crate::async_runtime::ASYNC_RUNTIME;
 use crate::core::state::GlobalState;
-use crate::graphics::netlog::{make_record, PacketDirection};
+use crate::graphics::netlog::{make_record, session_event, PacketDirection, SessionEvent};
+use crate::jvm::get_jvm;
 use crate::mappings::classes;
 use anyhow::{Context, Result};
 use jni::objects::{JByteArray, JObject, JString, JValue};
@@ -80,8 +82,57 @@ pub unsafe fn read_all_bytes(
 
 pub fn push_packet_log(direction: PacketDirection, name: String, bytes: Vec<u8>) {
     if let Some(store) = GlobalState::instance().get_packet_store().get() {
-        if !GlobalState::instance().is_packet_paused() {
-            store.lock().push(make_record(direction, name, bytes));
+        let mut rec = make_record(direction, name, bytes);
+        let event = session_event(&rec);
+        let paused = GlobalState::instance().is_packet_paused();
+        // триггеры до блокировки стора: им может понадобиться расшифровка и запись в файл
+        if !paused {
+            if let Some(triggers) = GlobalState::instance().get_packet_triggers().get() {
+                if triggers.lock().apply(&mut rec) {
+                    GlobalState::instance().set_packet_paused(true);
+                }
+            }
+        }
+        if let Some(SessionEvent::Connect { server: Some(server) }) = &event {
+            if let Some(bindings) = GlobalState::instance().get_server_bindings().get() {
+                bindings.lock().on_handshake(server);
+            }
+            remember_account_server(server);
+        }
+        let mut store = store.lock();
+        // сессии отслеживаем и на паузе, иначе после снятия паузы пакеты уйдут не в ту сессию
+        match &event {
+            Some(SessionEvent::Connect { server }) => {
+                store.begin_session(server.clone(), current_account());
+            }
+            Some(SessionEvent::Login) if store.current_session().is_none() => {
+                store.begin_session(None, current_account());
+            }
+            _ => {}
+        }
+        if !paused {
+            store.push(rec);
+        }
+        if event == Some(SessionEvent::Disconnect) {
+            store.end_session();
         }
     }
 }
+
+fn current_account() -> Option<String> {
+    let username = get_jvm().get_current_session().username;
+    (!username.is_empty()).then_some(username)
+}
+
+/// Для сортировки списка учёток по серверу. Connect приходит только на login-handshake,
+/// так что пинги из списка серверов сюда не попадают. Запись файла — в фоне, не в потоке netty.
+fn remember_account_server(server: &str) {
+    let Some(username) = current_account() else { return };
+    let server = server.to_string();
+    ASYNC_RUNTIME.spawn_blocking(move || {
+        let Some(manager) = GlobalState::instance().get_account_manager().get() else { return };
+        if let Err(e) = manager.lock().record_server(&username, &server) {
+            tracing::warn!("Failed to remember server for {}: {}", username, e);
+        }
+    });
+}
@@ -4,12 +4,13 @@ use crate::hooks::setup_jni_hooks;
 use jni::objects::{JClass, JObject, JString, JValue};
 use jni::{AttachGuard, JNIEnv, JavaVM};
 use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
 use std::ptr::null_mut;
 use std::sync::OnceLock;
 use jni::errors::Error;
 use winapi::um::libloaderapi::{GetModuleHandleA, GetProcAddress};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SessionInfo {
     pub username: String,
     pub player_id: String,
@@ -13,6 +13,9 @@ use winapi::{
 use crate::core::custom_payload::init_default_decoders;
 
 mod account;
+mod account_files;
+mod account_history;
+mod account_transfer;
 mod async_runtime;
 mod auth;
 mod core;
@@ -21,8 +24,10 @@ mod hooks;
 mod input;
 mod jvm;
 mod mappings;
+mod server_binding;
 mod ui;
 mod utils;
+mod vault;
 mod jni_hook;
 
 use crate::core::logging::initialize_logging;
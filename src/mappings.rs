@@ -54,6 +54,23 @@ pub mod classes {
     pub const FML_MOD_LIST_MESSAGE: &str = "cpw.mods.fml.common.network.handshake.FMLHandshakeMessage$ModList";
 
     pub const PG_HWID: &str = "ru.sky_drive.dw.pG";
+
+    pub const HANDSHAKE: &str = "net.minecraft.network.handshake.client.C00Handshake";
+
+    #[cfg(feature = "mc_1_7_10")]
+    pub const LOGIN_START: &str = "net.minecraft.network.login.client.C00PacketLoginStart";
+    #[cfg(all(feature = "mc_1_12_2", not(feature = "mc_1_7_10")))]
+    pub const LOGIN_START: &str = "net.minecraft.network.login.client.CPacketLoginStart";
+
+    #[cfg(feature = "mc_1_7_10")]
+    pub const DISCONNECT_PLAY: &str = "net.minecraft.network.play.server.S40PacketDisconnect";
+    #[cfg(all(feature = "mc_1_12_2", not(feature = "mc_1_7_10")))]
+    pub const DISCONNECT_PLAY: &str = "net.minecraft.network.play.server.SPacketDisconnect";
+
+    #[cfg(feature = "mc_1_7_10")]
+    pub const DISCONNECT_LOGIN: &str = "net.minecraft.network.login.server.S00PacketDisconnect";
+    #[cfg(all(feature = "mc_1_12_2", not(feature = "mc_1_7_10")))]
+    pub const DISCONNECT_LOGIN: &str = "net.minecraft.network.login.server.SPacketDisconnectLogin";
 }
 
 pub mod methods {
@@ -78,6 +95,13 @@ pub mod methods {
     pub const CUSTOM_PAYLOAD_GET_CHANNEL: &str = "func_149559_c";
 }
 
+pub mod fields {
+    #[cfg(feature = "mc_1_7_10")]
+    pub const LOGIN_START_PROFILE: &str = "field_149305_a";
+    #[cfg(all(feature = "mc_1_12_2", not(feature = "mc_1_7_10")))]
+    pub const LOGIN_START_PROFILE: &str = "field_149305_a";
+}
+
 pub mod signatures {
     #[cfg(feature = "mc_1_7_10")]
     pub const PACKET_ENCODER_ENCODE: &str = "(Lio/netty/channel/ChannelHandlerContext;Ljava/lang/Object;Lio/netty/buffer/ByteBuf;)V";
@@ -99,6 +123,9 @@ pub mod signatures {
     #[cfg(all(feature = "mc_1_12_2", not(feature = "mc_1_7_10")))]
     pub const PACKET_WRITE_DATA: &str = "(Lnet/minecraft/network/PacketBuffer;)V";
 
+    pub const GAME_PROFILE: &str = "Lcom/mojang/authlib/GameProfile;";
+    pub const GAME_PROFILE_INIT: &str = "(Ljava/util/UUID;Ljava/lang/String;)V";
+
     #[cfg(feature = "mc_1_7_10")]
     pub const INJECT_WORLD_ID_MAP: &str = "(Ljava/util/Map;Ljava/util/Set;Ljava/util/Map;Ljava/util/Map;Ljava/util/Set;Ljava/util/Set;ZZ)Ljava/util/List;";
 
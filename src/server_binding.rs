@@ -0,0 +1,183 @@
+use crate::account::SwitchTrigger;
+use crate::account_history::switch_session_deferred;
+use crate::core::state::GlobalState;
+use crate::jvm::get_jvm;
+use std::collections::VecDeque;
+
+/// Сколько непоказанных событий держим, если окно долго не открывают
+const MAX_PENDING_EVENTS: usize = 32;
+
+/// Что делать со следующим подключением вместо привязок
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum ConnectionOverride {
+    #[default]
+    Auto,
+    /// Остаться на текущей учётке, привязки не смотреть
+    KeepCurrent,
+    Account(String),
+}
+
+#[derive(Debug, Clone)]
+pub enum BindingEvent {
+    Applied { account: String, server: String, forced: bool },
+    Failed { account: String, server: String, error: String },
+}
+
+/// Профиль, который нужно подставить в LoginStart текущего подключения
+#[derive(Debug, Clone)]
+pub struct PendingProfile {
+    pub username: String,
+    pub player_id: String,
+}
+
+/// Привязки проверяются в потоке netty на исходящем handshake, UI только забирает события.
+pub struct ServerBindings {
+    enabled: bool,
+    next_override: ConnectionOverride,
+    pending_profile: Option<PendingProfile>,
+    events: VecDeque<BindingEvent>,
+}
+
+impl Default for ServerBindings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            next_override: ConnectionOverride::Auto,
+            pending_profile: None,
+            events: VecDeque::new(),
+        }
+    }
+}
+
+impl ServerBindings {
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    pub fn next_override(&self) -> &ConnectionOverride {
+        &self.next_override
+    }
+
+    /// Действует на одно подключение, после handshake сбрасывается в `Auto`
+    pub fn set_next_override(&mut self, value: ConnectionOverride) {
+        self.next_override = value;
+    }
+
+    pub fn drain_events(&mut self) -> Vec<BindingEvent> {
+        self.events.drain(..).collect()
+    }
+
+    pub fn take_pending_profile(&mut self) -> Option<PendingProfile> {
+        self.pending_profile.take()
+    }
+
+    fn push_event(&mut self, event: BindingEvent) {
+        self.events.push_back(event);
+        while self.events.len() > MAX_PENDING_EVENTS {
+            self.events.pop_front();
+        }
+    }
+
+    /// Вызывается в потоке netty на исходящем login-handshake до того, как игра отправит LoginStart.
+    /// Здесь только смена сессии в памяти, запись на диск уходит в фон.
+    pub fn on_handshake(&mut self, server: &str) {
+        self.pending_profile = None;
+        let choice = std::mem::take(&mut self.next_override);
+        let forced = matches!(choice, ConnectionOverride::Account(_));
+
+        if matches!(choice, ConnectionOverride::KeepCurrent) || (choice == ConnectionOverride::Auto && !self.enabled) {
+            return;
+        }
+        let Some(manager) = GlobalState::instance().get_account_manager().get() else { return };
+        let account = {
+            // Поток netty не ждёт менеджер: его может держать UI или сохранение на диск
+            let Some(manager) = manager.try_lock() else {
+                tracing::warn!("Account manager is busy, skipping server binding for {}", server);
+                if let ConnectionOverride::Account(name) = choice {
+                    self.push_event(BindingEvent::Failed {
+                        account: name,
+                        server: server.to_string(),
+                        error: "Account manager was busy".to_string(),
+                    });
+                }
+                return;
+            };
+            match &choice {
+                ConnectionOverride::KeepCurrent => None,
+                ConnectionOverride::Account(name) => manager.get_account(name).cloned(),
+                ConnectionOverride::Auto if self.enabled => manager.account_for_server(server).cloned(),
+                ConnectionOverride::Auto => None,
+            }
+        };
+        let Some(account) = account else { return };
+
+        let current = get_jvm().get_current_session();
+        if current.username == account.username && current.player_id == account.player_id {
+            return;
+        }
+
+        let session = account.to_session_info();
+        match switch_session_deferred(session, SwitchTrigger::AutoBind, Some(account.name.clone())) {
+            Ok(()) => {
+                tracing::info!("Applied account '{}' for {}", account.name, server);
+                self.pending_profile = Some(PendingProfile {
+                    username: account.username.clone(),
+                    player_id: account.player_id.clone(),
+                });
+                self.push_event(BindingEvent::Applied { account: account.name, server: server.to_string(), forced });
+            }
+            Err(error) => {
+                self.push_event(BindingEvent::Failed { account: account.name, server: server.to_string(), error });
+            }
+        }
+    }
+}
+
+/// Шаблон: `host`, `host:port` или `host:*`; в хосте допускается `*`.
+pub fn validate_pattern(pattern: &str) -> Result<(), String> {
+    let (host, port) = split_pattern(pattern);
+    if host.is_empty() {
+        return Err(format!("Server pattern '{}' has no host", pattern));
+    }
+    if let Some(port) = port {
+        if port != "*" && port.parse::<u16>().is_err() {
+            return Err(format!("Server pattern '{}' has an invalid port", pattern));
+        }
+    }
+    Ok(())
+}
+
+/// `server` — `host:port` из handshake
+pub fn pattern_matches(pattern: &str, server: &str) -> bool {
+    let (host, port) = split_pattern(pattern);
+    let (server_host, server_port) = server.rsplit_once(':').unwrap_or((server, ""));
+    let port_ok = match port {
+        None | Some("*") => true,
+        Some(port) => port == server_port,
+    };
+    port_ok && glob_matches(&host.to_lowercase(), &server_host.to_lowercase())
+}
+
+fn split_pattern(pattern: &str) -> (&str, Option<&str>) {
+    let pattern = pattern.trim();
+    match pattern.rsplit_once(':') {
+        Some((host, port)) => (host, Some(port)),
+        None => (pattern, None),
+    }
+}
+
+fn glob_matches(pattern: &str, text: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == text,
+        Some((prefix, rest)) => {
+            let Some(text) = text.strip_prefix(prefix) else { return false };
+            (0..=text.len())
+                .filter(|&i| text.is_char_boundary(i))
+                .any(|i| glob_matches(rest, &text[i..]))
+        }
+    }
+}
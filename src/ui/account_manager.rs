@@ -1,23 +1,61 @@
 use crate::{
-    account::StoredAccount,
+    account::{AccountEdit, AccountSort, StoredAccount, SwitchTrigger},
+    account_history::{previous_session, revert_to_previous, switch_session},
+    async_runtime::ASYNC_RUNTIME,
+    auth::offline_session,
+    account_transfer::{
+        export_accounts, import_accounts, plan_import, resolve_transfer_path, ImportAction, TransferFormat, TRANSFER_DIR,
+    },
+    core::account_list_settings::persist_account_list_settings,
     core::state::GlobalState,
     graphics::icon_renderer::{render_clickable_icon_with_text, render_decorative_icon},
     graphics::svg_icons::SvgIconManager,
     jvm::{get_jvm, SessionInfo},
+    server_binding::{BindingEvent, ConnectionOverride},
+    ui::authenticator::render_provider_picker,
     ui::UiState,
+    vault::VaultKey,
 };
 use egui::{Color32, Order, RichText, ScrollArea, TextEdit, Ui, Vec2};
+use once_cell::sync::Lazy;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use tokio::sync::mpsc;
+use zeroize::Zeroizing;
+
+/// Argon2 занимает около секунды: ключ выводится в фоне, без мьютекса менеджера.
+/// Экспорт и импорт тоже идут здесь — зашифрованный пакет выводит ключ так же.
+enum VaultTaskResult {
+    Unlock(Result<VaultKey, String>),
+    NewPassword(Result<VaultKey, String>),
+    Exported { path: String, count: usize, result: Result<(), String> },
+    Imported { path: String, result: Result<Vec<StoredAccount>, String> },
+}
+
+static VAULT_TASK_CHANNEL: Lazy<Mutex<(
+    mpsc::UnboundedSender<VaultTaskResult>,
+    mpsc::UnboundedReceiver<VaultTaskResult>,
+)>> = Lazy::new(|| {
+    let (tx, rx) = mpsc::unbounded_channel();
+    Mutex::new((tx, rx))
+});
 
 pub fn render_account_manager_tab(ui_state: &mut UiState, icon_manager: &mut SvgIconManager, ui: &mut Ui) {
     ui.vertical(|ui| {
         render_header_section(icon_manager, ui);
+        render_stale_storage_banner(ui_state, ui);
+        render_storage_security_section(ui_state, icon_manager, ui);
         render_current_account_section(icon_manager, ui);
         render_add_account_section(ui_state, icon_manager, ui);
+        render_import_export_section(ui_state, ui);
+        render_server_bindings_section(ui, &accounts_for_bindings());
+        render_session_history_section(ui_state, ui);
         render_accounts_list(ui_state, icon_manager, ui);
     });
 
     render_manual_input_dialog(ui_state, icon_manager, ui);
     render_edit_account_dialog(ui_state, icon_manager, ui);
+    render_import_plan_dialog(ui_state, icon_manager, ui);
 }
 
 fn render_header_section(icon_manager: &mut SvgIconManager, ui: &mut Ui) {
@@ -39,6 +77,223 @@ fn render_header_section(icon_manager: &mut SvgIconManager, ui: &mut Ui) {
     });
 }
 
+/// Другой экземпляр игры переписал sessions.json: сохранения отклоняются, пока не перечитаем
+fn render_stale_storage_banner(ui_state: &mut UiState, ui: &mut Ui) {
+    let Some(manager_mutex) = GlobalState::instance().get_account_manager().get() else { return };
+    if !manager_mutex.lock().is_stale() {
+        return;
+    }
+    ui.horizontal(|ui| {
+        ui.colored_label(Color32::YELLOW, "sessions.json was changed by another game instance.");
+        if ui.button("Reload").on_hover_text("Unsaved changes made here are discarded").clicked() {
+            *ui_state.selected_account = None;
+            if let Err(e) = manager_mutex.lock().load_accounts() {
+                ui_state.notification_manager.show_error("Load Failed", &e);
+            }
+        }
+    });
+}
+
+/// Варианты автоблокировки в минутах, 0 — выключена
+const AUTO_LOCK_CHOICES: [u32; 6] = [0, 5, 15, 30, 60, 240];
+
+fn render_storage_security_section(ui_state: &mut UiState, icon_manager: &mut SvgIconManager, ui: &mut Ui) {
+    let Some(manager_mutex) = GlobalState::instance().get_account_manager().get() else { return };
+    let (encrypted, locked, auto_lock) = {
+        let manager = manager_mutex.lock();
+        (manager.is_encrypted(), manager.is_locked(), manager.auto_lock_minutes())
+    };
+
+    ui.group(|ui| {
+        ui.vertical(|ui| {
+            ui.horizontal(|ui| {
+                render_decorative_icon(icon_manager, ui, "key", Color32::YELLOW, Some(16));
+                ui.label(RichText::new("Storage Encryption").size(16.0).color(Color32::YELLOW));
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if locked {
+                        ui.colored_label(Color32::LIGHT_RED, "Locked");
+                    } else if encrypted {
+                        ui.colored_label(Color32::LIGHT_GREEN, "Encrypted");
+                    } else {
+                        ui.colored_label(Color32::GRAY, "Plain text");
+                    }
+                });
+            });
+
+            ui.separator();
+
+            if locked {
+                ui.horizontal(|ui| {
+                    ui.label("Master Password:");
+                    let resp = ui.add(
+                        TextEdit::singleline(ui_state.vault_password_input)
+                            .password(true)
+                            .desired_width(200.0),
+                    );
+                    let submit = resp.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                    if *ui_state.vault_busy {
+                        ui.spinner();
+                    } else if ui.button("Unlock").clicked() || submit {
+                        let password = Zeroizing::new(std::mem::take(ui_state.vault_password_input));
+                        match manager_mutex.lock().locked_kdf() {
+                            Ok(kdf) => spawn_vault_task(ui_state, move || {
+                                VaultTaskResult::Unlock(VaultKey::derive(&password, kdf))
+                            }),
+                            Err(e) => {
+                                ui_state.notification_manager.show_error("Unlock Failed", &e);
+                            }
+                        }
+                    }
+                });
+                return;
+            }
+
+            if !encrypted {
+                ui.colored_label(
+                    Color32::YELLOW,
+                    "sessions.json keeps access tokens and passwords in plain text.",
+                );
+            }
+
+            ui.horizontal(|ui| {
+                ui.label(if encrypted { "New Password:" } else { "Master Password:" });
+                ui.add(TextEdit::singleline(ui_state.vault_new_password).password(true).desired_width(140.0));
+                ui.label("Confirm:");
+                ui.add(TextEdit::singleline(ui_state.vault_confirm_password).password(true).desired_width(140.0));
+
+                let label = if encrypted { "Change Password" } else { "Encrypt" };
+                if *ui_state.vault_busy {
+                    ui.spinner();
+                } else if ui.button(label).clicked() {
+                    if ui_state.vault_new_password.is_empty() {
+                        ui_state.notification_manager.show_error("Validation Error", "Please enter a master password");
+                    } else if ui_state.vault_new_password != ui_state.vault_confirm_password {
+                        ui_state.notification_manager.show_error("Validation Error", "Passwords do not match");
+                    } else {
+                        let password = Zeroizing::new(ui_state.vault_new_password.clone());
+                        spawn_vault_task(ui_state, move || VaultTaskResult::NewPassword(VaultKey::create(&password)));
+                    }
+                }
+            });
+
+            if !encrypted {
+                return;
+            }
+
+            ui.horizontal(|ui| {
+                ui.label("Auto-lock:");
+                let text = |m: u32| if m == 0 { "Never".to_string() } else { format!("after {} min idle", m) };
+                let mut selected = auto_lock;
+                egui::ComboBox::from_id_salt("account_auto_lock")
+                    .selected_text(text(selected))
+                    .show_ui(ui, |ui| {
+                        for m in AUTO_LOCK_CHOICES {
+                            ui.selectable_value(&mut selected, m, text(m));
+                        }
+                    });
+                if selected != auto_lock {
+                    if let Err(e) = manager_mutex.lock().set_auto_lock_minutes(selected) {
+                        ui_state.notification_manager.show_error("Save Failed", &e);
+                    }
+                }
+
+                ui.separator();
+
+                if ui.button("Lock Now").clicked() {
+                    manager_mutex.lock().lock();
+                    *ui_state.selected_account = None;
+                }
+                if ui.button("Remove Encryption").on_hover_text("Write sessions.json back in plain text").clicked() {
+                    if let Err(e) = manager_mutex.lock().remove_master_password() {
+                        ui_state.notification_manager.show_error("Decryption Failed", &e);
+                    } else {
+                        ui_state.notification_manager.show_warning("Encryption Removed", "sessions.json is stored in plain text");
+                    }
+                }
+            });
+        });
+    });
+}
+
+fn spawn_vault_task<F>(ui_state: &mut UiState, task: F)
+where
+    F: FnOnce() -> VaultTaskResult + Send + 'static,
+{
+    *ui_state.vault_busy = true;
+    let sender = VAULT_TASK_CHANNEL.lock().unwrap().0.clone();
+    ASYNC_RUNTIME.spawn_blocking(move || {
+        let _ = sender.send(task());
+    });
+}
+
+/// Каждый кадр: готовый ключ ставится в менеджер уже в потоке UI
+pub fn poll_vault_task(ui_state: &mut UiState) {
+    let Ok(mut guard) = VAULT_TASK_CHANNEL.try_lock() else { return };
+    let Ok(result) = guard.1.try_recv() else { return };
+    drop(guard);
+    *ui_state.vault_busy = false;
+    let Some(manager_mutex) = GlobalState::instance().get_account_manager().get() else { return };
+
+    match result {
+        VaultTaskResult::Unlock(key) => match key.and_then(|key| manager_mutex.lock().unlock(key)) {
+            Ok(_) => {
+                ui_state.notification_manager.show_success("Unlocked", "Saved accounts are available");
+            }
+            Err(e) => {
+                ui_state.notification_manager.show_error("Unlock Failed", &e);
+            }
+        },
+        VaultTaskResult::NewPassword(key) => match key.and_then(|key| manager_mutex.lock().set_master_key(key)) {
+            Ok(_) => {
+                ui_state.notification_manager.show_success("Storage Encrypted", "sessions.json is now encrypted");
+                ui_state.vault_new_password.clear();
+                ui_state.vault_confirm_password.clear();
+            }
+            Err(e) => {
+                ui_state.notification_manager.show_error("Encryption Failed", &e);
+            }
+        },
+        VaultTaskResult::Exported { path, count, result } => match result {
+            Ok(_) => {
+                ui_state.notification_manager.show_success(
+                    "Accounts Exported",
+                    &format!("{} accounts written to {}", count, path),
+                );
+            }
+            Err(e) => {
+                ui_state.notification_manager.show_error("Export Failed", &e);
+            }
+        },
+        VaultTaskResult::Imported { path, result } => match result {
+            Ok(incoming) if incoming.is_empty() => {
+                ui_state.notification_manager.show_warning("Nothing to Import", &format!("No accounts found in {}", path));
+            }
+            Ok(incoming) => {
+                let existing = manager_mutex.lock().get_all_accounts();
+                *ui_state.account_import_plan = plan_import(incoming, &existing);
+            }
+            Err(e) => {
+                ui_state.notification_manager.show_error("Import Failed", &e);
+            }
+        },
+    }
+}
+
+/// Каждый кадр с открытым меню: любой ввод продлевает сессию. Саму блокировку делает хук swap buffers.
+pub fn check_account_auto_lock(ctx: &egui::Context, ui_state: &mut UiState) {
+    let Some(manager_mutex) = GlobalState::instance().get_account_manager().get() else { return };
+    let mut manager = manager_mutex.lock();
+    if manager.take_auto_locked() {
+        drop(manager);
+        *ui_state.selected_account = None;
+        ui_state.notification_manager.show_info("Accounts Locked", "Locked after inactivity");
+        return;
+    }
+    if ctx.input(|i| !i.events.is_empty()) {
+        manager.touch();
+    }
+}
+
 fn render_current_account_section(icon_manager: &mut SvgIconManager, ui: &mut Ui) {
     ui.group(|ui| {
         ui.vertical(|ui| {
@@ -117,11 +372,452 @@ fn render_add_account_section(ui_state: &mut UiState, icon_manager: &mut SvgIcon
                     ui_state.manual_access_token.clear();
                     *ui_state.manual_session_type = "mojang".to_string();
                 }
+
+                ui.separator();
+
+                if render_clickable_icon_with_text(
+                    icon_manager,
+                    ui,
+                    "user",
+                    "Create Offline",
+                    Color32::LIGHT_BLUE,
+                    Some(16),
+                    "Create an offline account: the name is used as the nickname, UUID is derived from it"
+                ).clicked() {
+                    create_offline_account(ui_state);
+                }
+            });
+        });
+    });
+}
+
+fn create_offline_account(ui_state: &mut UiState) {
+    let username = ui_state.account_name_input.trim().to_string();
+    if username.is_empty() {
+        ui_state.notification_manager.show_error("Validation Error", "Please enter a nickname in Account Name");
+        return;
+    }
+    let Some(manager_mutex) = GlobalState::instance().get_account_manager().get() else { return };
+
+    let result = manager_mutex.lock().add_offline_account(username.clone(), &username);
+    match result {
+        Ok(_) => {
+            ui_state.notification_manager.show_success(
+                "Offline Account Added",
+                &format!("Account '{}' added with offline UUID", username)
+            );
+            ui_state.account_name_input.clear();
+        }
+        Err(e) => {
+            ui_state.notification_manager.show_error("Add Failed", &format!("Failed to add account: {}", e));
+        }
+    }
+}
+
+fn accounts_for_bindings() -> Vec<StoredAccount> {
+    GlobalState::instance()
+        .get_account_manager()
+        .get()
+        .map(|m| m.lock())
+        .filter(|m| !m.is_locked())
+        .map(|m| m.get_all_accounts())
+        .unwrap_or_default()
+}
+
+fn render_server_bindings_section(ui: &mut Ui, accounts: &[StoredAccount]) {
+    let Some(bindings) = GlobalState::instance().get_server_bindings().get() else { return };
+
+    egui::CollapsingHeader::new("Server Bindings")
+        .id_salt("account_server_bindings")
+        .default_open(false)
+        .show(ui, |ui| {
+            let mut enabled = bindings.lock().is_enabled();
+            if ui
+                .checkbox(&mut enabled, "Switch accounts automatically on connect")
+                .on_hover_text("Uses the server patterns set in Edit Account")
+                .changed()
+            {
+                bindings.lock().set_enabled(enabled);
+            }
+
+            ui.horizontal(|ui| {
+                ui.label("Next connection:");
+                let mut choice = bindings.lock().next_override().clone();
+                let selected = match &choice {
+                    ConnectionOverride::Auto => "Use bindings".to_string(),
+                    ConnectionOverride::KeepCurrent => "Keep current account".to_string(),
+                    ConnectionOverride::Account(name) => name.clone(),
+                };
+                egui::ComboBox::from_id_salt("account_next_connection")
+                    .selected_text(selected)
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut choice, ConnectionOverride::Auto, "Use bindings");
+                        ui.selectable_value(&mut choice, ConnectionOverride::KeepCurrent, "Keep current account");
+                        for account in accounts {
+                            ui.selectable_value(
+                                &mut choice,
+                                ConnectionOverride::Account(account.name.clone()),
+                                account.name.as_str(),
+                            );
+                        }
+                    });
+                if choice != *bindings.lock().next_override() {
+                    bindings.lock().set_next_override(choice);
+                }
+            });
+            ui.colored_label(Color32::GRAY, "The override applies to one connection, then bindings are used again.");
+
+            ui.separator();
+            let bound: Vec<&StoredAccount> = accounts.iter().filter(|a| !a.servers.is_empty()).collect();
+            if bound.is_empty() {
+                ui.colored_label(Color32::GRAY, "No bindings. Add server patterns in Edit Account.");
+            }
+            egui::Grid::new("account_bindings_grid").num_columns(2).striped(true).show(ui, |ui| {
+                for account in bound {
+                    ui.label(RichText::new(&account.name).strong());
+                    ui.label(account.servers.join(", "));
+                    ui.end_row();
+                }
+            });
+        });
+}
+
+fn render_session_history_section(ui_state: &mut UiState, ui: &mut Ui) {
+    let Some(manager_mutex) = GlobalState::instance().get_account_manager().get() else { return };
+
+    egui::CollapsingHeader::new("Session History")
+        .id_salt("account_session_history")
+        .default_open(false)
+        .show(ui, |ui| {
+            let previous = previous_session();
+            ui.horizontal(|ui| {
+                let hover = match &previous {
+                    Some(session) => format!("Switch back to {}", session.username),
+                    None => "No previous session recorded".to_string(),
+                };
+                if ui
+                    .add_enabled(previous.is_some(), egui::Button::new("Revert to previous session"))
+                    .on_hover_text(hover)
+                    .clicked()
+                {
+                    match revert_to_previous() {
+                        Ok(session) => {
+                            ui_state
+                                .notification_manager
+                                .show_success("Session Reverted", &format!("Switched back to {}", session.username));
+                        }
+                        Err(e) => {
+                            ui_state.notification_manager.show_error("Revert Failed", &e);
+                        }
+                    }
+                }
+                if ui.button("Clear history").clicked() {
+                    if let Err(e) = manager_mutex.lock().clear_history() {
+                        ui_state.notification_manager.show_error("Clear Failed", &e);
+                    }
+                }
+            });
+
+            let history: Vec<_> = manager_mutex.lock().history().iter().rev().cloned().collect();
+            if history.is_empty() {
+                ui.colored_label(Color32::GRAY, "No session changes recorded yet.");
+                return;
+            }
+
+            ScrollArea::vertical().id_salt("account_history_scroll").max_height(200.0).show(ui, |ui| {
+                egui::Grid::new("account_history_grid").num_columns(4).striped(true).show(ui, |ui| {
+                    for entry in &history {
+                        ui.label(RichText::new(entry.format_time()).color(Color32::GRAY));
+                        ui.label(entry.trigger.label());
+                        let from = entry.from_account.clone().unwrap_or_else(|| entry.previous.username.clone());
+                        let to = entry.to_account.clone().unwrap_or_else(|| entry.to_username.clone());
+                        ui.label(format!("{} → {}", from, to));
+                        match &entry.error {
+                            None => ui.colored_label(Color32::LIGHT_GREEN, "OK"),
+                            Some(e) => ui.colored_label(Color32::LIGHT_RED, "Failed").on_hover_text(e),
+                        };
+                        ui.end_row();
+                    }
+                });
             });
         });
+}
+
+/// Уведомления о смене учётки по привязке; сама смена происходит в потоке netty
+pub fn check_server_binding_events(ui_state: &mut UiState) {
+    let Some(bindings) = GlobalState::instance().get_server_bindings().get() else { return };
+    let events = bindings.lock().drain_events();
+    for event in events {
+        match event {
+            BindingEvent::Applied { account, server, forced } => {
+                let how = if forced { "override" } else { "binding" };
+                ui_state
+                    .notification_manager
+                    .show_info("Account Applied", &format!("'{}' for {} ({})", account, server, how));
+                *ui_state.selected_account = Some(account);
+            }
+            BindingEvent::Failed { account, server, error } => {
+                ui_state
+                    .notification_manager
+                    .show_error("Binding Failed", &format!("'{}' for {}: {}", account, server, error));
+            }
+        }
+    }
+}
+
+fn render_import_export_section(ui_state: &mut UiState, ui: &mut Ui) {
+    egui::CollapsingHeader::new("Import / Export")
+        .id_salt("account_import_export")
+        .default_open(false)
+        .show(ui, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Format:");
+                let before = *ui_state.account_transfer_format;
+                egui::ComboBox::from_id_salt("account_transfer_format")
+                    .selected_text(ui_state.account_transfer_format.label())
+                    .show_ui(ui, |ui| {
+                        for format in TransferFormat::ALL {
+                            ui.selectable_value(ui_state.account_transfer_format, format, format.label());
+                        }
+                    });
+                // подставляем имя файла по умолчанию, пока пользователь не ввёл своё
+                if *ui_state.account_transfer_format != before
+                    && (ui_state.account_transfer_path.is_empty()
+                        || ui_state.account_transfer_path.as_str() == before.default_file())
+                {
+                    *ui_state.account_transfer_path = ui_state.account_transfer_format.default_file().to_string();
+                }
+            });
+
+            ui.horizontal(|ui| {
+                ui.label("File:");
+                ui.add(
+                    TextEdit::singleline(ui_state.account_transfer_path)
+                        .hint_text(ui_state.account_transfer_format.default_file())
+                        .desired_width(320.0),
+                )
+                .on_hover_text(format!("Relative paths are inside {}/", TRANSFER_DIR));
+            });
+
+            if ui_state.account_transfer_format.needs_password() {
+                ui.horizontal(|ui| {
+                    ui.label("Bundle Password:");
+                    ui.add(
+                        TextEdit::singleline(ui_state.account_transfer_password)
+                            .password(true)
+                            .desired_width(200.0),
+                    );
+                });
+            }
+
+            ui.horizontal(|ui| {
+                if *ui_state.vault_busy {
+                    ui.spinner();
+                    return;
+                }
+                if ui.button("Export All").clicked() {
+                    export_all_accounts(ui_state, None);
+                }
+                if ui
+                    .button("Import")
+                    .on_hover_text("The format is detected from the file when possible")
+                    .clicked()
+                {
+                    load_import_file(ui_state);
+                }
+            });
+
+            if let Some(path) = ui_state.account_export_confirm.clone() {
+                ui.horizontal(|ui| {
+                    ui.colored_label(Color32::YELLOW, format!("{} already exists.", path));
+                    if ui.add_enabled(!*ui_state.vault_busy, egui::Button::new("Overwrite")).clicked() {
+                        *ui_state.account_export_confirm = None;
+                        export_all_accounts(ui_state, Some(&path));
+                    }
+                    if ui.button("Cancel").clicked() {
+                        *ui_state.account_export_confirm = None;
+                    }
+                });
+            }
+
+            let format = *ui_state.account_transfer_format;
+            if !format.needs_password() {
+                let secrets = if format.stores_passwords() { "access tokens and passwords" } else { "access tokens" };
+                ui.colored_label(Color32::YELLOW, format!("This format stores {} in plain text.", secrets));
+            }
+        });
+}
+
+fn transfer_path(ui_state: &UiState) -> String {
+    let path = ui_state.account_transfer_path.trim();
+    let path = if path.is_empty() { ui_state.account_transfer_format.default_file() } else { path };
+    resolve_transfer_path(path).display().to_string()
+}
+
+/// Существующий файл перезаписывается, только если именно этот путь подтверждён
+fn export_all_accounts(ui_state: &mut UiState, confirmed: Option<&str>) {
+    let Some(manager_mutex) = GlobalState::instance().get_account_manager().get() else { return };
+    let accounts = manager_mutex.lock().get_all_accounts();
+    if accounts.is_empty() {
+        ui_state.notification_manager.show_error("Export Failed", "There are no accounts to export");
+        return;
+    }
+
+    let format = *ui_state.account_transfer_format;
+    let path = transfer_path(ui_state);
+    if confirmed != Some(path.as_str()) && std::path::Path::new(&path).exists() {
+        *ui_state.account_export_confirm = Some(path);
+        return;
+    }
+    let password = Zeroizing::new(ui_state.account_transfer_password.clone());
+    spawn_vault_task(ui_state, move || {
+        let result = export_accounts(&accounts, format, &password).and_then(|bytes| {
+            if let Some(dir) = std::path::Path::new(&path).parent() {
+                std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+            }
+            std::fs::write(&path, bytes).map_err(|e| format!("Failed to write {}: {}", path, e))
+        });
+        VaultTaskResult::Exported { path, count: accounts.len(), result }
+    });
+}
+
+/// Читает файл; план импорта строится, когда фоновый разбор закончится, сам импорт — после подтверждения
+fn load_import_file(ui_state: &mut UiState) {
+    let path = transfer_path(ui_state);
+    let bytes = match std::fs::read(&path) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            ui_state.notification_manager.show_error("Import Failed", &format!("Failed to read {}: {}", path, e));
+            return;
+        }
+    };
+
+    let format = TransferFormat::detect(&bytes).unwrap_or(*ui_state.account_transfer_format);
+    if format.needs_password() && ui_state.account_transfer_password.is_empty() {
+        *ui_state.account_transfer_format = format;
+        ui_state.notification_manager.show_error("Import Failed", "This bundle is encrypted, enter its password");
+        return;
+    }
+
+    let password = Zeroizing::new(ui_state.account_transfer_password.clone());
+    spawn_vault_task(ui_state, move || VaultTaskResult::Imported {
+        result: import_accounts(&bytes, format, &password),
+        path,
     });
 }
 
+fn render_import_plan_dialog(ui_state: &mut UiState, icon_manager: &mut SvgIconManager, ui: &mut Ui) {
+    if ui_state.account_import_plan.is_empty() {
+        return;
+    }
+
+    let mut apply = false;
+    let mut cancel = false;
+
+    egui::Window::new("Import Accounts")
+        .collapsible(false)
+        .resizable(true)
+        .order(Order::Foreground)
+        .default_size([520.0, 360.0])
+        .show(ui.ctx(), |ui| {
+            let conflicts = ui_state.account_import_plan.iter().filter(|c| c.conflict.is_some()).count();
+            ui.label(format!(
+                "{} accounts in file, {} conflict with saved ones.",
+                ui_state.account_import_plan.len(),
+                conflicts
+            ));
+
+            ui.horizontal(|ui| {
+                ui.label("All conflicts:");
+                for action in [ImportAction::Merge, ImportAction::Rename, ImportAction::Skip] {
+                    if ui.small_button(action.label()).clicked() {
+                        for c in ui_state.account_import_plan.iter_mut().filter(|c| c.conflict.is_some()) {
+                            c.action = action;
+                        }
+                    }
+                }
+            });
+            ui.separator();
+
+            ScrollArea::vertical().max_height(240.0).show(ui, |ui| {
+                egui::Grid::new("account_import_plan").striped(true).show(ui, |ui| {
+                    for (idx, c) in ui_state.account_import_plan.iter_mut().enumerate() {
+                        ui.colored_label(Color32::WHITE, &c.account.name);
+                        ui.colored_label(Color32::LIGHT_BLUE, &c.account.username);
+                        match &c.conflict {
+                            Some((existing, kind)) => {
+                                ui.colored_label(Color32::YELLOW, format!("{} as '{}'", kind.label(), existing));
+                                egui::ComboBox::from_id_salt(("account_import_action", idx))
+                                    .selected_text(c.action.label())
+                                    .show_ui(ui, |ui| {
+                                        for action in [ImportAction::Merge, ImportAction::Rename, ImportAction::Skip] {
+                                            ui.selectable_value(&mut c.action, action, action.label());
+                                        }
+                                    });
+                            }
+                            None => {
+                                ui.colored_label(Color32::LIGHT_GREEN, "new");
+                                let mut add = c.action == ImportAction::Add;
+                                if ui.checkbox(&mut add, "Add").changed() {
+                                    c.action = if add { ImportAction::Add } else { ImportAction::Skip };
+                                }
+                            }
+                        }
+                        ui.end_row();
+                    }
+                });
+            });
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                if render_clickable_icon_with_text(
+                    icon_manager,
+                    ui,
+                    "apply",
+                    "Import",
+                    Color32::LIGHT_GREEN,
+                    Some(16),
+                    "Import with the chosen actions"
+                ).clicked() {
+                    apply = true;
+                }
+
+                ui.separator();
+
+                if render_clickable_icon_with_text(
+                    icon_manager,
+                    ui,
+                    "cancel",
+                    "Cancel",
+                    Color32::RED,
+                    Some(16),
+                    "Close without importing"
+                ).clicked() {
+                    cancel = true;
+                }
+            });
+        });
+
+    if apply {
+        let Some(manager_mutex) = GlobalState::instance().get_account_manager().get() else { return };
+        let result = manager_mutex.lock().apply_import(ui_state.account_import_plan);
+        match result {
+            Ok((added, merged, skipped)) => {
+                ui_state.notification_manager.show_success(
+                    "Accounts Imported",
+                    &format!("{} added, {} merged, {} skipped", added, merged, skipped),
+                );
+                ui_state.account_import_plan.clear();
+            }
+            Err(e) => {
+                ui_state.notification_manager.show_error("Import Failed", &e);
+            }
+        }
+    } else if cancel {
+        ui_state.account_import_plan.clear();
+    }
+}
+
 fn render_manual_input_dialog(ui_state: &mut UiState, icon_manager: &mut SvgIconManager, ui: &mut Ui) {
     if !*ui_state.show_manual_input_dialog {
         return;
@@ -166,6 +862,22 @@ fn render_manual_input_dialog(ui_state: &mut UiState, icon_manager: &mut SvgIcon
                 ui.horizontal(|ui| {
                     ui.label("Player ID:");
                     ui.text_edit_singleline(ui_state.manual_player_id);
+                    if ui
+                        .small_button("Offline")
+                        .on_hover_text("Fill player ID, token and session type for an offline server from the username")
+                        .clicked()
+                    {
+                        match offline_session(ui_state.manual_username) {
+                            Ok(session) => {
+                                *ui_state.manual_player_id = session.player_id;
+                                *ui_state.manual_access_token = session.access_token;
+                                *ui_state.manual_session_type = session.session_type;
+                            }
+                            Err(e) => {
+                                ui_state.notification_manager.show_error("Validation Error", &e);
+                            }
+                        }
+                    }
                 });
 
                 ui.horizontal(|ui| {
@@ -280,6 +992,32 @@ fn render_edit_account_dialog(ui_state: &mut UiState, icon_manager: &mut SvgIcon
                         });
                 });
 
+                ui.horizontal(|ui| {
+                    ui.label("Provider:");
+                    render_provider_picker(ui, "edit_account_provider", ui_state.edit_provider);
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label("Group:");
+                    ui.add(egui::TextEdit::singleline(ui_state.edit_group).hint_text("no group"));
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label("Tags:");
+                    ui.add(egui::TextEdit::singleline(ui_state.edit_tags).hint_text("comma separated"));
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label("Servers:");
+                    ui.add(egui::TextEdit::singleline(ui_state.edit_servers).hint_text("host:port, *.example.net"))
+                        .on_hover_text("Switch to this account automatically when connecting to a matching server");
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label("Notes:");
+                    ui.add(egui::TextEdit::multiline(ui_state.edit_notes).desired_rows(3));
+                });
+
                 ui.separator();
 
                 ui.horizontal(|ui| {
@@ -428,6 +1166,29 @@ fn render_account_info_aligned(
                 }
             );
         });
+
+        if !account.tags.is_empty() || !account.notes.is_empty() || !account.servers.is_empty() {
+            ui.add_space(4.0);
+            ui.horizontal_wrapped(|ui| {
+                for server in &account.servers {
+                    ui.label(RichText::new(format!("@{}", server)).small().color(Color32::LIGHT_GREEN))
+                        .on_hover_text("Applied automatically on connect");
+                }
+                for tag in &account.tags {
+                    ui.label(RichText::new(format!("#{}", tag)).small().color(Color32::LIGHT_BLUE));
+                }
+                if !account.notes.is_empty() {
+                    let first_line = account.notes.lines().next().unwrap_or_default();
+                    let preview = if first_line.chars().count() > 40 {
+                        format!("{}...", first_line.chars().take(37).collect::<String>())
+                    } else {
+                        first_line.to_string()
+                    };
+                    ui.label(RichText::new(preview).small().italics().color(Color32::GRAY))
+                        .on_hover_text(&account.notes);
+                }
+            });
+        }
     });
 }
 
@@ -448,6 +1209,23 @@ fn render_accounts_list(ui_state: &mut UiState, icon_manager: &mut SvgIconManage
                     manager.get_all_accounts()
                 };
 
+                let load_error = manager_mutex.lock().load_error().map(str::to_string);
+                if let Some(error) = load_error {
+                    render_restore_backup_section(ui_state, ui, &error);
+                    return;
+                }
+
+                if manager_mutex.lock().is_locked() {
+                    ui.vertical_centered(|ui| {
+                        ui.add_space(20.0);
+                        render_decorative_icon(icon_manager, ui, "key", Color32::GRAY, Some(24));
+                        ui.add_space(8.0);
+                        ui.colored_label(Color32::GRAY, "Accounts are locked. Enter the master password above.");
+                        ui.add_space(20.0);
+                    });
+                    return;
+                }
+
                 if accounts.is_empty() {
                     ui.vertical_centered(|ui| {
                         ui.add_space(20.0);
@@ -459,97 +1237,333 @@ fn render_accounts_list(ui_state: &mut UiState, icon_manager: &mut SvgIconManage
                     return;
                 }
 
-                let mut account_to_delete: Option<String> = None;
-                let mut account_to_use: Option<String> = None;
-                let mut account_to_copy: Option<String> = None;
-                let mut account_to_edit: Option<String> = None;
+                render_account_filter_bar(ui_state, ui, &accounts);
+                ui.add_space(4.0);
+
+                let mut accounts = accounts;
+                ui_state.account_sort.sort(&mut accounts, *ui_state.account_sort_desc);
+                let visible: Vec<&StoredAccount> = accounts
+                    .iter()
+                    .filter(|a| a.matches_search(ui_state.account_search))
+                    .filter(|a| !*ui_state.account_favorites_only || a.favorite)
+                    .filter(|a| ui_state.account_filter_tag.is_empty() || a.tags.contains(ui_state.account_filter_tag))
+                    .filter(|a| ui_state.account_filter_password.is_none_or(|saved| saved != a.password.is_empty()))
+                    .filter(|a| ui_state.account_filter_session.is_empty() || a.session_type == *ui_state.account_filter_session)
+                    .collect();
+
+                let mut actions = AccountActions::default();
 
                 ScrollArea::vertical()
                     .max_height(300.0)
                     .show(ui, |ui| {
-                        for (index, account) in accounts.iter().enumerate() {
-
-                            if index > 0 {
-                                ui.add_space(8.0);
-                            }
-
-                            ui.group(|ui| {
-                                ui.horizontal(|ui| {
-
-                                    render_account_info_aligned(icon_manager, ui, account);
-
-                                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-
-                                        if render_clickable_icon_with_text(
-                                            icon_manager,
-                                            ui,
-                                            "delete",
-                                            "Delete",
-                                            Color32::RED,
-                                            Some(16),
-                                            "Delete this account"
-                                        ).clicked() {
-                                            account_to_delete = Some(account.name.clone());
-                                        }
+                        if visible.is_empty() {
+                            ui.colored_label(Color32::GRAY, "No accounts match the filter.");
+                        }
 
-                                        if render_clickable_icon_with_text(
-                                            icon_manager,
-                                            ui,
-                                            "edit",
-                                            "Edit",
-                                            Color32::YELLOW,
-                                            Some(16),
-                                            "Edit this account"
-                                        ).clicked() {
-                                            account_to_edit = Some(account.name.clone());
-                                        }
+                        if !*ui_state.account_group_by_folder {
+                            let mut sorted = visible.clone();
+                            sorted.sort_by_key(|a| !a.favorite);
+                            render_account_rows(icon_manager, ui, &sorted, &mut actions);
+                            return;
+                        }
 
-                                        if render_clickable_icon_with_text(
-                                            icon_manager,
-                                            ui,
-                                            "refresh",
-                                            "Use",
-                                            Color32::LIGHT_GREEN,
-                                            Some(16),
-                                            "Switch to this account"
-                                        ).clicked() {
-                                            account_to_use = Some(account.name.clone());
-                                        }
+                        // Группы по алфавиту, учётки без группы — в конце
+                        let mut groups: BTreeMap<&str, Vec<&StoredAccount>> = BTreeMap::new();
+                        let mut ungrouped = Vec::new();
+                        for account in &visible {
+                            match account.group.as_deref() {
+                                Some(group) => groups.entry(group).or_default().push(*account),
+                                None => ungrouped.push(*account),
+                            }
+                        }
 
-                                        if render_clickable_icon_with_text(
-                                            icon_manager,
-                                            ui,
-                                            "copy",
-                                            "Copy",
-                                            Color32::LIGHT_BLUE,
-                                            Some(16),
-                                            "Copy account data to clipboard"
-                                        ).clicked() {
-                                            account_to_copy = Some(account.name.clone());
-                                        }
-                                    });
-                                });
+                        for (group, mut members) in groups {
+                            members.sort_by_key(|a| !a.favorite);
+                            egui::CollapsingHeader::new(
+                                RichText::new(format!("{} ({})", group, members.len())).strong(),
+                            )
+                            .id_salt(("account_group", group))
+                            .default_open(true)
+                            .show(ui, |ui| {
+                                render_account_rows(icon_manager, ui, &members, &mut actions);
+                            });
+                        }
+                        if !ungrouped.is_empty() {
+                            ungrouped.sort_by_key(|a| !a.favorite);
+                            egui::CollapsingHeader::new(
+                                RichText::new(format!("Ungrouped ({})", ungrouped.len())).strong(),
+                            )
+                            .id_salt("account_group_none")
+                            .default_open(true)
+                            .show(ui, |ui| {
+                                render_account_rows(icon_manager, ui, &ungrouped, &mut actions);
                             });
                         }
                     });
 
-                if let Some(account_name) = account_to_delete {
+                if let Some(account_name) = actions.delete {
                     delete_account(&account_name, ui_state);
                 }
-                if let Some(account_name) = account_to_use {
+                if let Some(account_name) = actions.use_account {
                     use_account(&account_name, ui_state);
                 }
-                if let Some(account_name) = account_to_copy {
+                if let Some(account_name) = actions.copy {
                     copy_account_to_clipboard(&account_name, ui_state);
                 }
-                if let Some(account_name) = account_to_edit {
+                if let Some(account_name) = actions.edit {
                     edit_account(&account_name, ui_state);
                 }
+                if let Some((account_name, favorite)) = actions.favorite {
+                    if let Err(e) = manager_mutex.lock().set_favorite(&account_name, favorite) {
+                        ui_state.notification_manager.show_error("Update Failed", &e);
+                    }
+                }
+
+                if let Err(e) = persist_account_list_settings(ui_state) {
+                    tracing::warn!("Failed to save account list settings: {}", e);
+                }
             }
         });
     });
 }
 
+/// Что нажато в строках списка; применяется после отрисовки, когда список уже не заимствован
+#[derive(Default)]
+struct AccountActions {
+    delete: Option<String>,
+    use_account: Option<String>,
+    copy: Option<String>,
+    edit: Option<String>,
+    favorite: Option<(String, bool)>,
+}
+
+fn render_account_filter_bar(ui_state: &mut UiState, ui: &mut Ui, accounts: &[StoredAccount]) {
+    let mut tags: Vec<&str> = accounts.iter().flat_map(|a| a.tags.iter().map(String::as_str)).collect();
+    tags.sort_unstable();
+    tags.dedup();
+    if !ui_state.account_filter_tag.is_empty() && !tags.contains(&ui_state.account_filter_tag.as_str()) {
+        ui_state.account_filter_tag.clear();
+    }
+
+    let mut session_types: Vec<&str> = ["mojang", "legacy"]
+        .into_iter()
+        .chain(accounts.iter().map(|a| a.session_type.as_str()))
+        .collect();
+    session_types.sort_unstable();
+    session_types.dedup();
+
+    ui.horizontal(|ui| {
+        ui.label("Search:");
+        ui.add(
+            TextEdit::singleline(ui_state.account_search)
+                .hint_text("name, username, UUID or tag")
+                .desired_width(220.0),
+        );
+        if !ui_state.account_search.is_empty() && ui.small_button("x").on_hover_text("Clear search").clicked() {
+            ui_state.account_search.clear();
+        }
+
+        ui.separator();
+        ui.label("Sort:");
+        egui::ComboBox::from_id_salt("account_sort")
+            .selected_text(ui_state.account_sort.label())
+            .show_ui(ui, |ui| {
+                for sort in AccountSort::ALL {
+                    ui.selectable_value(ui_state.account_sort, sort, sort.label());
+                }
+            });
+        let (arrow, hover) = if *ui_state.account_sort_desc { ("⬇", "Descending") } else { ("⬆", "Ascending") };
+        if ui.button(arrow).on_hover_text(hover).clicked() {
+            *ui_state.account_sort_desc = !*ui_state.account_sort_desc;
+        }
+    });
+
+    ui.horizontal(|ui| {
+        ui.checkbox(ui_state.account_group_by_folder, "Group by folder");
+        ui.checkbox(ui_state.account_favorites_only, "Favorites only");
+
+        ui.label("Password:");
+        let password_label = match *ui_state.account_filter_password {
+            None => "Any",
+            Some(true) => "Saved",
+            Some(false) => "Not saved",
+        };
+        egui::ComboBox::from_id_salt("account_filter_password")
+            .selected_text(password_label)
+            .show_ui(ui, |ui| {
+                ui.selectable_value(ui_state.account_filter_password, None, "Any");
+                ui.selectable_value(ui_state.account_filter_password, Some(true), "Saved");
+                ui.selectable_value(ui_state.account_filter_password, Some(false), "Not saved");
+            });
+
+        ui.label("Session:");
+        let selected = if ui_state.account_filter_session.is_empty() { "Any" } else { ui_state.account_filter_session.as_str() };
+        egui::ComboBox::from_id_salt("account_filter_session")
+            .selected_text(selected.to_string())
+            .show_ui(ui, |ui| {
+                ui.selectable_value(ui_state.account_filter_session, String::new(), "Any");
+                for session_type in &session_types {
+                    ui.selectable_value(ui_state.account_filter_session, session_type.to_string(), *session_type);
+                }
+            });
+
+        ui.label("Tag:");
+        let selected = if ui_state.account_filter_tag.is_empty() { "Any" } else { ui_state.account_filter_tag.as_str() };
+        egui::ComboBox::from_id_salt("account_filter_tag")
+            .selected_text(selected.to_string())
+            .show_ui(ui, |ui| {
+                ui.selectable_value(ui_state.account_filter_tag, String::new(), "Any");
+                for tag in &tags {
+                    ui.selectable_value(ui_state.account_filter_tag, tag.to_string(), *tag);
+                }
+            });
+    });
+}
+
+fn render_account_rows(
+    icon_manager: &mut SvgIconManager,
+    ui: &mut Ui,
+    accounts: &[&StoredAccount],
+    actions: &mut AccountActions,
+) {
+    for (index, account) in accounts.iter().enumerate() {
+        if index > 0 {
+            ui.add_space(8.0);
+        }
+
+        ui.group(|ui| {
+            ui.horizontal(|ui| {
+                let (star, color, hover) = if account.favorite {
+                    ("★", Color32::GOLD, "Remove from favorites")
+                } else {
+                    ("☆", Color32::GRAY, "Add to favorites")
+                };
+                if ui.add(egui::Button::new(RichText::new(star).size(16.0).color(color)).frame(false))
+                    .on_hover_text(hover)
+                    .clicked()
+                {
+                    actions.favorite = Some((account.name.clone(), !account.favorite));
+                }
+
+                render_account_info_aligned(icon_manager, ui, account);
+
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+
+                    if render_clickable_icon_with_text(
+                        icon_manager,
+                        ui,
+                        "delete",
+                        "Delete",
+                        Color32::RED,
+                        Some(16),
+                        "Delete this account"
+                    ).clicked() {
+                        actions.delete = Some(account.name.clone());
+                    }
+
+                    if render_clickable_icon_with_text(
+                        icon_manager,
+                        ui,
+                        "edit",
+                        "Edit",
+                        Color32::YELLOW,
+                        Some(16),
+                        "Edit this account"
+                    ).clicked() {
+                        actions.edit = Some(account.name.clone());
+                    }
+
+                    if render_clickable_icon_with_text(
+                        icon_manager,
+                        ui,
+                        "refresh",
+                        "Use",
+                        Color32::LIGHT_GREEN,
+                        Some(16),
+                        "Switch to this account"
+                    ).clicked() {
+                        actions.use_account = Some(account.name.clone());
+                    }
+
+                    if render_clickable_icon_with_text(
+                        icon_manager,
+                        ui,
+                        "copy",
+                        "Copy",
+                        Color32::LIGHT_BLUE,
+                        Some(16),
+                        "Copy account data to clipboard"
+                    ).clicked() {
+                        actions.copy = Some(account.name.clone());
+                    }
+                });
+            });
+        });
+    }
+}
+
+/// sessions.json не прочитался: запись заблокирована, пока не выбрана копия или чистый старт
+fn render_restore_backup_section(ui_state: &mut UiState, ui: &mut Ui, error: &str) {
+    let Some(manager_mutex) = GlobalState::instance().get_account_manager().get() else { return };
+
+    ui.colored_label(Color32::LIGHT_RED, "sessions.json could not be loaded. Saving is disabled until it is restored.");
+    ui.colored_label(Color32::GRAY, error);
+    ui.add_space(6.0);
+
+    let backups = manager_mutex.lock().backups();
+    let mut restore = None;
+    if backups.is_empty() {
+        ui.colored_label(Color32::GRAY, "No backups found.");
+    } else {
+        ui.label("Restore from backup:");
+        ScrollArea::vertical().id_salt("account_backups").max_height(160.0).show(ui, |ui| {
+            for path in &backups {
+                ui.horizontal(|ui| {
+                    let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+                    ui.monospace(name);
+                    if ui.button("Restore").clicked() {
+                        restore = Some(path.clone());
+                    }
+                });
+            }
+        });
+    }
+
+    ui.horizontal(|ui| {
+        if ui.button("Retry").clicked() {
+            if let Err(e) = manager_mutex.lock().load_accounts() {
+                ui_state.notification_manager.show_error("Load Failed", &e);
+            }
+        }
+        if ui
+            .button("Start Fresh")
+            .on_hover_text("Move the broken file aside and start with no accounts")
+            .clicked()
+        {
+            match manager_mutex.lock().start_fresh() {
+                Ok(_) => {
+                    ui_state.notification_manager.show_warning("Started Fresh", "The broken file was kept as sessions.json.broken-*");
+                }
+                Err(e) => {
+                    ui_state.notification_manager.show_error("Start Fresh Failed", &e);
+                }
+            }
+        }
+    });
+
+    if let Some(path) = restore {
+        let result = manager_mutex.lock().restore_backup(&path);
+        match result {
+            Ok(_) => {
+                ui_state.notification_manager.show_success("Backup Restored", &format!("Restored {}", path.display()));
+            }
+            Err(e) => {
+                ui_state.notification_manager.show_error("Restore Failed", &e);
+            }
+        }
+    }
+}
+
 fn save_manual_account(ui_state: &mut UiState) -> bool {
     if ui_state.manual_account_name.is_empty() {
         ui_state.notification_manager.show_error("Validation Error", "Please enter an account name");
@@ -613,16 +1627,17 @@ fn save_edited_account(ui_state: &mut UiState) -> bool {
     };
 
     if let Some(manager_mutex) = GlobalState::instance().get_account_manager().get() {
-        let mut manager = manager_mutex.lock();
-
-        if ui_state.edit_account_name != ui_state.edit_original_name {
-            if let Err(e) = manager.rename_account(&ui_state.edit_original_name, ui_state.edit_account_name.clone()) {
-                ui_state.notification_manager.show_error("Rename Failed", &format!("Failed to rename account: {}", e));
-                return false;
-            }
-        }
-
-        match manager.update_account(&ui_state.edit_account_name, session_info) {
+        let edit = AccountEdit {
+            name: ui_state.edit_account_name.clone(),
+            session: session_info,
+            group: ui_state.edit_group.clone(),
+            tags: ui_state.edit_tags.split(',').map(str::to_string).collect(),
+            notes: ui_state.edit_notes.clone(),
+            servers: ui_state.edit_servers.split(',').map(str::to_string).collect(),
+            provider: ui_state.edit_provider.clone(),
+        };
+        let result = manager_mutex.lock().edit_account(ui_state.edit_original_name, edit);
+        match result {
             Ok(_) => {
                 ui_state.notification_manager.show_success(
                     "Account Updated",
@@ -652,6 +1667,11 @@ fn edit_account(account_name: &str, ui_state: &mut UiState) {
             *ui_state.edit_access_token = account.access_token.clone();
             *ui_state.edit_session_type = account.session_type.clone();
             *ui_state.edit_original_name = account.name.clone();
+            *ui_state.edit_group = account.group.clone().unwrap_or_default();
+            *ui_state.edit_tags = account.tags.join(", ");
+            *ui_state.edit_notes = account.notes.clone();
+            *ui_state.edit_servers = account.servers.join(", ");
+            *ui_state.edit_provider = account.provider.clone();
 
             *ui_state.show_edit_dialog = true;
         } else {
@@ -696,8 +1716,7 @@ fn use_account(account_name: &str, ui_state: &mut UiState) {
 
         match session_info {
             Ok(session_info) => {
-                let session_manager = get_jvm();
-                match session_manager.change_session(session_info) {
+                match switch_session(session_info, SwitchTrigger::Manual) {
                     Ok(_) => {
                         ui_state.notification_manager.show_success(
                             "Account Switched",
@@ -1,8 +1,12 @@
 use crate::{
+    account::{StoredAccount, SwitchTrigger},
+    account_history::switch_session,
     async_runtime::ASYNC_RUNTIME,
-    auth,
+    auth::{AuthData, AuthFuture, AuthProvider, AuthProviderKind, OFFLINE_SESSION_TYPE},
+    core::state::GlobalState,
     graphics::icon_renderer::{render_clickable_icon_with_text, render_decorative_icon},
     graphics::svg_icons::SvgIconManager,
+    jvm::SessionInfo,
     ui::UiState,
 };
 use egui::{Color32, RichText, TextEdit, Ui};
@@ -11,7 +15,8 @@ use std::sync::Mutex;
 use tokio::sync::mpsc;
 
 enum AuthTabResult {
-    Success { token: String, profile: String },
+    Success(AuthData),
+    Validated(bool),
     Error(String),
 }
 
@@ -40,10 +45,19 @@ fn poll_auth_result(ui_state: &mut UiState) {
         if let Ok(result) = guard.1.try_recv() {
             *ui_state.auth_tab_in_progress = false;
             match result {
-                AuthTabResult::Success { token, profile } => {
+                AuthTabResult::Success(data) => {
                     *ui_state.auth_tab_error = None;
-                    *ui_state.auth_tab_result_token = token;
-                    *ui_state.auth_tab_result_profile = profile;
+                    *ui_state.auth_tab_result_token = data.access_token;
+                    *ui_state.auth_tab_result_profile = data.profile;
+                    *ui_state.auth_tab_result_username = data.username;
+                    *ui_state.auth_tab_client_token = data.client_token.unwrap_or_default();
+                }
+                AuthTabResult::Validated(valid) => {
+                    if valid {
+                        ui_state.notification_manager.show_success("Токен", "Токен действителен");
+                    } else {
+                        ui_state.notification_manager.show_warning("Токен", "Токен недействителен, обновите его");
+                    }
                 }
                 AuthTabResult::Error(msg) => {
                     *ui_state.auth_tab_error = Some(msg);
@@ -63,6 +77,13 @@ fn render_header(icon_manager: &mut SvgIconManager, ui: &mut Ui) {
 fn render_input_section(ui_state: &mut UiState, icon_manager: &mut SvgIconManager, ui: &mut Ui) {
     ui.group(|ui| {
         ui.vertical(|ui| {
+            ui.horizontal(|ui| {
+                ui.label(RichText::new("Провайдер:").color(Color32::LIGHT_BLUE));
+                render_provider_picker(ui, "auth_tab_provider", ui_state.auth_tab_provider);
+            });
+
+            ui.add_space(4.0);
+
             ui.horizontal(|ui| {
                 render_decorative_icon(icon_manager, ui, "user", Color32::LIGHT_BLUE, Some(14));
                 ui.label(RichText::new("Никнейм:").color(Color32::LIGHT_BLUE));
@@ -79,23 +100,26 @@ fn render_input_section(ui_state: &mut UiState, icon_manager: &mut SvgIconManage
                 render_decorative_icon(icon_manager, ui, "key", Color32::LIGHT_BLUE, Some(14));
                 ui.label(RichText::new("Пароль:").color(Color32::LIGHT_BLUE));
             });
-            ui.add(
+            let needs_password = ui_state.auth_tab_provider.needs_password();
+            ui.add_enabled(
+                needs_password,
                 TextEdit::singleline(ui_state.auth_tab_password)
                     .desired_width(ui.available_width())
                     .password(true)
-                    .hint_text("Введите пароль"),
+                    .hint_text(if needs_password { "Введите пароль" } else { "Не нужен в offline-режиме" }),
             );
 
             ui.add_space(8.0);
 
             let can_auth = !ui_state.auth_tab_username.is_empty()
-                && !ui_state.auth_tab_password.is_empty()
+                && (!needs_password || !ui_state.auth_tab_password.is_empty())
                 && !*ui_state.auth_tab_in_progress;
 
             ui.horizontal(|ui| {
                 if *ui_state.auth_tab_in_progress {
                     ui.spinner();
-                    ui.label(RichText::new("Авторизация...").color(Color32::YELLOW));
+                    let provider = ui_state.auth_tab_provider.provider();
+                    ui.label(RichText::new(format!("Авторизация ({})...", provider.name())).color(Color32::YELLOW));
                 } else {
                     ui.add_enabled_ui(can_auth, |ui| {
                         if render_clickable_icon_with_text(
@@ -177,33 +201,181 @@ fn render_result_section(ui_state: &mut UiState, icon_manager: &mut SvgIconManag
                         ui_state.notification_manager.show_success("Скопировано", "Player ID скопирован");
                     }
                 });
+
+                ui.add_space(4.0);
+
+                ui.horizontal(|ui| {
+                    if render_clickable_icon_with_text(
+                        icon_manager, ui, "apply", "Применить в игре",
+                        Color32::LIGHT_GREEN, Some(16), "Сменить сессию игры на полученный токен",
+                    ).clicked() {
+                        apply_auth_result(ui_state);
+                    }
+
+                    ui.add_enabled_ui(!*ui_state.auth_tab_in_progress, |ui| {
+                        if render_clickable_icon_with_text(
+                            icon_manager, ui, "refresh", "Обновить",
+                            Color32::LIGHT_BLUE, Some(16), "Получить новый токен у провайдера",
+                        ).clicked() {
+                            start_refresh(ui_state);
+                        }
+                        if render_clickable_icon_with_text(
+                            icon_manager, ui, "success", "Проверить",
+                            Color32::LIGHT_BLUE, Some(16), "Проверить, что токен ещё действителен",
+                        ).clicked() {
+                            start_validate(ui_state);
+                        }
+                    });
+                });
+
+                ui.add_space(4.0);
+
+                ui.horizontal(|ui| {
+                    ui.add(
+                        TextEdit::singleline(ui_state.auth_tab_account_name)
+                            .desired_width(160.0)
+                            .hint_text("Имя аккаунта"),
+                    );
+                    if render_clickable_icon_with_text(
+                        icon_manager, ui, "save", "Сохранить аккаунт",
+                        Color32::LIGHT_GREEN, Some(16), "Сохранить в список аккаунтов вместе с провайдером",
+                    ).clicked() {
+                        save_auth_result(ui_state);
+                    }
+                });
             });
         });
     }
 }
 
+/// Выбор провайдера; для Yggdrasil рядом поле адреса сервера
+pub fn render_provider_picker(ui: &mut Ui, id: &str, provider: &mut AuthProviderKind) {
+    egui::ComboBox::from_id_salt(id)
+        .selected_text(provider.label())
+        .show_ui(ui, |ui| {
+            if ui.selectable_label(matches!(provider, AuthProviderKind::Launcher), "Launcher").clicked() {
+                *provider = AuthProviderKind::Launcher;
+            }
+            if ui
+                .selectable_label(matches!(provider, AuthProviderKind::Yggdrasil { .. }), "Yggdrasil")
+                .clicked()
+                && !matches!(provider, AuthProviderKind::Yggdrasil { .. })
+            {
+                *provider = AuthProviderKind::Yggdrasil { url: String::new() };
+            }
+            if ui.selectable_label(matches!(provider, AuthProviderKind::Offline), "Offline").clicked() {
+                *provider = AuthProviderKind::Offline;
+            }
+        });
+    if let AuthProviderKind::Yggdrasil { url } = provider {
+        ui.add(
+            TextEdit::singleline(url)
+                .desired_width(220.0)
+                .hint_text("https://example.com/api/yggdrasil"),
+        );
+    }
+}
+
+fn result_session(ui_state: &UiState) -> SessionInfo {
+    let username = if ui_state.auth_tab_result_username.is_empty() {
+        ui_state.auth_tab_username.clone()
+    } else {
+        ui_state.auth_tab_result_username.clone()
+    };
+    SessionInfo {
+        username,
+        player_id: ui_state.auth_tab_result_profile.clone(),
+        access_token: ui_state.auth_tab_result_token.clone(),
+        session_type: if *ui_state.auth_tab_provider == AuthProviderKind::Offline { OFFLINE_SESSION_TYPE } else { "mojang" }.to_string(),
+    }
+}
+
+fn result_auth_data(ui_state: &UiState) -> AuthData {
+    AuthData {
+        access_token: ui_state.auth_tab_result_token.clone(),
+        profile: ui_state.auth_tab_result_profile.clone(),
+        username: ui_state.auth_tab_result_username.clone(),
+        client_token: Some(ui_state.auth_tab_client_token.clone()).filter(|t| !t.is_empty()),
+    }
+}
+
+fn save_auth_result(ui_state: &mut UiState) {
+    let name = ui_state.auth_tab_account_name.trim().to_string();
+    if name.is_empty() {
+        ui_state.notification_manager.show_error("Ошибка", "Введите имя аккаунта");
+        return;
+    }
+    let Some(manager_mutex) = GlobalState::instance().get_account_manager().get() else { return };
+    let mut account = StoredAccount::new(name.clone(), result_session(ui_state));
+    account.provider = ui_state.auth_tab_provider.clone();
+    account.client_token = result_auth_data(ui_state).client_token;
+    let result = manager_mutex.lock().insert_account(account);
+    match result {
+        Ok(_) => {
+            ui_state.notification_manager.show_success("Сохранено", &format!("Аккаунт '{}' сохранён", name));
+            ui_state.auth_tab_account_name.clear();
+        }
+        Err(e) => {
+            ui_state.notification_manager.show_error("Ошибка", &format!("Не удалось сохранить: {}", e));
+        }
+    }
+}
+
+/// Повторная авторизация: ник, токен и UUID из ответа провайдера
+fn apply_auth_result(ui_state: &mut UiState) {
+    let session = result_session(ui_state);
+    match switch_session(session, SwitchTrigger::Reauth) {
+        Ok(_) => {
+            ui_state.notification_manager.show_success("Сессия изменена", "Новый токен применён");
+        }
+        Err(e) => {
+            ui_state.notification_manager.show_error("Ошибка", &format!("Не удалось сменить сессию: {}", e));
+        }
+    }
+}
+
 fn start_auth(ui_state: &mut UiState) {
-    *ui_state.auth_tab_in_progress = true;
-    *ui_state.auth_tab_error = None;
     ui_state.auth_tab_result_token.clear();
     ui_state.auth_tab_result_profile.clear();
+    ui_state.auth_tab_result_username.clear();
+    ui_state.auth_tab_client_token.clear();
 
     let username = ui_state.auth_tab_username.clone();
     let password = ui_state.auth_tab_password.clone();
+    spawn_provider_task(ui_state, move |provider| {
+        Box::pin(async move {
+            provider.auth(&username, &password).await.map(AuthTabResult::Success)
+        })
+    });
+}
 
+fn start_refresh(ui_state: &mut UiState) {
+    let session = result_auth_data(ui_state);
+    spawn_provider_task(ui_state, move |provider| {
+        Box::pin(async move { provider.refresh(&session).await.map(AuthTabResult::Success) })
+    });
+}
+
+fn start_validate(ui_state: &mut UiState) {
+    let session = result_auth_data(ui_state);
+    spawn_provider_task(ui_state, move |provider| {
+        Box::pin(async move { provider.validate(&session).await.map(AuthTabResult::Validated) })
+    });
+}
+
+/// Запрос к выбранному провайдеру в фоне; результат заберёт `poll_auth_result`
+fn spawn_provider_task<F>(ui_state: &mut UiState, task: F)
+where
+    F: FnOnce(Box<dyn AuthProvider>) -> AuthFuture<'static, AuthTabResult> + Send + 'static,
+{
+    *ui_state.auth_tab_in_progress = true;
+    *ui_state.auth_tab_error = None;
+
+    let provider = ui_state.auth_tab_provider.provider();
     let sender = AUTH_TAB_CHANNEL.lock().unwrap().0.clone();
 
     ASYNC_RUNTIME.spawn(async move {
-        match auth::auth(&username, &password).await {
-            Ok(data) => {
-                let _ = sender.send(AuthTabResult::Success {
-                    token: data.access_token,
-                    profile: data.profile,
-                });
-            }
-            Err(e) => {
-                let _ = sender.send(AuthTabResult::Error(format!("{}", e)));
-            }
-        }
+        let result = task(provider).await.unwrap_or_else(|e| AuthTabResult::Error(format!("{}", e)));
+        let _ = sender.send(result);
     });
 }
@@ -3,7 +3,7 @@ use crate::{
     graphics::icon_renderer::{render_clickable_icon_with_text, render_decorative_icon},
     graphics::svg_icons::SvgIconManager,
     initiate_unload,
-    ui::account_manager::render_account_manager_tab,
+    ui::account_manager::{check_account_auto_lock, check_server_binding_events, poll_vault_task, render_account_manager_tab},
     ui::authenticator::render_authenticator_tab,
     ui::session_window::render_session_tab,
     ui::UiState,
@@ -139,6 +139,9 @@ pub fn render_main_window(
 
     // Render floating/detached packet analyzer windows regardless of the active tab
     render_packet_analyzer_detached_windows(ctx, ui_state);
+    poll_vault_task(ui_state);
+    check_account_auto_lock(ctx, ui_state);
+    check_server_binding_events(ui_state);
 }
 
 fn render_unload_section(
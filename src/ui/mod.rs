@@ -5,6 +5,13 @@ pub mod account_manager;
 pub mod authenticator;
 pub mod notification_manager;
 pub mod packet_analyzer;
+pub mod packet_stats;
+pub mod packet_timeline;
+pub mod packet_layout;
+pub mod packet_columns;
+pub mod packet_hex_editor;
+pub mod packet_builder;
+pub mod packet_snapshots;
 pub mod jvm_analyzer;
 pub mod state;
 
@@ -1,23 +1,47 @@
-use crate::core::custom_payload::{decode_custom_payload, DecodedStruct, DecodedValue};
-use crate::core::packets::Bound;
+use crate::core::analyzer_settings::persist_analyzer_settings;
+use crate::core::annotations::{record_key, type_key, Annotation};
+use crate::core::custom_payload::{decode_record, DecodedStruct, DecodedValue};
+use crate::core::packet_correlation::{correlate, CorrelatedPair, CorrelationRule};
+use crate::core::packet_diff::{align_pairs, diff_bytes, diff_structs, ByteOp, ByteOpKind, FieldChange, FieldDiff};
+use crate::core::packet_query::{Query, QueryError};
+use crate::core::packet_search::{HitLocation, SearchHit, SearchMode, SearchPattern};
+use crate::core::packet_triggers::{export_path, PacketTrigger, TriggerEvent};
 use crate::core::state::GlobalState;
-use crate::graphics::context::{PacketFilterProfile, PacketTrigger};
-use crate::graphics::netlog::{PacketDetails, PacketDirection, PacketRecord, make_record};
+use crate::graphics::context::PacketFilterProfile;
+use crate::graphics::netlog::{PacketDetails, PacketDirection, PacketRecord, PacketSession, make_record};
 use crate::graphics::svg_icons::SvgIconManager;
 use crate::ui::UiState;
+use crate::ui::packet_columns::{column_rects, render_column_header, render_columns_menu, sort_records};
+use crate::ui::packet_builder::render_packet_builder_section;
+use crate::ui::packet_hex_editor::{render_hex_editor, HexEditor};
+use crate::ui::packet_snapshots::{
+    freeze_snapshot, render_snapshot_banner, render_snapshots_sidebar, viewed_snapshot_records,
+};
+use crate::ui::packet_layout::{render_layout_infer_section, run_layout_inference};
+use crate::ui::packet_stats::render_packet_stats_section;
+use crate::ui::packet_timeline::render_packet_timeline;
 use base64::Engine;
 use egui::{pos2, ScrollArea, Sense};
 use egui::{Color32, RichText, StrokeKind, TextStyle, Ui, vec2};
+use egui::text::{LayoutJob, TextFormat};
 use once_cell::sync::Lazy;
 use std::cmp::min;
 use std::collections::HashMap;
+use std::ops::Range;
 use std::sync::Mutex;
 
 #[derive(Default)]
 struct Caches {
     decoded_cache: HashMap<u64, DecodedStruct>,
-    short_hex_cache: HashMap<u64, String>,
     details_hex_cache: HashMap<(u64, usize), String>,
+    query: Option<(String, Result<Query, QueryError>)>,
+    search: Option<((SearchMode, String), Result<SearchPattern, String>)>,
+    /// (число записей, последний id, правила) -> пары запрос/ответ
+    correlation: Option<((usize, u64, Vec<CorrelationRule>), HashMap<u64, CorrelatedPair>)>,
+    /// id -> `record_key`, sha1 по всем байтам каждый кадр не считаем
+    note_keys: HashMap<u64, String>,
+    /// (id A, id B) -> LCS-выравнивание, таблица до 16 МБ — не пересчитываем каждый кадр
+    byte_diff: Option<((u64, u64), Vec<ByteOp>)>,
 }
 
 static CACHES: Lazy<Mutex<Caches>> = Lazy::new(|| Mutex::new(Caches::default()));
@@ -43,12 +67,17 @@ pub fn render_packet_analyzer_tab(
 
             ui.add_space(12.0);
             ui.label("Filter:");
-            ui.text_edit_singleline(ui_state.packet_filter);
+            ui.add(
+                egui::TextEdit::singleline(ui_state.packet_filter)
+                    .hint_text("dir:in channel:dwcity and len>100"),
+            )
+            .on_hover_text(QUERY_HELP);
 
             ui.add_space(12.0);
             ui.checkbox(ui_state.packet_autoscroll, "Autoscroll");
-            ui.checkbox(ui_state.packet_paused, "Paused");
-            if ui.input(|i| i.modifiers.is_none()) {
+            // паузу может включить и триггер из потока хука
+            *ui_state.packet_paused = GlobalState::instance().is_packet_paused();
+            if ui.checkbox(ui_state.packet_paused, "Paused").changed() {
                 GlobalState::instance().set_packet_paused(*ui_state.packet_paused);
             }
 
@@ -90,8 +119,8 @@ pub fn render_packet_analyzer_tab(
 
                 if let Ok(mut c) = CACHES.lock() {
                     c.decoded_cache.clear();
-                    c.short_hex_cache.clear();
                     c.details_hex_cache.clear();
+                    c.note_keys.clear();
                 }
             }
             if ui.button("Mark seen").clicked() {
@@ -102,12 +131,24 @@ pub fn render_packet_analyzer_tab(
             }
         });
 
+        if let Err(e) = compile_query(ui_state.packet_filter) {
+            ui.colored_label(Color32::LIGHT_RED, format!("Filter error {e}"));
+        }
+
         ui.add_space(6.0);
         render_filter_profiles_bar(ui, ui_state);
         ui.add_space(4.0);
         render_trigger_bar(ui, ui_state);
         ui.add_space(4.0);
+        render_correlation_bar(ui, ui_state);
+        ui.add_space(4.0);
         render_import_export_bar(ui, ui_state);
+        ui.add_space(4.0);
+        render_packet_stats_section(ui, ui_state);
+        ui.add_space(4.0);
+        render_layout_infer_section(ui, ui_state);
+        ui.add_space(4.0);
+        render_packet_builder_section(ui, ui_state);
     });
 
     ui.add_space(8.0);
@@ -134,6 +175,10 @@ pub fn render_packet_analyzer_detached_windows(
     ctx: &egui::Context,
     ui_state: &mut UiState,
 ) {
+    drain_trigger_events(ui_state);
+    if let Err(e) = persist_analyzer_settings(ui_state) {
+        ui_state.notification_manager.show_error("Settings not saved", &e);
+    }
     if !*ui_state.packets_detached {
         return;
     }
@@ -150,57 +195,79 @@ pub fn render_packet_analyzer_detached_windows(
     *ui_state.packets_window_open = win_open;
 }
 
-fn record_matches_query(rec: &PacketRecord, needle_lc: &str) -> bool {
-    let name = rec.name.as_str();
-    let ch = match &rec.details {
-        Some(PacketDetails::CustomPayload { channel, .. }) => channel.as_str(),
-        _ => "",
-    };
-    let group = rec.group.as_deref().unwrap_or("");
-    let tags_match = rec.tags.iter().any(|t| contains_ascii_ci(t, needle_lc));
-
-    let hex = {
-        let mut caches = CACHES.lock().unwrap();
-        caches
-            .short_hex_cache
-            .entry(rec.id)
-            .or_insert_with(|| first_bytes_hex(&rec.data, 64))
-            .clone()
-    };
+const QUERY_HELP: &str = "Keys: dir:in|out, name, channel, group, tag, len, hex:\"0a 0b\", field.<path>\n\
+Operators: ':' contains, '=' / '!=' equals, '~' regex, '>' '>=' '<' '<='\n\
+Combine with and / or / not and parentheses; a bare word searches name, channel, group, hex and tags";
 
-    contains_ascii_ci(name, needle_lc)
-        || contains_ascii_ci(ch, needle_lc)
-        || contains_ascii_ci(group, needle_lc)
-        || contains_ascii_ci(&hex, needle_lc)
-        || tags_match
+/// Разобранный запрос кэшируется, пока строка фильтра не меняется.
+fn compile_query(text: &str) -> Result<Option<Query>, QueryError> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Ok(None);
+    }
+    let mut caches = CACHES.lock().unwrap();
+    if let Some((src, compiled)) = caches.query.as_ref() {
+        if src == text {
+            return compiled.clone().map(Some);
+        }
+    }
+    let compiled = Query::parse(text);
+    caches.query = Some((text.to_string(), compiled.clone()));
+    compiled.map(Some)
 }
 
-fn row_highlight_color(rec: &PacketRecord, ui_state: &mut UiState) -> Option<Color32> {
-    if let Some(rgb) = rec.color {
-        return Some(Color32::from_rgb(rgb[0], rgb[1], rgb[2]));
+fn compile_search(mode: SearchMode, text: &str) -> Option<Result<SearchPattern, String>> {
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
     }
-
-    for trig in ui_state.packet_triggers.iter() {
-        if trigger_matches(rec, trig) {
-            if trig.pin {
-                if let Some(store) = GlobalState::instance().get_packet_store().get() {
-                    store.lock().pin(rec.id, true);
-                }
-            }
-            return Some(Color32::from_rgb(trig.highlight[0], trig.highlight[1], trig.highlight[2]).linear_multiply(0.7));
+    let mut caches = CACHES.lock().unwrap();
+    if let Some(((m, src), compiled)) = caches.search.as_ref() {
+        if *m == mode && src == text {
+            return Some(compiled.clone());
         }
     }
-    None
+    let compiled = SearchPattern::compile(mode, text);
+    caches.search = Some(((mode, text.to_string()), compiled.clone()));
+    Some(compiled)
 }
 
-fn trigger_matches(rec: &PacketRecord, trig: &PacketTrigger) -> bool {
-    if let Some(dir) = trig.dir {
-        if dir != rec.dir {
-            return false;
-        }
+fn cached_decode(rec: &PacketRecord) -> Option<DecodedStruct> {
+    if let Some(d) = CACHES.lock().unwrap().decoded_cache.get(&rec.id) {
+        return Some(d.clone());
     }
-    let needle = trig.needle.as_str();
-    record_matches_query(rec, needle)
+    let d = decode_record(rec)?;
+    if let Ok(mut caches) = CACHES.lock() {
+        caches.decoded_cache.insert(rec.id, d.clone());
+    }
+    Some(d)
+}
+
+/// Заметки на тип и на саму запись из файла аннотаций
+fn record_notes(rec: &PacketRecord) -> (Option<Annotation>, Option<Annotation>) {
+    let Some(notes) = GlobalState::instance().get_annotations().get() else {
+        return (None, None);
+    };
+    let notes = notes.lock();
+    let on_type = notes.type_note(rec).cloned();
+    let on_record = if notes.has_record_notes() {
+        let key = CACHES
+            .lock()
+            .unwrap()
+            .note_keys
+            .entry(rec.id)
+            .or_insert_with(|| record_key(rec))
+            .clone();
+        notes.record_note(&key).cloned()
+    } else {
+        None
+    };
+    (on_type, on_record)
+}
+
+/// Цвет строки: свой или выставленный триггером при приходе пакета
+fn row_highlight_color(rec: &PacketRecord) -> Option<Color32> {
+    rec.color.map(|rgb| Color32::from_rgb(rgb[0], rgb[1], rgb[2]))
 }
 
 fn render_filter_profiles_bar(ui: &mut Ui, ui_state: &mut UiState) {
@@ -220,6 +287,10 @@ fn render_filter_profiles_bar(ui: &mut Ui, ui_state: &mut UiState) {
                     *ui_state.packet_show_inbound = prof.show_inbound;
                     *ui_state.packet_show_outbound = prof.show_outbound;
                     *ui_state.packet_only_pinned = prof.only_pinned;
+                    if !prof.columns.is_empty() {
+                        *ui_state.packet_columns = prof.columns;
+                        *ui_state.packet_sort = None;
+                    }
                 }
             }
             if remove {
@@ -244,50 +315,198 @@ fn render_filter_profiles_bar(ui: &mut Ui, ui_state: &mut UiState) {
             } else {
                 ui_state.packet_profile_new_query.trim().to_string()
             };
+            if !query.is_empty() {
+                if let Err(e) = Query::parse(&query) {
+                    ui_state
+                        .notification_manager
+                        .show_error("Invalid query", &format!("Profile not saved: {e}"));
+                    return;
+                }
+            }
             ui_state.packet_filter_profiles.push(PacketFilterProfile {
                 name,
                 query,
                 show_inbound: *ui_state.packet_show_inbound,
                 show_outbound: *ui_state.packet_show_outbound,
                 only_pinned: *ui_state.packet_only_pinned,
+                columns: ui_state.packet_columns.clone(),
             });
         }
     });
 }
 
+/// События триггеров из потока хука превращаются в уведомления.
+/// Вызывается каждый кадр, даже если вкладка анализатора закрыта.
+fn drain_trigger_events(ui_state: &mut UiState) {
+    let Some(triggers) = GlobalState::instance().get_packet_triggers().get() else { return };
+    let events = triggers.lock().drain_events();
+    for event in events {
+        match event {
+            TriggerEvent::Notify { trigger, summary } => {
+                ui_state.notification_manager.show_info(&format!("Trigger: {trigger}"), &summary);
+            }
+            TriggerEvent::Paused { trigger } => {
+                ui_state
+                    .notification_manager
+                    .show_warning("Capture paused", &format!("Trigger '{trigger}' paused packet capture"));
+            }
+            TriggerEvent::ExportFailed { trigger, error } => {
+                ui_state.notification_manager.show_error(&format!("Trigger: {trigger}"), &error);
+            }
+        }
+    }
+}
+
 fn render_trigger_bar(ui: &mut Ui, ui_state: &mut UiState) {
+    let Some(triggers) = GlobalState::instance().get_packet_triggers().get() else { return };
     ui.horizontal_wrapped(|ui| {
         ui.label("Triggers:");
         ui.add(
             egui::TextEdit::singleline(ui_state.packet_trigger_input)
-                .hint_text("substring or channel"),
-        );
+                .hint_text("query, e.g. channel:dwcity and field.emeralds>100"),
+        )
+        .on_hover_text(QUERY_HELP);
         ui.label("Highlight:");
-        ui.text_edit_singleline(ui_state.packet_color_hex);
-        if ui.button("Add trigger").clicked() {
-            if !ui_state.packet_trigger_input.trim().is_empty() {
-                let color = parse_hex_color(ui_state.packet_color_hex.trim())
-                    .unwrap_or([255, 200, 64]);
-                ui_state.packet_triggers.push(PacketTrigger {
-                    name: ui_state.packet_trigger_input.trim().to_string(),
-                    needle: ui_state.packet_trigger_input.trim().to_ascii_lowercase(),
-                    dir: None,
-                    highlight: color,
-                    pin: false,
-                });
-                ui_state.packet_trigger_input.clear();
+        ui.add(egui::TextEdit::singleline(ui_state.packet_color_hex).desired_width(64.0))
+            .on_hover_text("#RRGGBB, empty for no highlight");
+        ui.checkbox(ui_state.packet_trigger_pin, "Pin");
+        ui.checkbox(ui_state.packet_trigger_notify, "Notify");
+        ui.checkbox(ui_state.packet_trigger_pause, "Pause");
+        ui.label("Append to:");
+        ui.add(
+            egui::TextEdit::singleline(ui_state.packet_trigger_export)
+                .hint_text("file name")
+                .desired_width(100.0),
+        )
+        .on_hover_text("Matching packets are appended as JSON lines to packet_exports/<name>");
+        if ui.button("Add trigger").clicked() && !ui_state.packet_trigger_input.trim().is_empty() {
+            let query = ui_state.packet_trigger_input.trim().to_string();
+            let export = ui_state.packet_trigger_export.trim();
+            let trigger = PacketTrigger {
+                name: query.clone(),
+                query,
+                enabled: true,
+                highlight: parse_hex_color(ui_state.packet_color_hex.trim()),
+                pin: *ui_state.packet_trigger_pin,
+                notify: *ui_state.packet_trigger_notify,
+                pause: *ui_state.packet_trigger_pause,
+                export_file: (!export.is_empty()).then(|| export.to_string()),
+                hits: 0,
+            };
+            match triggers.lock().add(trigger) {
+                Ok(()) => ui_state.packet_trigger_input.clear(),
+                Err(e) => {
+                    ui_state.notification_manager.show_error("Trigger not added", &e);
+                }
             }
         }
-        for idx in 0..ui_state.packet_triggers.len() {
-            let name = ui_state.packet_triggers[idx].name.clone();
-            if ui.button(format!("x {}", name)).clicked() {
-                ui_state.packet_triggers.remove(idx);
+    });
+
+    let mut engine = triggers.lock();
+    let mut toggle = None;
+    let mut remove = None;
+    for (idx, trig) in engine.triggers().enumerate() {
+        ui.horizontal(|ui| {
+            let mut enabled = trig.enabled;
+            if ui.checkbox(&mut enabled, "").changed() {
+                toggle = Some((idx, enabled));
+            }
+            let color = trig
+                .highlight
+                .map(|c| Color32::from_rgb(c[0], c[1], c[2]))
+                .unwrap_or(Color32::WHITE);
+            ui.label(RichText::new(&trig.name).monospace().color(color));
+            ui.label(RichText::new(format!("x{}", trig.hits)).strong());
+            let mut actions = Vec::new();
+            if trig.pin { actions.push("pin".to_string()); }
+            if trig.notify { actions.push("notify".to_string()); }
+            if trig.pause { actions.push("pause".to_string()); }
+            if let Some(file) = &trig.export_file {
+                actions.push(format!("-> {}", export_path(file).display()));
+            }
+            ui.colored_label(Color32::GRAY, actions.join(", "));
+            if ui.small_button("x").clicked() {
+                remove = Some(idx);
+            }
+        });
+    }
+    if let Some((idx, enabled)) = toggle {
+        engine.set_enabled(idx, enabled);
+    }
+    if let Some(idx) = remove {
+        engine.remove(idx);
+    }
+    if engine.triggers().next().is_some() && ui.small_button("Reset counters").clicked() {
+        engine.reset_counters();
+    }
+}
+
+fn render_correlation_bar(ui: &mut Ui, ui_state: &mut UiState) {
+    ui.horizontal_wrapped(|ui| {
+        ui.label("Pair keys:").on_hover_text(
+            "Requests are paired with the next response on the same channel and packet ID.\n\
+             A key field makes the response also match that field, e.g. dwcity + username",
+        );
+        for idx in 0..ui_state.packet_correlation_rules.len() {
+            let rule = &ui_state.packet_correlation_rules[idx];
+            if ui.button(format!("x {}.{}", rule.channel, rule.key_field)).clicked() {
+                ui_state.packet_correlation_rules.remove(idx);
                 break;
             }
         }
+        ui.add(
+            egui::TextEdit::singleline(ui_state.packet_correlation_channel_input)
+                .hint_text("channel")
+                .desired_width(90.0),
+        );
+        ui.add(
+            egui::TextEdit::singleline(ui_state.packet_correlation_key_input)
+                .hint_text("key field")
+                .desired_width(110.0),
+        );
+        if ui.button("Add key").clicked() {
+            let channel = ui_state.packet_correlation_channel_input.trim().to_string();
+            let key_field = ui_state.packet_correlation_key_input.trim().to_string();
+            if !channel.is_empty() && !key_field.is_empty() {
+                ui_state.packet_correlation_rules.retain(|r| !r.channel.eq_ignore_ascii_case(&channel));
+                ui_state.packet_correlation_rules.push(CorrelationRule { channel, key_field });
+                ui_state.packet_correlation_channel_input.clear();
+                ui_state.packet_correlation_key_input.clear();
+            }
+        }
     });
 }
 
+/// Пары считаются по всему стору (а не по отфильтрованному списку) и пересчитываются,
+/// только когда меняется набор записей или правила.
+fn correlation_map(all: &[PacketRecord], rules: &[CorrelationRule]) -> HashMap<u64, CorrelatedPair> {
+    let sig = (all.len(), all.last().map_or(0, |r| r.id), rules.to_vec());
+    if let Some((cached_sig, map)) = CACHES.lock().unwrap().correlation.as_ref() {
+        if *cached_sig == sig {
+            return map.clone();
+        }
+    }
+    let map = correlate(all, rules, &mut cached_decode);
+    if let Ok(mut caches) = CACHES.lock() {
+        caches.correlation = Some((sig, map.clone()));
+    }
+    map
+}
+
+fn cached_byte_diff(a: &PacketRecord, b: &PacketRecord) -> Vec<ByteOp> {
+    let key = (a.id, b.id);
+    if let Some((cached_key, ops)) = CACHES.lock().unwrap().byte_diff.as_ref() {
+        if *cached_key == key {
+            return ops.clone();
+        }
+    }
+    let ops = diff_bytes(&a.data, &b.data);
+    if let Ok(mut caches) = CACHES.lock() {
+        caches.byte_diff = Some((key, ops.clone()));
+    }
+    ops
+}
+
 fn render_import_export_bar(ui: &mut Ui, ui_state: &mut UiState) {
     ui.collapsing("Import/Replay buffer", |ui| {
         ui.label("Paste JSON (export format) to replay into store:");
@@ -302,28 +521,268 @@ fn render_import_export_bar(ui: &mut Ui, ui_state: &mut UiState) {
                 ui_state.packet_import_buffer.clear();
             }
         });
+
+        let Some(notes) = GlobalState::instance().get_annotations().get() else { return };
+        ui.horizontal(|ui| {
+            let (types, records) = notes.lock().counts();
+            ui.label(format!("Notes: {types} on types, {records} on records"));
+            if ui.button("Copy notes JSON").clicked() {
+                match notes.lock().export_json() {
+                    Ok(text) => {
+                        let _ = ui_state.clipboard.set_text(&text);
+                        ui_state.notification_manager.show_success("Notes", "Annotations copied as JSON");
+                    }
+                    Err(e) => {
+                        ui_state.notification_manager.show_error("Notes", &e);
+                    }
+                }
+            }
+            if ui.button("Merge notes from clipboard").on_hover_text("Newer note wins on conflicts").clicked() {
+                let Some(text) = ui_state.clipboard.get_text() else { return };
+                match notes.lock().import_json(&text) {
+                    Ok(n) => {
+                        ui_state.notification_manager.show_success("Notes", &format!("{n} notes merged"));
+                    }
+                    Err(e) => {
+                        ui_state.notification_manager.show_error("Notes", &e);
+                    }
+                }
+                *ui_state.packet_note_loaded_for = None;
+            }
+        });
+    });
+}
+
+const SEARCH_HIT_BG: Color32 = Color32::from_rgb(150, 100, 0);
+
+const SEARCH_HELP: &str = "Bytes: hex with ?? wildcards, e.g. 0A ?? FF\n\
+Regex (fields): matched against text fields of decoded packets\n\
+Regex (raw): matched against the whole payload, e.g. \\x0a.{2}\\xff or player\\w+\n\
+Enter / Next goes down the table, Shift+Enter / Prev goes up";
+
+fn render_search_bar(ui: &mut Ui, ui_state: &mut UiState, records: &[PacketRecord]) {
+    let compiled = compile_search(*ui_state.packet_search_mode, ui_state.packet_search_input);
+    let mut step: Option<bool> = None;
+
+    ui.horizontal_wrapped(|ui| {
+        ui.label("Search:");
+        egui::ComboBox::from_id_salt("packet_search_mode")
+            .selected_text(ui_state.packet_search_mode.label())
+            .show_ui(ui, |ui| {
+                for mode in [SearchMode::Bytes, SearchMode::FieldRegex, SearchMode::RawRegex] {
+                    ui.selectable_value(ui_state.packet_search_mode, mode, mode.label());
+                }
+            });
+        let resp = ui
+            .add(
+                egui::TextEdit::singleline(ui_state.packet_search_input)
+                    .hint_text("0A ?? FF")
+                    .desired_width(220.0),
+            )
+            .on_hover_text(SEARCH_HELP);
+        if resp.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+            step = Some(!ui.input(|i| i.modifiers.shift));
+            resp.request_focus();
+        }
+        if ui.button("Prev").clicked() {
+            step = Some(false);
+        }
+        if ui.button("Next").clicked() {
+            step = Some(true);
+        }
+        if ui.button("Reset").clicked() {
+            *ui_state.packet_search_hit = None;
+        }
+
+        match (&compiled, ui_state.packet_search_hit.as_ref()) {
+            (Some(Err(e)), _) => {
+                ui.colored_label(Color32::LIGHT_RED, e.lines().last().unwrap_or(e).trim_start_matches("error: "));
+            }
+            (_, Some(hit)) => {
+                let at = match &hit.location {
+                    HitLocation::Bytes { offset, len } => format!("bytes {offset:#06X}..{:#06X}", offset + len),
+                    HitLocation::Field { path } => format!("field {path}"),
+                };
+                ui.monospace(format!("#{} {at}", hit.record_id));
+            }
+            _ => {}
+        }
+    });
+
+    let (Some(forward), Some(Ok(pattern))) = (step, compiled) else {
+        return;
+    };
+    // порядок как в таблице: сверху новые
+    let ordered: Vec<&PacketRecord> = records.iter().rev().collect();
+    match find_next_hit(&ordered, &pattern, ui_state.packet_search_hit.as_ref(), forward) {
+        Some(hit) => {
+            *ui_state.selected_packet_id = Some(hit.record_id);
+            *ui_state.packet_search_scroll_to = Some(hit.record_id);
+            *ui_state.packet_autoscroll = false;
+            ui_state.packet_tag_editor.clear();
+            *ui_state.packet_search_hit = Some(hit);
+        }
+        None => {
+            *ui_state.packet_search_hit = None;
+            ui_state
+                .notification_manager
+                .show_info("Search", "No matches in the visible packets");
+        }
+    }
+}
+
+/// Следующее (или предыдущее) совпадение после `current`, с переходом через край списка.
+fn find_next_hit(
+    ordered: &[&PacketRecord],
+    pattern: &SearchPattern,
+    current: Option<&SearchHit>,
+    forward: bool,
+) -> Option<SearchHit> {
+    let n = ordered.len();
+    if n == 0 {
+        return None;
+    }
+    let current = current.and_then(|h| {
+        ordered
+            .iter()
+            .position(|r| r.id == h.record_id)
+            .map(|row| (row, &h.location))
+    });
+    let start = match current {
+        Some((row, _)) => row,
+        None if forward => 0,
+        None => n - 1,
+    };
+
+    for k in 0..=n {
+        let row = if forward { (start + k) % n } else { (start + n - k % n) % n };
+        let rec = ordered[row];
+        let hits = pattern.find_all(rec, &mut cached_decode);
+        if hits.is_empty() {
+            continue;
+        }
+        let pick = match current {
+            Some((_, loc)) if k == 0 => {
+                let Some(p) = hits.iter().position(|h| h == loc) else { continue };
+                if forward { hits.get(p + 1) } else { p.checked_sub(1).and_then(|p| hits.get(p)) }
+            }
+            _ if forward => hits.first(),
+            _ => hits.last(),
+        };
+        if let Some(loc) = pick {
+            return Some(SearchHit { record_id: rec.id, location: loc.clone() });
+        }
+    }
+    None
+}
+
+fn session_label(s: &PacketSession) -> String {
+    let server = s.server.as_deref().unwrap_or("unknown server");
+    let account = s.account.as_deref().unwrap_or("?");
+    let end = match s.ended_millis {
+        Some(t) => format_time(t),
+        None => "now".to_string(),
+    };
+    format!("#{} {server} ({account}) {}-{end}, {} pkts", s.id, format_time(s.started_millis), s.packets)
+}
+
+fn render_session_bar(ui: &mut Ui, ui_state: &mut UiState, all: &[PacketRecord]) {
+    let sessions: Vec<PacketSession> = GlobalState::instance()
+        .get_packet_store()
+        .get()
+        .map(|store| store.lock().sessions().to_vec())
+        .unwrap_or_default();
+    if ui_state
+        .packet_session_filter
+        .is_some_and(|id| !sessions.iter().any(|s| s.id == id))
+    {
+        *ui_state.packet_session_filter = None;
+    }
+
+    ui.horizontal_wrapped(|ui| {
+        ui.label("Session:");
+        let selected = ui_state
+            .packet_session_filter
+            .and_then(|id| sessions.iter().find(|s| s.id == id))
+            .map(session_label)
+            .unwrap_or_else(|| "All".to_string());
+        egui::ComboBox::from_id_salt("packet_session_picker")
+            .selected_text(selected)
+            .width(320.0)
+            .show_ui(ui, |ui| {
+                ui.selectable_value(ui_state.packet_session_filter, None, "All");
+                for s in sessions.iter().rev() {
+                    let label = if s.is_open() {
+                        RichText::new(session_label(s)).color(Color32::LIGHT_GREEN)
+                    } else {
+                        RichText::new(session_label(s))
+                    };
+                    ui.selectable_value(ui_state.packet_session_filter, Some(s.id), label);
+                }
+            });
+
+        let Some(session) = ui_state
+            .packet_session_filter
+            .and_then(|id| sessions.iter().find(|s| s.id == id))
+        else {
+            return;
+        };
+        if ui.button("Copy session JSON").clicked() {
+            let records: Vec<&PacketRecord> = all.iter().filter(|r| r.session == Some(session.id)).collect();
+            let export = serde_json::json!({ "session": session, "records": records });
+            if let Ok(text) = serde_json::to_string_pretty(&export) {
+                let _ = ui_state.clipboard.set_text(&text);
+                ui_state.notification_manager.show_success(
+                    "Exported",
+                    &format!("Session #{}: {} packets copied as JSON", session.id, records.len()),
+                );
+            }
+        }
+        if ui.button("Copy session CSV").clicked() {
+            let records: Vec<PacketRecord> = all.iter().filter(|r| r.session == Some(session.id)).cloned().collect();
+            let _ = ui_state.clipboard.set_text(&packets_to_csv(&records));
+            ui_state.notification_manager.show_success(
+                "Exported",
+                &format!("Session #{}: {} packets copied as CSV", session.id, records.len()),
+            );
+        }
+        if session.packets as usize > all.iter().filter(|r| r.session == Some(session.id)).count() {
+            ui.colored_label(Color32::GRAY, "older packets of this session were evicted");
+        }
     });
 }
 
 fn render_packets_panel_with_height(ui: &mut Ui, ui_state: &mut UiState, list_h: f32) {
-    let mut records = if let Some(store) = GlobalState::instance().get_packet_store().get() {
+    if *ui_state.packet_snapshots_open {
+        egui::SidePanel::left("packet_snapshots_sidebar")
+            .resizable(true)
+            .default_width(200.0)
+            .show_inside(ui, |ui| render_snapshots_sidebar(ui, ui_state));
+    }
+
+    let mut records = if let Some(snapshot) = viewed_snapshot_records(ui_state) {
+        snapshot
+    } else if let Some(store) = GlobalState::instance().get_packet_store().get() {
         store.lock().snapshot()
     } else {
         Vec::new()
     };
+    let pairs = correlation_map(&records, ui_state.packet_correlation_rules);
+    render_snapshot_banner(ui, ui_state, records.len());
+    render_session_bar(ui, ui_state, &records);
 
     records.retain(|r| {
         (*ui_state.packet_show_inbound && r.dir == PacketDirection::Inbound)
             || (*ui_state.packet_show_outbound && r.dir == PacketDirection::Outbound)
     });
+    if let Some(sid) = *ui_state.packet_session_filter {
+        records.retain(|r| r.session == Some(sid));
+    }
 
     let max_seen = records.iter().map(|r| r.id).max().unwrap_or(0);
 
-    let f = ui_state.packet_filter.trim();
-    let needle = if f.is_empty() { None } else { Some(f.to_ascii_lowercase()) };
-
-    if let Some(needle_lc) = needle.as_ref() {
-        records.retain(|r| record_matches_query(r, needle_lc));
+    if let Ok(Some(query)) = compile_query(ui_state.packet_filter) {
+        records.retain(|r| query.matches(r, &mut cached_decode));
     }
 
     if *ui_state.packet_only_pinned {
@@ -336,6 +795,15 @@ fn render_packets_panel_with_height(ui: &mut Ui, ui_state: &mut UiState, list_h:
         *ui_state.packet_last_seen_id = max_seen;
     }
 
+    render_packet_timeline(ui, ui_state, &records);
+    if let Some((from, to)) = *ui_state.packet_time_range {
+        records.retain(|r| r.ts_millis >= from && r.ts_millis <= to);
+    }
+    if let Some((column, descending)) = ui_state.packet_sort.clone() {
+        sort_records(&mut records, &column, descending, &mut cached_decode);
+    }
+    let base_ts = records.iter().map(|r| r.ts_millis).min().unwrap_or(0);
+
     let total = records.len();
 
     let row_h = {
@@ -344,15 +812,43 @@ fn render_packets_panel_with_height(ui: &mut Ui, ui_state: &mut UiState, list_h:
     };
 
     ui.separator();
+    render_search_bar(ui, ui_state, &records);
+
+    ui.horizontal(|ui| {
+        ui.toggle_value(ui_state.packet_snapshots_open, "Snapshots");
+        render_columns_menu(ui, ui_state);
+        ui.checkbox(ui_state.packet_split_rows, "Split in/out")
+            .on_hover_text("Inbound on the left, outbound on the right");
+        if ui_state.packet_sort.is_some() && ui.small_button("Arrival order").clicked() {
+            *ui_state.packet_sort = None;
+        }
+    });
+    let (header, _) = ui.allocate_exact_size(vec2(ui.available_width(), row_h), Sense::hover());
+    if *ui_state.packet_split_rows {
+        let (left, right) = split_row(header);
+        render_column_header(ui, ui_state, left, left.left() + ROW_TEXT_LEFT, left.right() - 8.0);
+        render_column_header(ui, ui_state, right, right.left() + ROW_TEXT_LEFT, right.right() - 8.0);
+    } else {
+        render_column_header(ui, ui_state, header, header.left() + ROW_TEXT_LEFT, header.right() - 8.0);
+    }
 
     let top_anchor = ui.min_rect();
 
     egui::Frame::default().show(ui, |ui| {
         ui.set_max_height(list_h);
 
-        ScrollArea::vertical()
+        let mut scroll = ScrollArea::vertical()
             .auto_shrink([false; 2])
-            .max_height(list_h)
+            .max_height(list_h);
+        // строки идут от новых к старым, поэтому позиция считается с конца
+        if let Some(target) = ui_state.packet_search_scroll_to.take() {
+            if let Some(i) = records.iter().position(|r| r.id == target) {
+                let row = (total - 1 - i) as f32;
+                let offset = row * (row_h + ui.spacing().item_spacing.y) - list_h * 0.5;
+                scroll = scroll.vertical_scroll_offset(offset.max(0.0));
+            }
+        }
+        scroll
             .show_rows(ui, row_h, total, |ui, row_range| {
                 for idx in row_range {
                     let i = total - 1 - idx;
@@ -363,18 +859,19 @@ fn render_packets_panel_with_height(ui: &mut Ui, ui_state: &mut UiState, list_h:
                         Sense::hover(),
                     );
 
-                    // делим пополам для inbound/outbound
-                    let mid = rect.center().x;
-                    let (left_rect, right_rect) = (
-                        egui::Rect::from_min_max(rect.min, pos2(mid - 4.0, rect.max.y)),
-                        egui::Rect::from_min_max(pos2(mid + 4.0, rect.min.y), rect.max),
-                    );
+                    let rect = if *ui_state.packet_split_rows {
+                        let (left, right) = split_row(rect);
+                        match rec.dir {
+                            PacketDirection::Inbound => left,
+                            PacketDirection::Outbound => right,
+                        }
+                    } else {
+                        rect
+                    };
 
-                    let highlight = row_highlight_color(rec, ui_state);
-                    match rec.dir {
-                        PacketDirection::Inbound => render_row_cell_in(ui, rec, left_rect, row_h, ui_state, highlight),
-                        PacketDirection::Outbound => render_row_cell_out(ui, rec, right_rect, row_h, ui_state, highlight),
-                    }
+                    let highlight = row_highlight_color(rec);
+                    let rtt = pairs.get(&rec.id).map(|p| p.latency_ms);
+                    render_row(ui, rec, rect, ui_state, highlight, rtt, base_ts);
                 }
             });
 
@@ -413,42 +910,76 @@ fn render_packets_panel_with_height(ui: &mut Ui, ui_state: &mut UiState, list_h:
             }
         }
     });
+    ui.horizontal(|ui| {
+        ui.add(
+            egui::TextEdit::singleline(ui_state.packet_snapshot_name)
+                .hint_text("snapshot name")
+                .desired_width(160.0),
+        );
+        let can_freeze = !ui_state.packet_snapshot_name.trim().is_empty() && total > 0;
+        if ui
+            .add_enabled(can_freeze, egui::Button::new("Snapshot filtered"))
+            .on_hover_text("Freeze all filtered records under a name; ring-buffer eviction does not touch them")
+            .clicked()
+        {
+            freeze_snapshot(ui_state, &records);
+        }
+    });
 
     if let Some(sel_id) = *ui_state.selected_packet_id {
-        if let Some(rec) = records.iter().find(|r| r.id == sel_id) {
-            render_details_panel(ui, rec, ui_state);
+        // пара могла оказаться скрыта фильтром — детали всё равно показываем
+        let visible = records.iter().find(|r| r.id == sel_id).cloned();
+        let hidden = visible.is_none();
+        if let Some(rec) = visible.or_else(|| find_packet(sel_id)) {
+            if hidden {
+                ui.colored_label(Color32::GRAY, "Selected packet is hidden by the current filter");
+            }
+            render_details_panel(ui, &rec, ui_state, pairs.get(&rec.id).copied());
         }
     }
-}
 
-fn render_row_cell_in(ui: &mut Ui, rec: &PacketRecord, rect: egui::Rect, row_h: f32, ui_state: &mut UiState, highlight: Option<Color32>) {
-    render_row_cell_common(ui, rec, rect, row_h, ui_state, Color32::from_rgb(18, 24, 32), "<-", highlight);
+    render_hex_editor(ui, ui_state);
 }
 
-fn render_row_cell_out(ui: &mut Ui, rec: &PacketRecord, rect: egui::Rect, row_h: f32, ui_state: &mut UiState, highlight: Option<Color32>) {
-    render_row_cell_common(ui, rec, rect, row_h, ui_state, Color32::from_rgb(24, 18, 18), "->", highlight);
+/// Отступ первой колонки от края строки: слева кнопка pin
+const ROW_TEXT_LEFT: f32 = 36.0;
+
+/// делим пополам для inbound/outbound
+fn split_row(rect: egui::Rect) -> (egui::Rect, egui::Rect) {
+    let mid = rect.center().x;
+    (
+        egui::Rect::from_min_max(rect.min, pos2(mid - 4.0, rect.max.y)),
+        egui::Rect::from_min_max(pos2(mid + 4.0, rect.min.y), rect.max),
+    )
 }
 
-fn render_row_cell_common(
+fn render_row(
     ui: &mut Ui,
     rec: &PacketRecord,
     rect: egui::Rect,
-    row_h: f32,
     ui_state: &mut UiState,
-    bg: Color32,
-    arrow: &str,
     highlight: Option<Color32>,
+    rtt: Option<u64>,
+    base_ts: u64,
 ) {
     let id = egui::Id::new(("row", rec.id));
     let resp = ui.interact(rect, id, Sense::click());
 
+    let bg = match rec.dir {
+        PacketDirection::Inbound => Color32::from_rgb(18, 24, 32),
+        PacketDirection::Outbound => Color32::from_rgb(24, 18, 18),
+    };
     let tint = highlight.unwrap_or_else(|| bg.linear_multiply(0.35));
     ui.painter().rect_filled(rect, 4.0, tint);
 
-    let short = rec.name.rsplit('.').next().unwrap_or(&rec.name);
-    let t = format_time(rec.ts_millis);
-    let title = format!("{arrow} [{t}] {short}");
-    let meta = format!("{} bytes", rec.len);
+    let (type_note, record_note) = record_notes(rec);
+    let mut meta = Vec::new();
+    if type_note.is_some() || record_note.is_some() {
+        meta.push("note".to_string());
+    }
+    if let Some(ms) = rtt {
+        meta.push(format!("rtt {ms} ms"));
+    }
 
     let left = rect.left() + 8.0;
     let top = rect.center().y - ui.text_style_height(&TextStyle::Body) * 0.5;
@@ -470,27 +1001,35 @@ fn render_row_cell_common(
         if rec.pinned { Color32::YELLOW } else { Color32::GRAY },
     );
 
-    let text_left = left + 28.0;
     if pin_resp.clicked() {
         if let Some(store) = GlobalState::instance().get_packet_store().get() {
             store.lock().pin(rec.id, !rec.pinned);
         }
     }
-    ui.painter().text(
-        pos2(text_left, top),
-        egui::Align2::LEFT_TOP,
-        title,
-        TextStyle::Body.resolve(ui.style()),
-        Color32::WHITE,
-    );
 
-    ui.painter().text(
-        pos2(rect.right() - 8.0, top),
-        egui::Align2::RIGHT_TOP,
-        meta,
-        TextStyle::Monospace.resolve(ui.style()),
-        Color32::GRAY,
-    );
+    let mut right = rect.right() - 8.0;
+    if !meta.is_empty() {
+        let galley = ui.painter().layout_no_wrap(
+            meta.join("  "),
+            TextStyle::Monospace.resolve(ui.style()),
+            Color32::GRAY,
+        );
+        right -= galley.size().x;
+        ui.painter().galley(pos2(right, top), galley, Color32::GRAY);
+        right -= 8.0;
+    }
+
+    let cells = column_rects(rect, rect.left() + ROW_TEXT_LEFT, right, ui_state.packet_columns);
+    for (col, cell) in ui_state.packet_columns.iter().zip(cells) {
+        let text = col.cell_text(rec, base_ts, &mut cached_decode);
+        ui.painter().with_clip_rect(cell).text(
+            pos2(cell.left(), top),
+            egui::Align2::LEFT_TOP,
+            text,
+            TextStyle::Body.resolve(ui.style()),
+            col.color(),
+        );
+    }
 
     let is_selected = matches!(ui_state.selected_packet_id.as_ref(), Some(x) if *x == rec.id);
     if is_selected {
@@ -507,13 +1046,18 @@ fn render_row_cell_common(
     }
 
     if resp.hovered() {
-        resp.on_hover_text(format!("{} bytes", rec.data.len()));
+        let mut hover = format!("{} bytes", rec.data.len());
+        if let Some(n) = &type_note {
+            hover.push_str(&format!("\n\n{}: {}", type_key(rec), n.text));
+        }
+        if let Some(n) = &record_note {
+            hover.push_str(&format!("\n\nThis packet: {}", n.text));
+        }
+        resp.on_hover_text(hover);
     }
-
-    let _ = row_h;
 }
 
-fn render_details_panel(ui: &mut Ui, rec: &PacketRecord, ui_state: &mut UiState) {
+fn render_details_panel(ui: &mut Ui, rec: &PacketRecord, ui_state: &mut UiState, pair: Option<CorrelatedPair>) {
     let arrow = match rec.dir {
         PacketDirection::Outbound => "->",
         PacketDirection::Inbound => "<-",
@@ -527,32 +1071,51 @@ fn render_details_panel(ui: &mut Ui, rec: &PacketRecord, ui_state: &mut UiState)
         .default_open(true)
         .show(ui, |ui| {
             let mut decoded: Option<DecodedStruct> = None;
+            let (byte_hit, field_hit) = match ui_state.packet_search_hit.as_ref() {
+                Some(hit) if hit.record_id == rec.id => match &hit.location {
+                    HitLocation::Bytes { offset, len } => (Some(*offset..*offset + *len), None),
+                    HitLocation::Field { path } => (None, Some(path.clone())),
+                },
+                _ => (None, None),
+            };
+
+            let mut marks: FieldMarks = HashMap::new();
+            if let Some(path) = field_hit {
+                marks.insert(path, SEARCH_HIT_BG);
+            }
 
             if let Some(PacketDetails::CustomPayload { channel, channel_len, preview }) = &rec.details {
-                ui.monospace(format!("channel: {channel} ({} bytes)", channel_len));
+                ui.horizontal(|ui| {
+                    ui.monospace(format!("channel: {channel} ({} bytes)", channel_len));
+                    if ui.small_button("Infer layout").on_hover_text("Guess field layout from all records of this channel").clicked() {
+                        run_layout_inference(ui_state, channel);
+                    }
+                });
                 if let Some(p) = preview { ui.monospace(format!("preview: \"{p}\"")); }
 
-                let bound = match rec.dir {
-                    PacketDirection::Inbound  => Bound::Server,
-                    PacketDirection::Outbound => Bound::Client,
-                };
+                decoded = cached_decode(rec);
+                ui.add_space(4.0);
+            }
 
-                let cached = {
-                    let caches = CACHES.lock().unwrap();
-                    caches.decoded_cache.get(&rec.id).cloned()
-                };
-                decoded = if let Some(d) = cached {
-                    Some(d)
-                } else {
-                    let d = decode_custom_payload(channel, &rec.data, bound);
-                    if let Some(ref dd) = d {
-                        if let Ok(mut caches) = CACHES.lock() {
-                            caches.decoded_cache.insert(rec.id, dd.clone());
-                        }
+            if let Some(pair) = pair {
+                ui.horizontal(|ui| {
+                    let (role, jump) = if rec.id == pair.request_id {
+                        ("Request", "Go to response")
+                    } else {
+                        ("Response", "Go to request")
+                    };
+                    ui.label(
+                        RichText::new(format!("{role}, round trip {} ms", pair.latency_ms))
+                            .color(Color32::LIGHT_GREEN),
+                    );
+                    let other = pair.other(rec.id);
+                    if ui.button(format!("{jump} #{other}")).clicked() {
+                        *ui_state.selected_packet_id = Some(other);
+                        *ui_state.packet_search_scroll_to = Some(other);
+                        *ui_state.packet_autoscroll = false;
+                        ui_state.packet_tag_editor.clear();
                     }
-                    d
-                };
-                ui.add_space(4.0);
+                });
             }
 
             ui.horizontal(|ui| {
@@ -580,6 +1143,9 @@ fn render_details_panel(ui: &mut Ui, rec: &PacketRecord, ui_state: &mut UiState)
                     let b64 = base64::engine::general_purpose::STANDARD.encode(&rec.data);
                     let _ = ui_state.clipboard.set_text(&b64);
                 }
+                if ui.button("Edit bytes").on_hover_text("Open a copy in the hex editor").clicked() {
+                    *ui_state.packet_hex_editor = Some(HexEditor::open(rec));
+                }
                 if ui.button("Unselect").clicked() {
                     *ui_state.selected_packet_id = None;
                 }
@@ -625,27 +1191,58 @@ fn render_details_panel(ui: &mut Ui, rec: &PacketRecord, ui_state: &mut UiState)
                 }
             });
 
+            render_notes_editor(ui, rec, ui_state);
+
+            let target = ui_state
+                .packet_secondary_selected_id
+                .filter(|id| *id != rec.id)
+                .and_then(find_packet);
             ui.horizontal(|ui| {
                 if ui.button("Set as compare target").clicked() {
                     *ui_state.packet_secondary_selected_id = Some(rec.id);
                 }
-                if let Some(other_id) = *ui_state.packet_secondary_selected_id {
-                    if other_id != rec.id {
-                        if let Some(other) = find_packet(other_id) {
-                            render_diff_section(ui, rec, &other);
-                        } else {
-                            ui.label("Compare target missing");
-                        }
+                match (*ui_state.packet_secondary_selected_id, target.as_ref()) {
+                    (Some(id), _) if id == rec.id => {
+                        ui.colored_label(Color32::GRAY, "This packet is the compare target");
                     }
+                    (Some(id), None) => {
+                        ui.label(format!("Compare target #{id} missing"));
+                    }
+                    _ => {}
+                }
+                if ui_state.packet_secondary_selected_id.is_some() && ui.button("Clear target").clicked() {
+                    *ui_state.packet_secondary_selected_id = None;
                 }
             });
 
+            if let Some(other) = target.as_ref() {
+                let other_decoded = decoded.as_ref().and_then(|_| cached_decode(other));
+                match (decoded.as_ref(), other_decoded.as_ref()) {
+                    (Some(da), Some(db)) => {
+                        let changes = diff_structs(da, db);
+                        for c in &changes {
+                            match c.change {
+                                FieldChange::Changed { .. } => {
+                                    marks.entry(c.path.clone()).or_insert(DIFF_CHANGED_BG);
+                                }
+                                FieldChange::Removed(_) => {
+                                    marks.entry(c.path.clone()).or_insert(DIFF_REMOVED_BG);
+                                }
+                                FieldChange::Added(_) => {}
+                            }
+                        }
+                        render_struct_diff(ui, rec, other, &changes);
+                    }
+                    _ => render_byte_diff(ui, rec, other),
+                }
+            }
+
             if let Some(ds) = decoded.as_ref() {
                 ui.add_space(8.0);
                 ui.separator();
                 ui.add_space(4.0);
                 ui.label(RichText::new("Decoded").strong().color(Color32::LIGHT_BLUE));
-                render_decoded_struct(ui, ds, ui_state);
+                render_decoded_struct(ui, ds, ui_state, "", &marks);
             }
 
             ui.add_space(6.0);
@@ -654,50 +1251,124 @@ fn render_details_panel(ui: &mut Ui, rec: &PacketRecord, ui_state: &mut UiState)
             ui.label(RichText::new("Hex dump").strong());
 
             let width = 16usize;
-            let dump = {
-                let mut caches = CACHES.lock().unwrap();
-                caches
-                    .details_hex_cache
-                    .entry((rec.id, width))
-                    .or_insert_with(|| hex_dump(&rec.data, width))
-                    .clone()
-            };
-            ui.monospace(dump);
+            if let Some(range) = byte_hit.clone() {
+                ui.label(hex_dump_job(ui, &rec.data, width, range));
+            } else {
+                let dump = {
+                    let mut caches = CACHES.lock().unwrap();
+                    caches
+                        .details_hex_cache
+                        .entry((rec.id, width))
+                        .or_insert_with(|| hex_dump(&rec.data, width))
+                        .clone()
+                };
+                ui.monospace(dump);
+            }
+        });
+}
+
+fn render_notes_editor(ui: &mut Ui, rec: &PacketRecord, ui_state: &mut UiState) {
+    let Some(notes) = GlobalState::instance().get_annotations().get() else { return };
+    if *ui_state.packet_note_loaded_for != Some(rec.id) {
+        let (on_type, on_record) = record_notes(rec);
+        *ui_state.packet_note_type_input = on_type.map(|n| n.text).unwrap_or_default();
+        *ui_state.packet_note_record_input = on_record.map(|n| n.text).unwrap_or_default();
+        *ui_state.packet_note_loaded_for = Some(rec.id);
+    }
+
+    let key = type_key(rec);
+    egui::CollapsingHeader::new("Notes")
+        .id_salt("packet_notes")
+        .default_open(!ui_state.packet_note_type_input.is_empty() || !ui_state.packet_note_record_input.is_empty())
+        .show(ui, |ui| {
+            let mut result = None;
+            ui.label(format!("Type {key} (shown on every matching packet):"));
+            ui.add(
+                egui::TextEdit::multiline(ui_state.packet_note_type_input)
+                    .desired_rows(2)
+                    .desired_width(f32::INFINITY),
+            );
+            if ui.button("Save type note").clicked() {
+                result = Some(notes.lock().set_type_note(rec, ui_state.packet_note_type_input));
+            }
+
+            ui.label("This packet (matched by content):");
+            ui.add(
+                egui::TextEdit::multiline(ui_state.packet_note_record_input)
+                    .desired_rows(2)
+                    .desired_width(f32::INFINITY),
+            );
+            if ui.button("Save packet note").clicked() {
+                result = Some(notes.lock().set_record_note(rec, ui_state.packet_note_record_input));
+            }
+
+            match result {
+                Some(Ok(())) => {
+                    ui_state.notification_manager.show_success("Notes", "Saved");
+                }
+                Some(Err(e)) => {
+                    ui_state.notification_manager.show_error("Notes", &e);
+                }
+                None => {}
+            }
         });
 }
 
-fn render_decoded_struct(ui: &mut Ui, s: &DecodedStruct, ui_state: &mut UiState) {
+/// Подсветка полей по пути (как в `lookup_field`): найденное поиском, изменённое в diff
+pub type FieldMarks = HashMap<String, Color32>;
+
+/// `prefix` — путь до структуры, `marks` — подсвечиваемые поля.
+pub fn render_decoded_struct(ui: &mut Ui, s: &DecodedStruct, ui_state: &mut UiState, prefix: &str, marks: &FieldMarks) {
     egui::CollapsingHeader::new(&s.name)
         .default_open(true)
         .show(ui, |ui| {
             for f in &s.fields {
-                render_field_row(ui, &f.name, &f.value, ui_state);
+                let path = join_path(prefix, &f.name);
+                render_field_row(ui, &f.name, &f.value, ui_state, &path, marks);
             }
         });
 }
 
-fn render_field_row(ui: &mut Ui, name: &str, value: &DecodedValue, ui_state: &mut UiState) {
+fn join_path(prefix: &str, seg: &str) -> String {
+    if prefix.is_empty() { seg.to_string() } else { format!("{prefix}.{seg}") }
+}
+
+fn marked(text: RichText, mark: Option<Color32>) -> RichText {
+    match mark {
+        Some(bg) => text.color(Color32::WHITE).background_color(bg),
+        None => text,
+    }
+}
+
+fn render_field_row(ui: &mut Ui, name: &str, value: &DecodedValue, ui_state: &mut UiState, path: &str, marks: &FieldMarks) {
+    let mark = marks.get(path).copied();
+    // списки свёрнуты по умолчанию; если внутри есть подсветка — отдельное состояние, открытое по умолчанию
+    let mark_inside = marks
+        .keys()
+        .any(|k| k.len() > path.len() && k.starts_with(path) && k.as_bytes()[path.len()] == b'.');
     match value {
         DecodedValue::Struct(st) => {
-            egui::CollapsingHeader::new(format!("{name}:"))
+            egui::CollapsingHeader::new(marked(RichText::new(format!("{name}:")), mark))
+                .id_salt(path)
                 .default_open(true)
-                .show(ui, |ui| render_decoded_struct(ui, st, ui_state));
+                .show(ui, |ui| render_decoded_struct(ui, st, ui_state, path, marks));
         }
         DecodedValue::List(items) => {
-            egui::CollapsingHeader::new(format!("{name}: [{}]", items.len()))
-                .default_open(false)
+            egui::CollapsingHeader::new(marked(RichText::new(format!("{name}: [{}]", items.len())), mark))
+                .id_salt((path, mark_inside))
+                .default_open(mark_inside)
                 .show(ui, |ui| {
                     for (i, it) in items.iter().enumerate() {
-                        render_field_row(ui, &format!("#{i}"), it, ui_state);
+                        let item_path = join_path(path, &i.to_string());
+                        render_field_row(ui, &format!("#{i}"), it, ui_state, &item_path, marks);
                     }
                 });
         }
         DecodedValue::Bytes(bytes) => {
             ui.horizontal_wrapped(|ui| {
-                ui.monospace(format!(
-                    "{name}: <{} bytes>  {}",
-                    bytes.len(),
-                    first_bytes_hex(bytes, 32)
+                ui.label(marked(
+                    RichText::new(format!("{name}: <{} bytes>  {}", bytes.len(), first_bytes_hex(bytes, 32))).monospace(),
+                    mark,
                 ));
                 if ui.button("Copy field hex").clicked() {
                     let _ = ui_state.clipboard.set_text(&hex_dump(bytes, 16));
@@ -709,10 +1380,10 @@ fn render_field_row(ui: &mut Ui, name: &str, value: &DecodedValue, ui_state: &mu
             });
         }
         DecodedValue::Text(t) => {
-            ui.monospace(format!("{name}: {t}"));
+            ui.label(marked(RichText::new(format!("{name}: {t}")).monospace(), mark));
         }
         DecodedValue::Null => {
-            ui.monospace(format!("{name}: null"));
+            ui.label(marked(RichText::new(format!("{name}: null")).monospace(), mark));
         }
     }
 }
@@ -723,36 +1394,114 @@ fn find_packet(id: u64) -> Option<PacketRecord> {
     snap.into_iter().find(|r| r.id == id)
 }
 
-fn render_diff_section(ui: &mut Ui, a: &PacketRecord, b: &PacketRecord) {
-    let len_a = a.data.len();
-    let len_b = b.data.len();
-    let min_len = len_a.min(len_b);
-    let first_diff = (0..min_len).find(|i| a.data[*i] != b.data[*i]);
-    let diff_count = a
-        .data
-        .iter()
-        .take(min_len)
-        .zip(b.data.iter())
-        .filter(|(x, y)| x != y)
-        .count()
-        + len_a.saturating_sub(min_len)
-        + len_b.saturating_sub(min_len);
-
-    ui.vertical(|ui| {
-        ui.label(format!(
-            "Diff vs {}: len {} vs {}, mismatches {}",
-            b.id, len_a, len_b, diff_count
-        ));
-        if let Some(idx) = first_diff {
-            ui.monospace(format!("First mismatch at byte {idx}: {:02X} vs {:02X}", a.data[idx], b.data[idx]));
-        } else {
-            ui.monospace("Bodies match up to common length");
-        }
-        let prev_a = first_bytes_hex(&a.data, 32);
-        let prev_b = first_bytes_hex(&b.data, 32);
-        ui.monospace(format!("A head: {prev_a}"));
-        ui.monospace(format!("B head: {prev_b}"));
-    });
+const DIFF_CHANGED_BG: Color32 = Color32::from_rgb(120, 100, 20);
+const DIFF_REMOVED_BG: Color32 = Color32::from_rgb(120, 35, 35);
+const DIFF_ADDED_BG: Color32 = Color32::from_rgb(30, 100, 45);
+/// Сколько строк по 16 выровненных байт показываем в byte diff
+const BYTE_DIFF_MAX_ROWS: usize = 256;
+
+/// Оба пакета расшифрованы: список отличий по полям, само дерево подсвечивается через `marks`.
+fn render_struct_diff(ui: &mut Ui, a: &PacketRecord, b: &PacketRecord, changes: &[FieldDiff]) {
+    let count = |f: fn(&FieldChange) -> bool| changes.iter().filter(|c| f(&c.change)).count();
+    let changed = count(|c| matches!(c, FieldChange::Changed { .. }));
+    let added = count(|c| matches!(c, FieldChange::Added(_)));
+    let removed = count(|c| matches!(c, FieldChange::Removed(_)));
+
+    ui.label(RichText::new(format!("Structural diff #{} -> #{}", a.id, b.id)).strong());
+    if changes.is_empty() {
+        let note = if a.data == b.data { "identical" } else { "same decoded fields, raw bytes differ" };
+        ui.colored_label(Color32::GRAY, format!("Decoded trees match ({note})"));
+        return;
+    }
+    ui.monospace(format!("{changed} changed, {added} added, {removed} removed"));
+
+    ScrollArea::vertical()
+        .id_salt(("struct_diff", a.id, b.id))
+        .max_height(220.0)
+        .show(ui, |ui| {
+            egui::Grid::new(("struct_diff_grid", a.id, b.id))
+                .num_columns(3)
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.label(RichText::new("Field").strong());
+                    ui.label(RichText::new(format!("#{}", a.id)).strong());
+                    ui.label(RichText::new(format!("#{}", b.id)).strong());
+                    ui.end_row();
+                    for c in changes {
+                        let path = if c.path.is_empty() { "<packet>" } else { c.path.as_str() };
+                        let (old, new, bg) = match &c.change {
+                            FieldChange::Changed { old, new } => (old.as_str(), new.as_str(), DIFF_CHANGED_BG),
+                            FieldChange::Removed(v) => (v.as_str(), "-", DIFF_REMOVED_BG),
+                            FieldChange::Added(v) => ("-", v.as_str(), DIFF_ADDED_BG),
+                        };
+                        ui.label(marked(RichText::new(path).monospace(), Some(bg)));
+                        ui.monospace(old);
+                        ui.monospace(new);
+                        ui.end_row();
+                    }
+                });
+        });
+}
+
+/// Хотя бы один пакет не расшифрован: выровненный по LCS byte diff в две строки.
+fn render_byte_diff(ui: &mut Ui, a: &PacketRecord, b: &PacketRecord) {
+    let ops = cached_byte_diff(a, b);
+    let pairs = align_pairs(&a.data, &b.data, &ops);
+    let differing = pairs.iter().filter(|p| p.2 != ByteOpKind::Equal).count();
+
+    ui.label(RichText::new(format!("Byte diff #{} -> #{}", a.id, b.id)).strong());
+    ui.monospace(format!(
+        "len {} vs {}, {} differing positions in {} regions",
+        a.data.len(),
+        b.data.len(),
+        differing,
+        ops.iter().filter(|o| o.kind != ByteOpKind::Equal).count()
+    ));
+    if differing == 0 {
+        ui.colored_label(Color32::GRAY, "Bodies are identical");
+        return;
+    }
+
+    let font = TextStyle::Monospace.resolve(ui.style());
+    let plain = TextFormat::simple(font.clone(), ui.visuals().text_color());
+    let dim = TextFormat::simple(font.clone(), Color32::GRAY);
+    let with_bg = |bg: Color32| TextFormat { background: bg, ..TextFormat::simple(font.clone(), Color32::WHITE) };
+    let fmt_for = |kind: ByteOpKind| match kind {
+        ByteOpKind::Equal => plain.clone(),
+        ByteOpKind::Replace => with_bg(DIFF_CHANGED_BG),
+        ByteOpKind::Delete => with_bg(DIFF_REMOVED_BG),
+        ByteOpKind::Insert => with_bg(DIFF_ADDED_BG),
+    };
+
+    let width = 16usize;
+    let mut job = LayoutJob::default();
+    let (mut off_a, mut off_b) = (0usize, 0usize);
+    for row in pairs.chunks(width).take(BYTE_DIFF_MAX_ROWS) {
+        for (side, off) in [(0usize, off_a), (1usize, off_b)] {
+            job.append(&format!("{} {:04X}: ", if side == 0 { "A" } else { "B" }, off), 0.0, dim.clone());
+            for (x, y, kind) in row {
+                match if side == 0 { x } else { y } {
+                    Some(v) => job.append(&format!("{v:02X}"), 0.0, fmt_for(*kind)),
+                    None => job.append("--", 0.0, dim.clone()),
+                }
+                job.append(" ", 0.0, plain.clone());
+            }
+            job.append("\n", 0.0, plain.clone());
+        }
+        job.append("\n", 0.0, plain.clone());
+        off_a += row.iter().filter(|p| p.0.is_some()).count();
+        off_b += row.iter().filter(|p| p.1.is_some()).count();
+    }
+
+    ScrollArea::vertical()
+        .id_salt(("byte_diff", a.id, b.id))
+        .max_height(260.0)
+        .show(ui, |ui| {
+            ui.label(job);
+        });
+    if pairs.len() > width * BYTE_DIFF_MAX_ROWS {
+        ui.colored_label(Color32::GRAY, format!("Showing first {} aligned bytes", width * BYTE_DIFF_MAX_ROWS));
+    }
 }
 
 fn packets_to_csv(records: &[PacketRecord]) -> String {
@@ -784,7 +1533,16 @@ fn replay_from_json(text: &str) {
     if text.trim().is_empty() {
         return;
     }
-    let Ok(parsed) = serde_json::from_str::<Vec<PacketRecord>>(text) else { return; };
+    // массив записей или экспорт сессии { "session": .., "records": [..] }
+    let parsed = match serde_json::from_str::<Vec<PacketRecord>>(text) {
+        Ok(v) => v,
+        Err(_) => {
+            let Ok(serde_json::Value::Object(mut obj)) = serde_json::from_str::<serde_json::Value>(text) else { return; };
+            let Some(records) = obj.remove("records") else { return; };
+            let Ok(v) = serde_json::from_value::<Vec<PacketRecord>>(records) else { return; };
+            v
+        }
+    };
     if let Some(store) = GlobalState::instance().get_packet_store().get() {
         let mut guard = store.lock();
         for r in parsed {
@@ -825,6 +1583,38 @@ fn hex_dump(data: &[u8], width: usize) -> String {
     out
 }
 
+/// Тот же формат, что `hex_dump`, но с подсветкой диапазона `hl` в hex и ascii колонках.
+fn hex_dump_job(ui: &Ui, data: &[u8], width: usize, hl: Range<usize>) -> LayoutJob {
+    let font = TextStyle::Monospace.resolve(ui.style());
+    let plain = TextFormat::simple(font.clone(), ui.visuals().text_color());
+    let marked = TextFormat {
+        background: SEARCH_HIT_BG,
+        ..TextFormat::simple(font, Color32::WHITE)
+    };
+    let mut job = LayoutJob::default();
+    for (i, chunk) in data.chunks(width).enumerate() {
+        let base = i * width;
+        job.append(&format!("{:04X}:  ", base), 0.0, plain.clone());
+        for (j, b) in chunk.iter().enumerate() {
+            let pos = base + j;
+            let fmt = if hl.contains(&pos) { &marked } else { &plain };
+            job.append(&format!("{:02X}", b), 0.0, fmt.clone());
+            // пробел между подсвеченными байтами тоже подсвечиваем, чтобы было видно цельный кусок
+            let gap = if hl.contains(&pos) && hl.contains(&(pos + 1)) && j + 1 < chunk.len() { &marked } else { &plain };
+            job.append(" ", 0.0, gap.clone());
+        }
+        let pad = (width - chunk.len()) * 3 + 1;
+        job.append(&" ".repeat(pad), 0.0, plain.clone());
+        for (j, b) in chunk.iter().enumerate() {
+            let c = if b.is_ascii_graphic() || *b == b' ' { *b as char } else { '.' };
+            let fmt = if hl.contains(&(base + j)) { &marked } else { &plain };
+            job.append(&c.to_string(), 0.0, fmt.clone());
+        }
+        job.append("\n", 0.0, plain.clone());
+    }
+    job
+}
+
 fn first_bytes_hex(data: &[u8], n: usize) -> String {
     data.iter()
         .take(n)
@@ -833,20 +1623,6 @@ fn first_bytes_hex(data: &[u8], n: usize) -> String {
         .join("")
 }
 
-fn contains_ascii_ci(hay: &str, needle_lc: &str) -> bool {
-    if needle_lc.is_empty() {
-        return true;
-    }
-    let n = needle_lc.as_bytes();
-    let hb = hay.as_bytes();
-    if n.len() > hb.len() {
-        return false;
-    }
-    hb.windows(n.len()).any(|w| {
-        w.eq_ignore_ascii_case(n)
-    })
-}
-
 fn format_time(millis: u64) -> String {
     use chrono::{DateTime, Local, TimeZone, Utc};
     let secs = (millis / 1000) as i64;
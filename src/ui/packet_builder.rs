@@ -0,0 +1,234 @@
+use crate::core::packet_builder::{builder_packets, field_path, FieldError, FieldInput, FieldKind};
+use crate::core::packets::Bound;
+use crate::core::state::GlobalState;
+use crate::graphics::netlog::{make_record, PacketDirection};
+use crate::mappings::classes;
+use crate::ui::UiState;
+use base64::Engine;
+use egui::{Color32, RichText, Ui};
+
+/// Сколько байт показывать в превью собранного пакета
+const PREVIEW_BYTES: usize = 64;
+
+pub fn render_packet_builder_section(ui: &mut Ui, ui_state: &mut UiState) {
+    egui::CollapsingHeader::new("Packet builder")
+        .id_salt("packet_builder")
+        .default_open(false)
+        .show(ui, |ui| {
+            let packets = builder_packets();
+            let mut channels: Vec<&str> = packets.iter().map(|p| p.channel).collect();
+            channels.sort();
+            channels.dedup();
+
+            ui.horizontal_wrapped(|ui| {
+                ui.label("Channel:");
+                egui::ComboBox::from_id_salt("packet_builder_channel")
+                    .selected_text(if ui_state.packet_builder_channel.is_empty() {
+                        "select"
+                    } else {
+                        ui_state.packet_builder_channel.as_str()
+                    })
+                    .show_ui(ui, |ui| {
+                        for ch in &channels {
+                            if ui
+                                .selectable_label(ui_state.packet_builder_channel.as_str() == *ch, *ch)
+                                .clicked()
+                            {
+                                *ui_state.packet_builder_channel = ch.to_string();
+                                ui_state.packet_builder_packet.clear();
+                            }
+                        }
+                    });
+
+                ui.label("Packet:");
+                egui::ComboBox::from_id_salt("packet_builder_packet")
+                    .selected_text(if ui_state.packet_builder_packet.is_empty() {
+                        "select"
+                    } else {
+                        ui_state.packet_builder_packet.as_str()
+                    })
+                    .show_ui(ui, |ui| {
+                        for p in packets.iter().filter(|p| p.channel == ui_state.packet_builder_channel.as_str()) {
+                            let b = match p.bound {
+                                Bound::Server => "S",
+                                Bound::Client => "C",
+                            };
+                            let selected = ui_state.packet_builder_packet.as_str() == p.name;
+                            let resp = ui.add_enabled(
+                                p.encode.is_some(),
+                                egui::Button::selectable(selected, format!("{} (ID {}, {b})", p.name, p.id)),
+                            );
+                            if resp.on_disabled_hover_text("Server-bound: no encode()").clicked() {
+                                *ui_state.packet_builder_packet = p.name.to_string();
+                                *ui_state.packet_builder_input = FieldInput::empty(&p.fields);
+                            }
+                        }
+                    });
+            });
+
+            let Some(packet) = packets.iter().find(|p| {
+                p.channel == ui_state.packet_builder_channel.as_str() && p.name == ui_state.packet_builder_packet.as_str()
+            }) else {
+                ui.colored_label(Color32::GRAY, "Pick a channel and a client-bound packet to fill in its fields.");
+                return;
+            };
+
+            let result = packet.build(ui_state.packet_builder_input);
+            let errors = result.as_ref().err().cloned().unwrap_or_default();
+
+            if matches!(&packet.fields, FieldKind::Struct(specs) if specs.is_empty()) {
+                ui.colored_label(Color32::GRAY, "This packet has no fields.");
+            }
+            render_field_input(ui, None, &packet.fields, ui_state.packet_builder_input, "", &errors);
+
+            ui.add_space(4.0);
+            match result {
+                Ok(bytes) => {
+                    let mut preview = hex::encode(&bytes[..bytes.len().min(PREVIEW_BYTES)]);
+                    if bytes.len() > PREVIEW_BYTES {
+                        preview.push_str("...");
+                    }
+                    ui.label(RichText::new(preview).monospace().color(Color32::LIGHT_GRAY));
+                    ui.horizontal_wrapped(|ui| {
+                        ui.label(format!("{} bytes", bytes.len()));
+                        if ui.button("Copy hex").clicked() {
+                            let _ = ui_state.clipboard.set_text(&hex::encode(&bytes));
+                            ui_state.notification_manager.show_success("Packet builder", "Hex copied");
+                        }
+                        if ui.button("Copy base64").clicked() {
+                            let b64 = base64::engine::general_purpose::STANDARD.encode(&bytes);
+                            let _ = ui_state.clipboard.set_text(&b64);
+                            ui_state.notification_manager.show_success("Packet builder", "Base64 copied");
+                        }
+                        if ui.button("Add to store").clicked() {
+                            let mut rec = make_record(
+                                PacketDirection::Outbound,
+                                classes::CUSTOM_PAYLOAD_CLIENT.to_string(),
+                                bytes,
+                            );
+                            rec.tags = vec!["synthetic".to_string()];
+                            let id = rec.id;
+                            if let Some(store) = GlobalState::instance().get_packet_store().get() {
+                                store.lock().push(rec);
+                            }
+                            *ui_state.selected_packet_id = Some(id);
+                            ui_state
+                                .notification_manager
+                                .show_success("Packet builder", &format!("Added as record #{id}"));
+                        }
+                        if ui.button("Reset").clicked() {
+                            *ui_state.packet_builder_input = FieldInput::empty(&packet.fields);
+                        }
+                    });
+                }
+                Err(errors) => {
+                    ui.colored_label(Color32::LIGHT_RED, format!("{} field error(s)", errors.len()));
+                    // ошибки без пути (нет encode и т.п.) не привязаны ни к одному полю
+                    for e in errors.iter().filter(|e| e.path.is_empty()) {
+                        ui.colored_label(Color32::LIGHT_RED, &e.message);
+                    }
+                }
+            }
+        });
+}
+
+/// Поле формы по схеме; `name` — `None` только для корня пакета.
+fn render_field_input(
+    ui: &mut Ui,
+    name: Option<&str>,
+    kind: &FieldKind,
+    input: &mut FieldInput,
+    path: &str,
+    errors: &[FieldError],
+) {
+    // схема могла смениться после перерегистрации канала, форма под неё пересоздаётся
+    let shape_ok = matches!(
+        (kind, &*input),
+        (FieldKind::Scalar(_), FieldInput::Scalar(_))
+            | (FieldKind::List(_), FieldInput::List(_))
+            | (FieldKind::Struct(_), FieldInput::Struct(_))
+    );
+    if !shape_ok {
+        *input = FieldInput::empty(kind);
+    }
+
+    match (kind, input) {
+        (FieldKind::Scalar(type_name), FieldInput::Scalar(text)) => {
+            ui.horizontal(|ui| {
+                ui.label(RichText::new(name.unwrap_or("value")).monospace());
+                if *type_name == "bool" {
+                    let mut on = text.trim() == "true" || text.trim() == "1";
+                    if ui.checkbox(&mut on, "").changed() || text.is_empty() {
+                        *text = on.to_string();
+                    }
+                } else {
+                    ui.add(
+                        egui::TextEdit::singleline(text)
+                            .hint_text(*type_name)
+                            .desired_width(200.0),
+                    );
+                }
+                ui.label(RichText::new(*type_name).small().color(Color32::GRAY));
+            });
+            for e in errors.iter().filter(|e| e.path == path) {
+                ui.colored_label(Color32::LIGHT_RED, &e.message);
+            }
+        }
+        (FieldKind::List(item_kind), FieldInput::List(items)) => {
+            egui::CollapsingHeader::new(format!("{} [{}]", name.unwrap_or("list"), items.len()))
+                .id_salt(("packet_builder_field", path))
+                .default_open(true)
+                .show(ui, |ui| {
+                    let mut remove = None;
+                    for (i, item) in items.iter_mut().enumerate() {
+                        ui.horizontal(|ui| {
+                            if ui.small_button("x").on_hover_text("Remove item").clicked() {
+                                remove = Some(i);
+                            }
+                            ui.vertical(|ui| {
+                                render_field_input(
+                                    ui,
+                                    Some(&format!("[{i}]")),
+                                    item_kind,
+                                    item,
+                                    &field_path(path, &i.to_string()),
+                                    errors,
+                                );
+                            });
+                        });
+                    }
+                    if let Some(i) = remove {
+                        items.remove(i);
+                    }
+                    if ui.small_button("+ Add item").clicked() {
+                        items.push(FieldInput::empty(item_kind));
+                    }
+                });
+        }
+        (FieldKind::Struct(specs), FieldInput::Struct(values)) => {
+            let mut render_fields = |ui: &mut Ui| {
+                for spec in specs {
+                    let idx = match values.iter().position(|(n, _)| n == spec.name) {
+                        Some(idx) => idx,
+                        None => {
+                            values.push((spec.name.to_string(), FieldInput::empty(&spec.kind)));
+                            values.len() - 1
+                        }
+                    };
+                    let sub = field_path(path, spec.name);
+                    render_field_input(ui, Some(spec.name), &spec.kind, &mut values[idx].1, &sub, errors);
+                }
+            };
+            match name {
+                None => render_fields(ui),
+                Some(name) => {
+                    egui::CollapsingHeader::new(name)
+                        .id_salt(("packet_builder_field", path))
+                        .default_open(true)
+                        .show(ui, |ui| render_fields(ui));
+                }
+            }
+        }
+        _ => {}
+    }
+}
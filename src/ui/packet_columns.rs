@@ -0,0 +1,300 @@
+use crate::core::custom_payload::{DecodedStruct, DecodedValue};
+use crate::core::packet_diff::value_summary;
+use crate::core::packet_query::{lookup_field, record_channel};
+use crate::graphics::netlog::{PacketDirection, PacketRecord};
+use crate::ui::UiState;
+use egui::{pos2, Color32, Rect, RichText, Ui};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PacketColumn {
+    Id,
+    Time,
+    /// От первой записи в таблице
+    RelativeTime,
+    Direction,
+    Name,
+    Channel,
+    DecodedName,
+    Length,
+    Tags,
+    /// Поле расшифрованного пакета, путь как в `field.<path>`
+    Field(String),
+}
+
+impl PacketColumn {
+    pub const BUILTIN: [PacketColumn; 9] = [
+        PacketColumn::Id,
+        PacketColumn::Time,
+        PacketColumn::RelativeTime,
+        PacketColumn::Direction,
+        PacketColumn::Name,
+        PacketColumn::Channel,
+        PacketColumn::DecodedName,
+        PacketColumn::Length,
+        PacketColumn::Tags,
+    ];
+
+    /// Раскладка как у таблицы до настраиваемых колонок
+    pub fn defaults() -> Vec<PacketColumn> {
+        vec![PacketColumn::Direction, PacketColumn::Time, PacketColumn::Name, PacketColumn::Length]
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            PacketColumn::Id => "ID".to_string(),
+            PacketColumn::Time => "Time".to_string(),
+            PacketColumn::RelativeTime => "+Time".to_string(),
+            PacketColumn::Direction => "Dir".to_string(),
+            PacketColumn::Name => "Name".to_string(),
+            PacketColumn::Channel => "Channel".to_string(),
+            PacketColumn::DecodedName => "Decoded".to_string(),
+            PacketColumn::Length => "Length".to_string(),
+            PacketColumn::Tags => "Tags".to_string(),
+            PacketColumn::Field(path) => path.clone(),
+        }
+    }
+
+    pub fn width(&self) -> f32 {
+        match self {
+            PacketColumn::Id => 60.0,
+            PacketColumn::Time => 100.0,
+            PacketColumn::RelativeTime => 80.0,
+            PacketColumn::Direction => 32.0,
+            PacketColumn::Name => 220.0,
+            PacketColumn::Channel => 110.0,
+            PacketColumn::DecodedName => 160.0,
+            PacketColumn::Length => 70.0,
+            PacketColumn::Tags => 120.0,
+            PacketColumn::Field(_) => 110.0,
+        }
+    }
+
+    pub fn color(&self) -> Color32 {
+        match self {
+            PacketColumn::Name | PacketColumn::DecodedName => Color32::WHITE,
+            PacketColumn::Field(_) => Color32::LIGHT_BLUE,
+            _ => Color32::LIGHT_GRAY,
+        }
+    }
+
+    /// Текст ячейки. `base_ts` — время первой записи для относительного времени.
+    pub fn cell_text(
+        &self,
+        rec: &PacketRecord,
+        base_ts: u64,
+        decode: &mut dyn FnMut(&PacketRecord) -> Option<DecodedStruct>,
+    ) -> String {
+        match self {
+            PacketColumn::Id => rec.id.to_string(),
+            PacketColumn::Time => format_clock(rec.ts_millis),
+            PacketColumn::RelativeTime => {
+                let ms = rec.ts_millis.saturating_sub(base_ts);
+                format!("+{}.{:03}s", ms / 1000, ms % 1000)
+            }
+            PacketColumn::Direction => match rec.dir {
+                PacketDirection::Inbound => "<-".to_string(),
+                PacketDirection::Outbound => "->".to_string(),
+            },
+            PacketColumn::Name => rec.name.rsplit('.').next().unwrap_or(&rec.name).to_string(),
+            PacketColumn::Channel => record_channel(rec).to_string(),
+            PacketColumn::DecodedName => decode(rec).map(|d| d.name).unwrap_or_default(),
+            PacketColumn::Length => rec.len.to_string(),
+            PacketColumn::Tags => rec.tags.join(", "),
+            PacketColumn::Field(path) => field_value(rec, path, decode)
+                .map(|v| value_summary(&v))
+                .unwrap_or_default(),
+        }
+    }
+
+    fn sort_key(
+        &self,
+        rec: &PacketRecord,
+        decode: &mut dyn FnMut(&PacketRecord) -> Option<DecodedStruct>,
+    ) -> SortKey {
+        match self {
+            PacketColumn::Id => SortKey::Num(rec.id as f64),
+            PacketColumn::Time | PacketColumn::RelativeTime => SortKey::Num(rec.ts_millis as f64),
+            PacketColumn::Length => SortKey::Num(rec.len as f64),
+            PacketColumn::Field(path) => match field_value(rec, path, decode) {
+                Some(DecodedValue::Text(t)) => match t.trim().parse::<f64>() {
+                    Ok(n) => SortKey::Num(n),
+                    Err(_) => SortKey::Text(t.to_lowercase()),
+                },
+                Some(DecodedValue::Bytes(b)) => SortKey::Num(b.len() as f64),
+                Some(DecodedValue::List(items)) => SortKey::Num(items.len() as f64),
+                Some(v) => SortKey::Text(value_summary(&v).to_lowercase()),
+                None => SortKey::Missing,
+            },
+            other => {
+                let text = other.cell_text(rec, 0, decode);
+                if text.is_empty() { SortKey::Missing } else { SortKey::Text(text.to_lowercase()) }
+            }
+        }
+    }
+}
+
+fn field_value(
+    rec: &PacketRecord,
+    path: &str,
+    decode: &mut dyn FnMut(&PacketRecord) -> Option<DecodedStruct>,
+) -> Option<DecodedValue> {
+    let segs: Vec<String> = path.split('.').map(|s| s.trim().to_string()).collect();
+    let ds = decode(rec)?;
+    lookup_field(&ds, &segs).cloned()
+}
+
+/// Числа раньше строк, пустые ячейки всегда в конце
+#[derive(Debug, Clone, PartialEq)]
+enum SortKey {
+    Num(f64),
+    Text(String),
+    Missing,
+}
+
+impl SortKey {
+    fn rank(&self) -> u8 {
+        match self {
+            SortKey::Num(_) => 0,
+            SortKey::Text(_) => 1,
+            SortKey::Missing => 2,
+        }
+    }
+}
+
+impl Eq for SortKey {}
+
+impl PartialOrd for SortKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SortKey {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (SortKey::Num(a), SortKey::Num(b)) => a.total_cmp(b),
+            (SortKey::Text(a), SortKey::Text(b)) => a.cmp(b),
+            _ => self.rank().cmp(&other.rank()),
+        }
+    }
+}
+
+/// Сортировка для таблицы, которая рисует `records` с конца: первой строкой
+/// оказывается последний элемент. Без сортировки это самые новые записи сверху.
+pub fn sort_records(
+    records: &mut [PacketRecord],
+    column: &PacketColumn,
+    descending: bool,
+    decode: &mut dyn FnMut(&PacketRecord) -> Option<DecodedStruct>,
+) {
+    records.sort_by_cached_key(|r| {
+        let key = column.sort_key(r, decode);
+        // пустые ячейки внизу таблицы при любом направлении
+        let sink = (key == SortKey::Missing) != descending;
+        (sink, key, r.id)
+    });
+    if !descending {
+        records.reverse();
+    }
+}
+
+/// Прямоугольники колонок в строке; последняя тянется до `right`.
+pub fn column_rects(row: Rect, left: f32, right: f32, columns: &[PacketColumn]) -> Vec<Rect> {
+    let mut x = left;
+    let mut out = Vec::with_capacity(columns.len());
+    for (i, col) in columns.iter().enumerate() {
+        let end = if i + 1 == columns.len() { right } else { (x + col.width()).min(right) };
+        out.push(Rect::from_min_max(pos2(x, row.min.y), pos2(end.max(x), row.max.y)));
+        x = end + 6.0;
+    }
+    out
+}
+
+/// Заголовки колонок; клик переключает сортировку: по возрастанию, по убыванию, по порядку прихода.
+pub fn render_column_header(ui: &mut Ui, ui_state: &mut UiState, row: Rect, left: f32, right: f32) {
+    let rects = column_rects(row, left, right, ui_state.packet_columns);
+    let mut clicked = None;
+    for (col, rect) in ui_state.packet_columns.iter().zip(rects) {
+        let arrow = match ui_state.packet_sort.as_ref() {
+            Some((c, false)) if c == col => " ^",
+            Some((c, true)) if c == col => " v",
+            _ => "",
+        };
+        let resp = ui.put(
+            rect,
+            egui::Label::new(RichText::new(format!("{}{arrow}", col.label())).strong())
+                .sense(egui::Sense::click())
+                .truncate(),
+        );
+        if resp.on_hover_text("Click to sort").clicked() {
+            clicked = Some(col.clone());
+        }
+    }
+    if let Some(col) = clicked {
+        *ui_state.packet_sort = match ui_state.packet_sort.take() {
+            Some((c, false)) if c == col => Some((col, true)),
+            Some((c, true)) if c == col => None,
+            _ => Some((col, false)),
+        };
+    }
+}
+
+/// Меню выбора колонок: встроенные галочками, поля по пути.
+pub fn render_columns_menu(ui: &mut Ui, ui_state: &mut UiState) {
+    ui.menu_button("Columns", |ui| {
+        for col in PacketColumn::BUILTIN {
+            let mut on = ui_state.packet_columns.contains(&col);
+            if ui.checkbox(&mut on, col.label()).changed() {
+                if on {
+                    ui_state.packet_columns.push(col);
+                } else {
+                    ui_state.packet_columns.retain(|c| *c != col);
+                }
+            }
+        }
+        ui.separator();
+        let mut remove = None;
+        for (idx, col) in ui_state.packet_columns.iter().enumerate() {
+            if let PacketColumn::Field(path) = col {
+                ui.horizontal(|ui| {
+                    ui.monospace(format!("field.{path}"));
+                    if ui.small_button("x").clicked() {
+                        remove = Some(idx);
+                    }
+                });
+            }
+        }
+        if let Some(idx) = remove {
+            ui_state.packet_columns.remove(idx);
+        }
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(ui_state.packet_column_field_input)
+                    .hint_text("field path, e.g. emeralds")
+                    .desired_width(140.0),
+            );
+            let path = ui_state.packet_column_field_input.trim().trim_start_matches("field.").to_string();
+            if ui.button("Add").clicked() && !path.is_empty() {
+                let col = PacketColumn::Field(path);
+                if !ui_state.packet_columns.contains(&col) {
+                    ui_state.packet_columns.push(col);
+                }
+                ui_state.packet_column_field_input.clear();
+            }
+        });
+        ui.separator();
+        if ui.button("Reset to default").clicked() {
+            *ui_state.packet_columns = PacketColumn::defaults();
+            *ui_state.packet_sort = None;
+        }
+    });
+}
+
+fn format_clock(millis: u64) -> String {
+    use chrono::{DateTime, Local};
+    DateTime::from_timestamp_millis(millis as i64)
+        .map(|dt| dt.with_timezone(&Local).format("%H:%M:%S%.3f").to_string())
+        .unwrap_or_default()
+}
@@ -0,0 +1,389 @@
+use crate::core::custom_payload::{decode_record, DecodedStruct};
+use crate::core::state::GlobalState;
+use crate::graphics::netlog::{make_record, record_details, PacketDirection, PacketRecord};
+use crate::ui::packet_analyzer::{render_decoded_struct, FieldMarks};
+use crate::ui::UiState;
+use egui::{pos2, vec2, Color32, Event, EventFilter, Key, Rect, RichText, ScrollArea, Sense, TextStyle, Ui};
+
+const BYTES_PER_ROW: usize = 16;
+/// Глубина истории undo; пакеты маленькие, храним буфер целиком
+const MAX_UNDO: usize = 256;
+
+const CURSOR_BG: Color32 = Color32::from_rgb(40, 90, 160);
+const CURSOR_SIDE_BG: Color32 = Color32::from_rgb(40, 60, 80);
+const CHANGED_FG: Color32 = Color32::from_rgb(255, 200, 90);
+
+/// Офлайн-редактор байтов записи: правки, undo/redo и расшифровка на лету.
+#[derive(Debug, Clone)]
+pub struct HexEditor {
+    pub source_id: u64,
+    pub dir: PacketDirection,
+    pub name: String,
+    original: Vec<u8>,
+    data: Vec<u8>,
+    /// Позиция курсора, может стоять сразу за последним байтом (дописывание)
+    cursor: usize,
+    /// Старшая тетрада уже введена, следующая цифра допишет младшую
+    half: bool,
+    insert: bool,
+    /// Ввод идёт в ASCII-колонку
+    ascii: bool,
+    undo: Vec<(Vec<u8>, usize)>,
+    redo: Vec<(Vec<u8>, usize)>,
+    decoded: Option<DecodedStruct>,
+}
+
+impl HexEditor {
+    pub fn open(rec: &PacketRecord) -> Self {
+        let mut editor = Self {
+            source_id: rec.id,
+            dir: rec.dir,
+            name: rec.name.clone(),
+            original: rec.data.clone(),
+            data: rec.data.clone(),
+            cursor: 0,
+            half: false,
+            insert: false,
+            ascii: false,
+            undo: Vec::new(),
+            redo: Vec::new(),
+            decoded: None,
+        };
+        editor.redecode();
+        editor
+    }
+
+    pub fn is_modified(&self) -> bool {
+        self.data != self.original
+    }
+
+    /// Временная запись для декодера: канал и превью пересчитываются по текущим байтам
+    fn preview_record(&self) -> PacketRecord {
+        PacketRecord {
+            id: self.source_id,
+            ts_millis: 0,
+            dir: self.dir,
+            name: self.name.clone(),
+            len: self.data.len(),
+            data: self.data.clone(),
+            details: record_details(&self.name, &self.data),
+            pinned: false,
+            tags: Vec::new(),
+            color: None,
+            group: None,
+            session: None,
+        }
+    }
+
+    fn redecode(&mut self) {
+        self.decoded = decode_record(&self.preview_record());
+    }
+
+    fn checkpoint(&mut self) {
+        if self.undo.len() >= MAX_UNDO {
+            self.undo.remove(0);
+        }
+        self.undo.push((self.data.clone(), self.cursor));
+        self.redo.clear();
+    }
+
+    fn changed(&mut self) {
+        self.cursor = self.cursor.min(self.data.len());
+        self.redecode();
+    }
+
+    pub fn undo(&mut self) {
+        if let Some((data, cursor)) = self.undo.pop() {
+            self.redo.push((std::mem::replace(&mut self.data, data), self.cursor));
+            self.cursor = cursor;
+            self.half = false;
+            self.changed();
+        }
+    }
+
+    pub fn redo(&mut self) {
+        if let Some((data, cursor)) = self.redo.pop() {
+            self.undo.push((std::mem::replace(&mut self.data, data), self.cursor));
+            self.cursor = cursor;
+            self.half = false;
+            self.changed();
+        }
+    }
+
+    fn move_cursor(&mut self, delta: isize) {
+        self.half = false;
+        self.cursor = self.cursor.saturating_add_signed(delta).min(self.data.len());
+    }
+
+    fn set_cursor(&mut self, pos: usize, ascii: bool) {
+        self.half = false;
+        self.ascii = ascii;
+        self.cursor = pos.min(self.data.len());
+    }
+
+    /// Цифра в hex-колонке: старшая тетрада, затем младшая и сдвиг курсора
+    fn type_nibble(&mut self, nibble: u8) {
+        if self.half {
+            let b = &mut self.data[self.cursor];
+            *b = (*b & 0xF0) | nibble;
+            self.half = false;
+            self.cursor += 1;
+        } else {
+            self.checkpoint();
+            if self.insert || self.cursor == self.data.len() {
+                self.data.insert(self.cursor, nibble << 4);
+            } else {
+                let b = &mut self.data[self.cursor];
+                *b = (*b & 0x0F) | (nibble << 4);
+            }
+            self.half = true;
+        }
+        self.changed();
+    }
+
+    fn type_byte(&mut self, byte: u8) {
+        self.checkpoint();
+        if self.insert || self.cursor == self.data.len() {
+            self.data.insert(self.cursor, byte);
+        } else {
+            self.data[self.cursor] = byte;
+        }
+        self.cursor += 1;
+        self.half = false;
+        self.changed();
+    }
+
+    pub fn delete_forward(&mut self) {
+        if self.cursor < self.data.len() {
+            self.checkpoint();
+            self.data.remove(self.cursor);
+            self.half = false;
+            self.changed();
+        }
+    }
+
+    fn delete_back(&mut self) {
+        if self.cursor > 0 {
+            self.checkpoint();
+            self.cursor -= 1;
+            self.data.remove(self.cursor);
+            self.half = false;
+            self.changed();
+        }
+    }
+
+    pub fn insert_zero(&mut self) {
+        self.checkpoint();
+        self.data.insert(self.cursor, 0);
+        self.half = false;
+        self.changed();
+    }
+
+    pub fn revert(&mut self) {
+        if self.is_modified() {
+            self.checkpoint();
+            self.data = self.original.clone();
+            self.half = false;
+            self.changed();
+        }
+    }
+
+    fn handle_event(&mut self, event: &Event) {
+        match event {
+            Event::Text(text) => {
+                for c in text.chars() {
+                    if self.ascii {
+                        if c.is_ascii() && !c.is_ascii_control() {
+                            self.type_byte(c as u8);
+                        }
+                    } else if let Some(n) = c.to_digit(16) {
+                        self.type_nibble(n as u8);
+                    }
+                }
+            }
+            Event::Key { key, pressed: true, modifiers, .. } => match key {
+                Key::Z if modifiers.command && modifiers.shift => self.redo(),
+                Key::Z if modifiers.command => self.undo(),
+                Key::Y if modifiers.command => self.redo(),
+                Key::ArrowLeft => self.move_cursor(-1),
+                Key::ArrowRight => self.move_cursor(1),
+                Key::ArrowUp => self.move_cursor(-(BYTES_PER_ROW as isize)),
+                Key::ArrowDown => self.move_cursor(BYTES_PER_ROW as isize),
+                Key::Home => self.set_cursor(self.cursor - self.cursor % BYTES_PER_ROW, self.ascii),
+                Key::End => {
+                    let end = (self.cursor - self.cursor % BYTES_PER_ROW + BYTES_PER_ROW - 1).min(self.data.len());
+                    self.set_cursor(end, self.ascii);
+                }
+                Key::Backspace => self.delete_back(),
+                Key::Delete => self.delete_forward(),
+                Key::Insert => self.insert = !self.insert,
+                Key::Tab => self.set_cursor(self.cursor, !self.ascii),
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+
+    /// Новая запись из текущих байтов; id и время новые, источник отмечен тегом
+    pub fn to_record(&self) -> PacketRecord {
+        let mut rec = make_record(self.dir, self.name.clone(), self.data.clone());
+        rec.tags = vec!["edited".to_string(), format!("from #{}", self.source_id)];
+        rec
+    }
+}
+
+pub fn render_hex_editor(ui: &mut Ui, ui_state: &mut UiState) {
+    // редактор вынимаем из состояния, чтобы дерево расшифровки могло занять ui_state
+    let Some(mut editor) = ui_state.packet_hex_editor.take() else { return };
+    let mut close = false;
+    let mut saved = None;
+
+    let short = editor.name.rsplit('.').next().unwrap_or(&editor.name).to_string();
+    let title = format!(
+        "Hex editor: #{} {short}  ({} bytes{})",
+        editor.source_id,
+        editor.data.len(),
+        if editor.is_modified() { ", modified" } else { "" }
+    );
+    egui::CollapsingHeader::new(title)
+        .id_salt("packet_hex_editor")
+        .default_open(true)
+        .show(ui, |ui| {
+            ui.horizontal_wrapped(|ui| {
+                if ui.add_enabled(!editor.undo.is_empty(), egui::Button::new("Undo")).clicked() {
+                    editor.undo();
+                }
+                if ui.add_enabled(!editor.redo.is_empty(), egui::Button::new("Redo")).clicked() {
+                    editor.redo();
+                }
+                ui.separator();
+                ui.selectable_value(&mut editor.insert, false, "Overwrite");
+                ui.selectable_value(&mut editor.insert, true, "Insert");
+                if ui.button("Insert 00").clicked() {
+                    editor.insert_zero();
+                }
+                if ui.button("Delete byte").clicked() {
+                    editor.delete_forward();
+                }
+                ui.separator();
+                if ui.add_enabled(editor.is_modified(), egui::Button::new("Revert")).clicked() {
+                    editor.revert();
+                }
+                if ui.button("Save as new record").clicked() {
+                    saved = Some(editor.to_record());
+                }
+                if ui.button("Close").clicked() {
+                    close = true;
+                }
+            });
+            ui.colored_label(
+                Color32::GRAY,
+                "Click a byte and type hex digits. Tab switches to ASCII, Insert toggles mode, Backspace/Delete remove, Ctrl+Z / Ctrl+Y undo and redo",
+            );
+
+            ScrollArea::vertical()
+                .id_salt("packet_hex_editor_rows")
+                .max_height(320.0)
+                .show(ui, |ui| render_grid(ui, &mut editor));
+
+            ui.add_space(4.0);
+            match editor.decoded.clone() {
+                Some(ds) => {
+                    ui.label(RichText::new("Decoded").strong().color(Color32::LIGHT_BLUE));
+                    render_decoded_struct(ui, &ds, ui_state, "", &FieldMarks::new());
+                }
+                None => {
+                    ui.colored_label(Color32::LIGHT_RED, "No decoder accepts these bytes");
+                }
+            }
+        });
+
+    if let Some(rec) = saved {
+        let id = rec.id;
+        if let Some(store) = GlobalState::instance().get_packet_store().get() {
+            store.lock().push(rec);
+        }
+        *ui_state.selected_packet_id = Some(id);
+        ui_state
+            .notification_manager
+            .show_success("Hex editor", &format!("Saved as record #{id}"));
+    }
+    if !close {
+        *ui_state.packet_hex_editor = Some(editor);
+    }
+}
+
+/// Сетка смещение / hex / ASCII. Ввод с клавиатуры принимается, пока сетка в фокусе.
+fn render_grid(ui: &mut Ui, editor: &mut HexEditor) {
+    let font = TextStyle::Monospace.resolve(ui.style());
+    let char_w = ui.fonts(|f| f.glyph_width(&font, '0'));
+    let row_h = ui.text_style_height(&TextStyle::Monospace) + 2.0;
+    // строка за последним байтом нужна, чтобы поставить курсор для дописывания
+    let rows = editor.data.len() / BYTES_PER_ROW + 1;
+
+    let hex_x = char_w * 6.0;
+    let ascii_x = hex_x + char_w * (BYTES_PER_ROW as f32 * 3.0 + 1.0);
+    let width = ascii_x + char_w * BYTES_PER_ROW as f32;
+    let (rect, resp) = ui.allocate_exact_size(vec2(width, rows as f32 * row_h), Sense::click());
+    let id = resp.id;
+
+    if resp.clicked() {
+        resp.request_focus();
+        if let Some(p) = resp.interact_pointer_pos() {
+            let row = ((p.y - rect.top()) / row_h) as usize;
+            let x = p.x - rect.left();
+            let (col, ascii) = if x >= ascii_x {
+                (((x - ascii_x) / char_w) as usize, true)
+            } else {
+                (((x - hex_x).max(0.0) / (char_w * 3.0)) as usize, false)
+            };
+            editor.set_cursor(row * BYTES_PER_ROW + col.min(BYTES_PER_ROW - 1), ascii);
+        }
+    }
+
+    let focused = resp.has_focus();
+    if focused {
+        ui.memory_mut(|m| {
+            m.set_focus_lock_filter(
+                id,
+                EventFilter { tab: true, horizontal_arrows: true, vertical_arrows: true, escape: false },
+            )
+        });
+        let events = ui.input(|i| i.events.clone());
+        for event in &events {
+            editor.handle_event(event);
+        }
+    }
+
+    let painter = ui.painter_at(rect);
+    for row in 0..rows {
+        let y = rect.top() + row as f32 * row_h;
+        painter.text(
+            pos2(rect.left(), y),
+            egui::Align2::LEFT_TOP,
+            format!("{:04X}", row * BYTES_PER_ROW),
+            font.clone(),
+            Color32::GRAY,
+        );
+        for col in 0..BYTES_PER_ROW {
+            let i = row * BYTES_PER_ROW + col;
+            let hex_cell = Rect::from_min_size(pos2(rect.left() + hex_x + col as f32 * char_w * 3.0, y), vec2(char_w * 2.0, row_h));
+            let ascii_cell = Rect::from_min_size(pos2(rect.left() + ascii_x + col as f32 * char_w, y), vec2(char_w, row_h));
+            if i == editor.cursor {
+                let (main, side) = if editor.ascii { (ascii_cell, hex_cell) } else { (hex_cell, ascii_cell) };
+                painter.rect_filled(main, 2.0, if focused { CURSOR_BG } else { CURSOR_SIDE_BG });
+                painter.rect_filled(side, 2.0, CURSOR_SIDE_BG);
+            }
+            let Some(&b) = editor.data.get(i) else { continue };
+            let color = if editor.original.get(i) != Some(&b) { CHANGED_FG } else { Color32::LIGHT_GRAY };
+            let text = if i == editor.cursor && editor.half {
+                format!("{:X}_", b >> 4)
+            } else {
+                format!("{b:02X}")
+            };
+            painter.text(hex_cell.min, egui::Align2::LEFT_TOP, text, font.clone(), color);
+            let c = if b.is_ascii_graphic() || b == b' ' { b as char } else { '.' };
+            painter.text(ascii_cell.min, egui::Align2::LEFT_TOP, c, font.clone(), color);
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use crate::core::layout_infer::{draft_declaration, infer_channel, FieldKind};
+use crate::core::packets::Bound;
+use crate::core::state::GlobalState;
+use crate::ui::UiState;
+use egui::{Color32, RichText, ScrollArea, Ui};
+
+/// Запуск вывода раскладки по всем записям канала из текущего стора.
+pub fn run_layout_inference(ui_state: &mut UiState, channel: &str) {
+    let records = match GlobalState::instance().get_packet_store().get() {
+        Some(store) => store.lock().snapshot(),
+        None => Vec::new(),
+    };
+    let packets = infer_channel(channel, &records);
+    if packets.is_empty() {
+        ui_state
+            .notification_manager
+            .show_warning("Infer layout", &format!("No decodable samples for channel '{channel}'"));
+    }
+    *ui_state.packet_infer_draft = draft_declaration(channel, &packets);
+    *ui_state.packet_infer_channel = channel.to_string();
+    *ui_state.packet_infer_result = packets;
+}
+
+pub fn render_layout_infer_section(ui: &mut Ui, ui_state: &mut UiState) {
+    egui::CollapsingHeader::new("Infer layout")
+        .id_salt("packet_infer_layout")
+        .default_open(!ui_state.packet_infer_result.is_empty())
+        .show(ui, |ui| {
+            let channels: Vec<String> = GlobalState::instance()
+                .get_packet_store()
+                .get()
+                .map(|store| {
+                    let mut v: Vec<String> = store.lock().traffic_stats().channels.keys().cloned().collect();
+                    v.sort();
+                    v
+                })
+                .unwrap_or_default();
+
+            ui.horizontal_wrapped(|ui| {
+                ui.label("Channel:");
+                egui::ComboBox::from_id_salt("packet_infer_channel")
+                    .selected_text(if ui_state.packet_infer_channel.is_empty() {
+                        "select"
+                    } else {
+                        ui_state.packet_infer_channel.as_str()
+                    })
+                    .show_ui(ui, |ui| {
+                        for ch in &channels {
+                            ui.selectable_value(ui_state.packet_infer_channel, ch.clone(), ch);
+                        }
+                    });
+                if ui.button("Infer").clicked() && !ui_state.packet_infer_channel.is_empty() {
+                    let channel = ui_state.packet_infer_channel.clone();
+                    run_layout_inference(ui_state, &channel);
+                }
+                if !ui_state.packet_infer_draft.is_empty() {
+                    if ui.button("Copy draft").clicked() {
+                        let _ = ui_state.clipboard.set_text(ui_state.packet_infer_draft.as_str());
+                        ui_state
+                            .notification_manager
+                            .show_success("Infer layout", "mod_packets! draft copied");
+                    }
+                    if ui.button("Clear").clicked() {
+                        ui_state.packet_infer_result.clear();
+                        ui_state.packet_infer_draft.clear();
+                    }
+                }
+            });
+            ui.colored_label(
+                Color32::GRAY,
+                "Uses every stored record of the channel, grouped by packet ID and direction. Result is a guess: check it against samples.",
+            );
+
+            for p in ui_state.packet_infer_result.iter() {
+                let b = match p.bound {
+                    Bound::Server => "S",
+                    Bound::Client => "C",
+                };
+                egui::CollapsingHeader::new(format!("ID {} ({b}) - {} samples, {} fields", p.id, p.samples, p.fields.len()))
+                    .id_salt(("infer_packet", p.id, b))
+                    .default_open(true)
+                    .show(ui, |ui| {
+                        egui::Grid::new(("infer_grid", p.id, b))
+                            .num_columns(4)
+                            .striped(true)
+                            .show(ui, |ui| {
+                                ui.label(RichText::new("#").strong());
+                                ui.label(RichText::new("Offset").strong());
+                                ui.label(RichText::new("Kind").strong());
+                                ui.label(RichText::new("Examples").strong());
+                                ui.end_row();
+                                for (i, f) in p.fields.iter().enumerate() {
+                                    let color = match f.kind {
+                                        FieldKind::Const(_) => Color32::GRAY,
+                                        FieldKind::Trailing { .. } => Color32::LIGHT_RED,
+                                        _ if f.kind.rust_type().is_none() => Color32::YELLOW,
+                                        _ => Color32::LIGHT_GREEN,
+                                    };
+                                    ui.monospace(i.to_string());
+                                    ui.monospace(format!("{:#06X}", f.offset));
+                                    ui.label(RichText::new(f.kind.label()).monospace().color(color));
+                                    ui.monospace(f.examples.join(" | "));
+                                    ui.end_row();
+                                }
+                            });
+                    });
+            }
+
+            if !ui_state.packet_infer_draft.is_empty() {
+                ui.add_space(4.0);
+                ui.label(RichText::new("Draft").strong());
+                ScrollArea::vertical()
+                    .id_salt("packet_infer_draft")
+                    .max_height(260.0)
+                    .show(ui, |ui| {
+                        ui.add(
+                            egui::TextEdit::multiline(ui_state.packet_infer_draft)
+                                .code_editor()
+                                .desired_width(f32::INFINITY),
+                        );
+                    });
+            }
+        });
+}
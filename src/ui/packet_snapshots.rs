@@ -0,0 +1,172 @@
+use crate::core::state::GlobalState;
+use crate::graphics::netlog::PacketRecord;
+use crate::ui::UiState;
+use egui::{Color32, RichText, Ui};
+
+/// Записи открытого снапшота вместо живого стора. Если снапшот удалён, возвращаемся к живым.
+pub fn viewed_snapshot_records(ui_state: &mut UiState) -> Option<Vec<PacketRecord>> {
+    let name = ui_state.packet_snapshot_view.as_ref()?;
+    let records = GlobalState::instance()
+        .get_packet_snapshots()
+        .get()
+        .and_then(|s| s.lock().get(name).map(|snap| snap.records.clone()));
+    if records.is_none() {
+        *ui_state.packet_snapshot_view = None;
+    }
+    records
+}
+
+/// Строка над таблицей, пока открыт снапшот
+pub fn render_snapshot_banner(ui: &mut Ui, ui_state: &mut UiState, count: usize) {
+    let Some(name) = ui_state.packet_snapshot_view.clone() else { return };
+    ui.horizontal(|ui| {
+        ui.colored_label(Color32::GOLD, format!("Snapshot '{name}': {count} records, capture is not shown"));
+        if ui.button("Back to live").clicked() {
+            *ui_state.packet_snapshot_view = None;
+        }
+    });
+}
+
+/// Снимает текущий отфильтрованный набор под именем из поля ввода.
+pub fn freeze_snapshot(ui_state: &mut UiState, records: &[PacketRecord]) {
+    let Some(snapshots) = GlobalState::instance().get_packet_snapshots().get() else { return };
+    let name = ui_state.packet_snapshot_name.trim().to_string();
+    let result = snapshots.lock().add(&name, ui_state.packet_filter.as_str(), records.to_vec());
+    match result {
+        Ok(()) => {
+            ui_state
+                .notification_manager
+                .show_success("Snapshots", &format!("'{name}': {} records frozen", records.len()));
+            ui_state.packet_snapshot_name.clear();
+            *ui_state.packet_snapshots_open = true;
+        }
+        Err(e) => {
+            ui_state.notification_manager.show_error("Snapshots", &e);
+        }
+    }
+}
+
+pub fn render_snapshots_sidebar(ui: &mut Ui, ui_state: &mut UiState) {
+    let Some(snapshots) = GlobalState::instance().get_packet_snapshots().get() else { return };
+    ui.heading("Snapshots");
+
+    let list: Vec<(String, usize, u64, String)> = snapshots
+        .lock()
+        .snapshots()
+        .iter()
+        .map(|s| (s.name.clone(), s.records.len(), s.created_at, s.filter.clone()))
+        .collect();
+    if list.is_empty() {
+        ui.colored_label(Color32::GRAY, "No snapshots. Use \"Snapshot filtered\" under the table.");
+    }
+
+    let mut export = None;
+    let mut remove = None;
+    egui::ScrollArea::vertical().id_salt("packet_snapshots_list").max_height(400.0).show(ui, |ui| {
+        for (name, count, created_at, filter) in &list {
+            let renaming = matches!(ui_state.packet_snapshot_rename.as_ref(), Some((old, _)) if old == name);
+            if renaming {
+                let mut done = false;
+                if let Some((_, new)) = ui_state.packet_snapshot_rename.as_mut() {
+                    let resp = ui.text_edit_singleline(new);
+                    done = resp.lost_focus();
+                    if !done && !resp.has_focus() {
+                        resp.request_focus();
+                    }
+                }
+                if done {
+                    if let Some((old, new)) = ui_state.packet_snapshot_rename.take() {
+                        if let Err(e) = snapshots.lock().rename(&old, &new) {
+                            ui_state.notification_manager.show_error("Snapshots", &e);
+                        } else if ui_state.packet_snapshot_view.as_deref() == Some(old.as_str()) {
+                            *ui_state.packet_snapshot_view = Some(new.trim().to_string());
+                        }
+                    }
+                }
+                continue;
+            }
+
+            let viewing = ui_state.packet_snapshot_view.as_deref() == Some(name.as_str());
+            let resp = ui.selectable_label(viewing, RichText::new(name).strong());
+            let hover = if filter.is_empty() { "no filter".to_string() } else { format!("filter: {filter}") };
+            let resp = resp.on_hover_text(hover);
+            if resp.clicked() {
+                *ui_state.packet_snapshot_view = if viewing { None } else { Some(name.clone()) };
+            }
+            resp.context_menu(|ui| {
+                if ui.button("Rename").clicked() {
+                    *ui_state.packet_snapshot_rename = Some((name.clone(), name.clone()));
+                    ui.close();
+                }
+                if ui.button("Export to file").clicked() {
+                    export = Some(name.clone());
+                    ui.close();
+                }
+                if ui.button("Delete").clicked() {
+                    remove = Some(name.clone());
+                    ui.close();
+                }
+            });
+            ui.label(
+                RichText::new(format!("{count} records, {}", format_created(*created_at)))
+                    .small()
+                    .color(Color32::GRAY),
+            );
+            ui.add_space(2.0);
+        }
+    });
+
+    if let Some(name) = remove {
+        snapshots.lock().remove(&name);
+    }
+    if let Some(name) = export {
+        let result = snapshots.lock().export_to_file(Some(std::slice::from_ref(&name)), &name);
+        report_export(ui_state, result);
+    }
+
+    ui.separator();
+    ui.add(
+        egui::TextEdit::singleline(ui_state.packet_snapshot_file)
+            .hint_text("file in packet_snapshots/")
+            .desired_width(f32::INFINITY),
+    );
+    ui.horizontal_wrapped(|ui| {
+        let file = ui_state.packet_snapshot_file.trim().to_string();
+        if ui.button("Export all").clicked() {
+            let file = if file.is_empty() { "snapshots".to_string() } else { file.clone() };
+            let result = snapshots.lock().export_to_file(None, &file);
+            report_export(ui_state, result);
+        }
+        if ui.add_enabled(!file.is_empty(), egui::Button::new("Import")).clicked() {
+            let result = snapshots.lock().import_from_file(&file);
+            match result {
+                Ok(n) => {
+                    ui_state.notification_manager.show_success("Snapshots", &format!("{n} snapshots imported"));
+                }
+                Err(e) => {
+                    ui_state.notification_manager.show_error("Snapshots", &e);
+                }
+            }
+        }
+    });
+}
+
+fn report_export(ui_state: &mut UiState, result: Result<std::path::PathBuf, String>) {
+    match result {
+        Ok(path) => {
+            ui_state
+                .notification_manager
+                .show_success("Snapshots", &format!("Saved to {}", path.display()));
+        }
+        Err(e) => {
+            ui_state.notification_manager.show_error("Snapshots", &e);
+        }
+    }
+}
+
+fn format_created(millis: u64) -> String {
+    use chrono::{DateTime, Local};
+    DateTime::from_timestamp_millis(millis as i64)
+        .map(|dt| dt.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_default()
+}
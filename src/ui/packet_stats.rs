@@ -0,0 +1,238 @@
+use crate::core::state::GlobalState;
+use crate::graphics::netlog::PacketDirection;
+use crate::graphics::netstats::{
+    size_bucket_label, RateBucket, StatsKind, TypeStats, SIZE_BUCKETS,
+};
+use crate::ui::UiState;
+use egui::{pos2, vec2, Color32, RichText, Sense, Ui};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const RATE_WINDOW_SECS: usize = 60;
+
+const IN_COLOR: Color32 = Color32::from_rgb(90, 160, 230);
+const OUT_COLOR: Color32 = Color32::from_rgb(230, 140, 90);
+
+struct StatsView {
+    now_sec: u64,
+    rates: Vec<(PacketDirection, u64, u64, RateBucket, Vec<RateBucket>)>,
+    top_classes: Vec<(String, u64, u64)>,
+    top_channels: Vec<(String, u64, u64)>,
+    selected: Option<TypeStats>,
+}
+
+pub fn render_packet_stats_section(ui: &mut Ui, ui_state: &mut UiState) {
+    egui::CollapsingHeader::new("Statistics")
+        .id_salt("packet_stats")
+        .default_open(false)
+        .show(ui, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Top N:");
+                ui.add(egui::DragValue::new(ui_state.packet_stats_top_n).range(1..=100));
+                ui.radio_value(ui_state.packet_stats_by_bytes, false, "By count");
+                ui.radio_value(ui_state.packet_stats_by_bytes, true, "By volume");
+            });
+
+            let Some(view) = collect_view(ui_state) else {
+                ui.colored_label(Color32::GRAY, "Packet store is not initialized");
+                return;
+            };
+
+            ui.add_space(4.0);
+            render_rates(ui, &view);
+            ui.add_space(6.0);
+
+            ui.columns(2, |cols| {
+                cols[0].label(RichText::new("Packet classes").strong());
+                render_top_table(&mut cols[0], ui_state, StatsKind::Class, &view.top_classes);
+                cols[1].label(RichText::new("Custom payload channels").strong());
+                render_top_table(&mut cols[1], ui_state, StatsKind::Channel, &view.top_channels);
+            });
+
+            if let (Some((kind, key)), Some(ts)) =
+                (ui_state.packet_stats_selected.clone(), view.selected.as_ref())
+            {
+                ui.add_space(6.0);
+                ui.separator();
+                render_type_details(ui, ui_state, kind, &key, ts);
+            }
+        });
+}
+
+fn collect_view(ui_state: &UiState) -> Option<StatsView> {
+    let store = GlobalState::instance().get_packet_store().get()?;
+    let guard = store.lock();
+    let stats = guard.traffic_stats();
+
+    let now_sec = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let n = *ui_state.packet_stats_top_n as usize;
+    let by_bytes = *ui_state.packet_stats_by_bytes;
+
+    let rates = [PacketDirection::Inbound, PacketDirection::Outbound]
+        .into_iter()
+        .map(|dir| {
+            let d = stats.direction(dir);
+            (dir, d.packets, d.bytes, d.last_second(now_sec), d.series(now_sec, RATE_WINDOW_SECS))
+        })
+        .collect();
+
+    let selected = ui_state
+        .packet_stats_selected
+        .as_ref()
+        .and_then(|(kind, key)| stats.types(*kind).get(key).cloned());
+
+    Some(StatsView {
+        now_sec,
+        rates,
+        top_classes: stats.top(StatsKind::Class, n, by_bytes),
+        top_channels: stats.top(StatsKind::Channel, n, by_bytes),
+        selected,
+    })
+}
+
+fn render_rates(ui: &mut Ui, view: &StatsView) {
+    for (dir, packets, bytes, last, series) in &view.rates {
+        let (label, color) = match dir {
+            PacketDirection::Inbound => ("Inbound", IN_COLOR),
+            PacketDirection::Outbound => ("Outbound", OUT_COLOR),
+        };
+        ui.horizontal(|ui| {
+            ui.label(RichText::new(label).color(color).strong());
+            ui.monospace(format!(
+                "{} pkt/s  {}/s   total {} pkts, {}",
+                last.packets,
+                format_bytes(last.bytes),
+                packets,
+                format_bytes(*bytes)
+            ));
+        });
+        let start = view.now_sec.saturating_sub(series.len() as u64);
+        ui.columns(2, |cols| {
+            let pk: Vec<f64> = series.iter().map(|b| b.packets as f64).collect();
+            bar_chart(&mut cols[0], &pk, 40.0, color, |i| {
+                format!("-{}s: {} pkt/s", view.now_sec - (start + i as u64), series[i].packets)
+            });
+            let by: Vec<f64> = series.iter().map(|b| b.bytes as f64).collect();
+            bar_chart(&mut cols[1], &by, 40.0, color.linear_multiply(0.7), |i| {
+                format!("-{}s: {}/s", view.now_sec - (start + i as u64), format_bytes(series[i].bytes))
+            });
+        });
+    }
+}
+
+fn render_top_table(ui: &mut Ui, ui_state: &mut UiState, kind: StatsKind, rows: &[(String, u64, u64)]) {
+    if rows.is_empty() {
+        ui.colored_label(Color32::GRAY, "No data");
+        return;
+    }
+    let max = rows
+        .iter()
+        .map(|r| if *ui_state.packet_stats_by_bytes { r.2 } else { r.1 })
+        .max()
+        .unwrap_or(1)
+        .max(1);
+
+    egui::Grid::new(("packet_stats_top", kind as u8))
+        .num_columns(3)
+        .striped(true)
+        .show(ui, |ui| {
+            for (key, count, bytes) in rows {
+                let short = key.rsplit('.').next().unwrap_or(key);
+                let selected = matches!(&*ui_state.packet_stats_selected, Some((k, s)) if *k == kind && s == key);
+                if ui.selectable_label(selected, short).on_hover_text(key).clicked() {
+                    *ui_state.packet_stats_selected = if selected { None } else { Some((kind, key.clone())) };
+                }
+                ui.monospace(format!("{count} / {}", format_bytes(*bytes)));
+                let value = if *ui_state.packet_stats_by_bytes { *bytes } else { *count };
+                ui.add(
+                    egui::ProgressBar::new(value as f32 / max as f32)
+                        .desired_width(80.0)
+                        .desired_height(10.0),
+                );
+                ui.end_row();
+            }
+        });
+}
+
+fn render_type_details(ui: &mut Ui, ui_state: &mut UiState, kind: StatsKind, key: &str, ts: &TypeStats) {
+    ui.horizontal(|ui| {
+        let what = match kind {
+            StatsKind::Class => "Class",
+            StatsKind::Channel => "Channel",
+        };
+        ui.label(RichText::new(format!("{what}: {key}")).strong().color(Color32::LIGHT_BLUE));
+        if ui.small_button("x").clicked() {
+            *ui_state.packet_stats_selected = None;
+        }
+    });
+    let interval = ts
+        .avg_interval()
+        .map(|v| format!("{v:.1} ms"))
+        .unwrap_or_else(|| "-".to_string());
+    ui.monospace(format!(
+        "count {}  bytes {}  len min {} / avg {:.1} / max {}  avg interval {interval}",
+        ts.count,
+        format_bytes(ts.bytes),
+        if ts.count == 0 { 0 } else { ts.min_len },
+        ts.avg_len(),
+        ts.max_len
+    ));
+
+    ui.add_space(4.0);
+    ui.label("Size histogram");
+    // пустые корзины по краям не показываем
+    let first = ts.size_hist.iter().position(|c| *c > 0).unwrap_or(0);
+    let last = ts.size_hist.iter().rposition(|c| *c > 0).unwrap_or(SIZE_BUCKETS - 1);
+    let hist: Vec<f64> = ts.size_hist[first..=last].iter().map(|c| *c as f64).collect();
+    bar_chart(ui, &hist, 60.0, Color32::from_rgb(120, 200, 120), |i| {
+        format!("{} bytes: {}", size_bucket_label(first + i), ts.size_hist[first + i])
+    });
+
+    ui.add_space(4.0);
+    ui.label(format!("Inter-arrival, last {} intervals (ms)", ts.inter_arrival.len()));
+    let deltas: Vec<f64> = ts.inter_arrival.iter().map(|d| *d as f64).collect();
+    bar_chart(ui, &deltas, 60.0, Color32::from_rgb(200, 180, 90), |i| {
+        format!("#{i}: {} ms", ts.inter_arrival[i])
+    });
+}
+
+/// Простой столбчатый график на всю ширину, подпись столбца под курсором — через `label`.
+fn bar_chart(ui: &mut Ui, values: &[f64], height: f32, color: Color32, label: impl Fn(usize) -> String) {
+    let (rect, resp) = ui.allocate_exact_size(vec2(ui.available_width(), height), Sense::hover());
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 2.0, Color32::from_gray(20));
+    if values.is_empty() {
+        return;
+    }
+    let max = values.iter().cloned().fold(0.0f64, f64::max).max(1.0);
+    let w = rect.width() / values.len() as f32;
+    let hovered = resp
+        .hover_pos()
+        .map(|p| (((p.x - rect.left()) / w) as usize).min(values.len() - 1));
+
+    for (i, v) in values.iter().enumerate() {
+        let h = (*v / max) as f32 * (rect.height() - 2.0);
+        let x0 = rect.left() + i as f32 * w;
+        let bar = egui::Rect::from_min_max(
+            pos2(x0 + w * 0.1, rect.bottom() - h),
+            pos2(x0 + w * 0.9, rect.bottom()),
+        );
+        let c = if hovered == Some(i) { Color32::WHITE } else { color };
+        painter.rect_filled(bar, 0.0, c);
+    }
+    if let Some(i) = hovered {
+        resp.on_hover_text(label(i));
+    }
+}
+
+fn format_bytes(b: u64) -> String {
+    if b >= 1 << 20 {
+        format!("{:.1} MiB", b as f64 / (1u64 << 20) as f64)
+    } else if b >= 1 << 10 {
+        format!("{:.1} KiB", b as f64 / 1024.0)
+    } else {
+        format!("{b} B")
+    }
+}
@@ -0,0 +1,289 @@
+use crate::graphics::netlog::{PacketDirection, PacketRecord};
+use crate::ui::UiState;
+use egui::{pos2, vec2, Align2, Color32, Rect, Sense, Stroke, TextStyle, Ui};
+use std::collections::HashMap;
+
+const LANE_H: f32 = 18.0;
+const AXIS_H: f32 = 16.0;
+const LABEL_W: f32 = 110.0;
+/// Остальные группы складываются в одну дорожку "other"
+const MAX_GROUP_LANES: usize = 12;
+const MIN_SPAN_MS: f64 = 20.0;
+
+const IN_COLOR: Color32 = Color32::from_rgb(90, 160, 230);
+const OUT_COLOR: Color32 = Color32::from_rgb(230, 140, 90);
+const BRUSH_FILL: Color32 = Color32::from_rgba_premultiplied(60, 90, 140, 60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimelineLanes {
+    #[default]
+    Direction,
+    Group,
+}
+
+/// Рисует таймлайн по `records` (уже отфильтрованным, кроме диапазона времени).
+/// Выделение мышью пишет диапазон в `packet_time_range`, клик по точке выбирает запись.
+pub fn render_packet_timeline(ui: &mut Ui, ui_state: &mut UiState, records: &[PacketRecord]) {
+    let title = match *ui_state.packet_time_range {
+        Some((from, to)) => format!("Timeline  [{} - {}]", format_ts(from), format_ts(to)),
+        None => "Timeline".to_string(),
+    };
+    egui::CollapsingHeader::new(title)
+        .id_salt("packet_timeline")
+        .default_open(false)
+        .show(ui, |ui| {
+            ui.horizontal_wrapped(|ui| {
+                ui.label("Lanes:");
+                ui.radio_value(ui_state.packet_timeline_lanes, TimelineLanes::Direction, "Direction");
+                ui.radio_value(ui_state.packet_timeline_lanes, TimelineLanes::Group, "Channel / group");
+                ui.separator();
+                if ui.button("Fit").clicked() {
+                    *ui_state.packet_timeline_view = None;
+                }
+                if ui.button("Zoom in").clicked() {
+                    zoom_view(ui_state, records, 0.5, 0.5);
+                }
+                if ui.button("Zoom out").clicked() {
+                    zoom_view(ui_state, records, 2.0, 0.5);
+                }
+                if ui_state.packet_time_range.is_some() {
+                    if ui.button("Zoom to range").clicked() {
+                        *ui_state.packet_timeline_view = *ui_state.packet_time_range;
+                    }
+                    if ui.button("Clear range").clicked() {
+                        *ui_state.packet_time_range = None;
+                    }
+                }
+            });
+            ui.colored_label(
+                Color32::GRAY,
+                "Drag to select a range, right-drag to pan, Ctrl+wheel to zoom, double-click to fit",
+            );
+            if records.is_empty() {
+                ui.colored_label(Color32::GRAY, "No packets");
+                return;
+            }
+            render_plot(ui, ui_state, records);
+        });
+}
+
+fn data_span(records: &[PacketRecord]) -> (u64, u64) {
+    let min = records.iter().map(|r| r.ts_millis).min().unwrap_or(0);
+    let max = records.iter().map(|r| r.ts_millis).max().unwrap_or(0);
+    (min, max.max(min + 1))
+}
+
+fn current_view(ui_state: &UiState, records: &[PacketRecord]) -> (f64, f64) {
+    match *ui_state.packet_timeline_view {
+        Some((a, b)) => (a as f64, b as f64),
+        None => {
+            let (a, b) = data_span(records);
+            let pad = ((b - a) as f64 * 0.02).max(10.0);
+            (a as f64 - pad, b as f64 + pad)
+        }
+    }
+}
+
+/// `factor` < 1 — приближение, `anchor` — доля ширины, вокруг которой масштабируем.
+fn zoom_view(ui_state: &mut UiState, records: &[PacketRecord], factor: f64, anchor: f64) {
+    let (a, b) = current_view(ui_state, records);
+    let pivot = a + (b - a) * anchor;
+    let span = ((b - a) * factor).max(MIN_SPAN_MS);
+    let from = pivot - span * anchor;
+    *ui_state.packet_timeline_view = Some((from.max(0.0) as u64, (from + span).max(1.0) as u64));
+}
+
+fn lane_keys(lanes: TimelineLanes, records: &[PacketRecord]) -> Vec<String> {
+    match lanes {
+        TimelineLanes::Direction => vec!["Inbound".to_string(), "Outbound".to_string()],
+        TimelineLanes::Group => {
+            let mut counts: HashMap<&str, usize> = HashMap::new();
+            for r in records {
+                *counts.entry(lane_group(r)).or_default() += 1;
+            }
+            let mut v: Vec<(&str, usize)> = counts.into_iter().collect();
+            v.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+            let overflow = v.len() > MAX_GROUP_LANES;
+            let mut keys: Vec<String> = v
+                .into_iter()
+                .take(if overflow { MAX_GROUP_LANES - 1 } else { MAX_GROUP_LANES })
+                .map(|(k, _)| k.to_string())
+                .collect();
+            if overflow {
+                keys.push("other".to_string());
+            }
+            keys
+        }
+    }
+}
+
+fn lane_group(r: &PacketRecord) -> &str {
+    r.group.as_deref().unwrap_or(&r.name)
+}
+
+fn lane_of(lanes: TimelineLanes, keys: &[String], r: &PacketRecord) -> usize {
+    match lanes {
+        TimelineLanes::Direction => match r.dir {
+            PacketDirection::Inbound => 0,
+            PacketDirection::Outbound => 1,
+        },
+        TimelineLanes::Group => {
+            let g = lane_group(r);
+            keys.iter().position(|k| k == g).unwrap_or(keys.len() - 1)
+        }
+    }
+}
+
+fn render_plot(ui: &mut Ui, ui_state: &mut UiState, records: &[PacketRecord]) {
+    let lanes = *ui_state.packet_timeline_lanes;
+    let keys = lane_keys(lanes, records);
+    let height = keys.len() as f32 * LANE_H + AXIS_H;
+    let (rect, resp) = ui.allocate_exact_size(vec2(ui.available_width(), height), Sense::click_and_drag());
+    let plot = Rect::from_min_max(pos2(rect.left() + LABEL_W, rect.top()), pos2(rect.right(), rect.bottom() - AXIS_H));
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(plot, 2.0, Color32::from_gray(18));
+
+    let (v0, v1) = current_view(ui_state, records);
+    let span = (v1 - v0).max(1.0);
+    let to_x = |ts: f64| plot.left() + ((ts - v0) / span) as f32 * plot.width();
+    let to_ts = |x: f32| v0 + ((x - plot.left()) / plot.width()) as f64 * span;
+
+    let font = TextStyle::Small.resolve(ui.style());
+    for (i, key) in keys.iter().enumerate() {
+        let y = plot.top() + i as f32 * LANE_H;
+        if i > 0 {
+            painter.line_segment([pos2(plot.left(), y), pos2(plot.right(), y)], Stroke::new(1.0, Color32::from_gray(40)));
+        }
+        let short = key.rsplit('.').next().unwrap_or(key);
+        painter.text(pos2(rect.left() + 4.0, y + LANE_H * 0.5), Align2::LEFT_CENTER, short, font.clone(), Color32::LIGHT_GRAY);
+    }
+
+    // ось времени
+    let step = tick_step(span, plot.width());
+    let mut t = (v0 / step).ceil() * step;
+    while t <= v1 {
+        let x = to_x(t);
+        painter.line_segment([pos2(x, plot.bottom()), pos2(x, plot.bottom() + 4.0)], Stroke::new(1.0, Color32::GRAY));
+        painter.text(pos2(x, plot.bottom() + 4.0), Align2::CENTER_TOP, format_tick(t as u64, step), font.clone(), Color32::GRAY);
+        t += step;
+    }
+
+    if let Some((from, to)) = *ui_state.packet_time_range {
+        let r = Rect::from_x_y_ranges(to_x(from as f64)..=to_x(to as f64), plot.y_range());
+        painter.rect_filled(r.intersect(plot), 0.0, BRUSH_FILL);
+    }
+
+    // ближайшая к курсору запись, для подсказки и выбора кликом
+    let hover = resp.hover_pos().filter(|p| plot.contains(*p));
+    let mut nearest: Option<(f32, &PacketRecord)> = None;
+    for r in records {
+        let x = to_x(r.ts_millis as f64);
+        if x < plot.left() || x > plot.right() {
+            continue;
+        }
+        let lane = lane_of(lanes, &keys, r);
+        let y0 = plot.top() + lane as f32 * LANE_H;
+        let color = match r.dir {
+            PacketDirection::Inbound => IN_COLOR,
+            PacketDirection::Outbound => OUT_COLOR,
+        };
+        let selected = *ui_state.selected_packet_id == Some(r.id);
+        let (c, w) = if selected { (Color32::WHITE, 3.0) } else { (color, 1.5) };
+        painter.line_segment([pos2(x, y0 + 3.0), pos2(x, y0 + LANE_H - 3.0)], Stroke::new(w, c));
+
+        if let Some(p) = hover {
+            if p.y >= y0 && p.y < y0 + LANE_H {
+                let d = (p.x - x).abs();
+                if d <= 4.0 && nearest.is_none_or(|(nd, _)| d < nd) {
+                    nearest = Some((d, r));
+                }
+            }
+        }
+    }
+
+    let brush_id = resp.id.with("brush");
+    if resp.drag_started_by(egui::PointerButton::Primary) {
+        if let Some(p) = resp.interact_pointer_pos() {
+            ui.data_mut(|d| d.insert_temp(brush_id, p.x.clamp(plot.left(), plot.right())));
+        }
+    }
+    let brush_start: Option<f32> = ui.data(|d| d.get_temp(brush_id));
+    if let (Some(x0), Some(p)) = (brush_start, resp.interact_pointer_pos()) {
+        let x1 = p.x.clamp(plot.left(), plot.right());
+        if resp.dragged_by(egui::PointerButton::Primary) {
+            let r = Rect::from_x_y_ranges(x0.min(x1)..=x0.max(x1), plot.y_range());
+            painter.rect_stroke(r, 0.0, Stroke::new(1.0, Color32::LIGHT_BLUE), egui::StrokeKind::Inside);
+        }
+        if resp.drag_stopped() {
+            ui.data_mut(|d| d.remove::<f32>(brush_id));
+            if (x1 - x0).abs() > 3.0 {
+                let a = to_ts(x0.min(x1)).max(0.0) as u64;
+                let b = to_ts(x0.max(x1)).max(0.0) as u64;
+                *ui_state.packet_time_range = Some((a, b));
+            }
+        }
+    }
+
+    if resp.dragged_by(egui::PointerButton::Secondary) {
+        let dx = resp.drag_delta().x;
+        let shift = -(dx / plot.width()) as f64 * span;
+        let from = (v0 + shift).max(0.0);
+        *ui_state.packet_timeline_view = Some((from as u64, (from + span) as u64));
+    }
+
+    if resp.hovered() {
+        let zoom = ui.input(|i| i.zoom_delta());
+        if zoom != 1.0 {
+            if let Some(p) = hover {
+                let anchor = ((p.x - plot.left()) / plot.width()) as f64;
+                zoom_view(ui_state, records, 1.0 / zoom as f64, anchor.clamp(0.0, 1.0));
+            }
+        }
+    }
+
+    if resp.double_clicked() {
+        *ui_state.packet_timeline_view = None;
+    } else if resp.clicked() {
+        if let Some((_, r)) = nearest {
+            *ui_state.selected_packet_id = Some(r.id);
+            *ui_state.packet_search_scroll_to = Some(r.id);
+            *ui_state.packet_autoscroll = false;
+        }
+    }
+
+    if let Some((_, r)) = nearest {
+        let short = r.name.rsplit('.').next().unwrap_or(&r.name);
+        resp.on_hover_text(format!("#{} {} {}  {} bytes", r.id, format_ts(r.ts_millis), short, r.len));
+    } else if let Some(p) = hover {
+        resp.on_hover_text(format_ts(to_ts(p.x).max(0.0) as u64));
+    }
+}
+
+/// Шаг делений: 1-2-5 так, чтобы между подписями было не меньше ~90px
+fn tick_step(span_ms: f64, width: f32) -> f64 {
+    let target = span_ms / (width as f64 / 90.0).max(1.0);
+    let mut base = 1.0;
+    loop {
+        for m in [1.0, 2.0, 5.0] {
+            if base * m >= target {
+                return base * m;
+            }
+        }
+        base *= 10.0;
+    }
+}
+
+fn format_tick(ts: u64, step: f64) -> String {
+    if step < 1000.0 { format_ts(ts) } else { local_time(ts).format("%H:%M:%S").to_string() }
+}
+
+fn format_ts(ts: u64) -> String {
+    local_time(ts).format("%H:%M:%S%.3f").to_string()
+}
+
+fn local_time(ts: u64) -> chrono::DateTime<chrono::Local> {
+    use chrono::{DateTime, Local, Utc};
+    DateTime::<Utc>::from_timestamp_millis(ts as i64)
+        .unwrap_or_default()
+        .with_timezone(&Local)
+}
@@ -1,4 +1,6 @@
 use crate::{
+    account::SwitchTrigger,
+    account_history::switch_session,
     graphics::icon_renderer::{render_clickable_icon_with_text, render_decorative_icon},
     graphics::svg_icons::SvgIconManager,
     jvm::{get_jvm, SessionInfo},
@@ -226,7 +228,7 @@ fn render_session_buttons(ui_state: &mut UiState, icon_manager: &mut SvgIconMana
                 session_type: ui_state.new_session_type.clone(),
             };
 
-            match session_manager.change_session(new_session) {
+            match switch_session(new_session, SwitchTrigger::Manual) {
                 Ok(_) => {
                     ui_state.notification_manager.show_success("Session Changed", "Session changed successfully");
                 }
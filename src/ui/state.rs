@@ -1,4 +1,15 @@
-use crate::graphics::context::{AppTab, PacketFilterProfile, PacketTrigger};
+use crate::graphics::context::{AppTab, PacketFilterProfile};
+use crate::core::layout_infer::InferredPacket;
+use crate::core::packet_correlation::CorrelationRule;
+use crate::core::packet_search::{SearchHit, SearchMode};
+use crate::graphics::netstats::StatsKind;
+use crate::ui::packet_timeline::TimelineLanes;
+use crate::ui::packet_columns::PacketColumn;
+use crate::account::AccountSort;
+use crate::auth::AuthProviderKind;
+use crate::account_transfer::{ImportCandidate, TransferFormat};
+use crate::core::packet_builder::FieldInput;
+use crate::ui::packet_hex_editor::HexEditor;
 use crate::ui::notification_manager::NotificationManager;
 
 #[allow(clippy::too_many_arguments)]
@@ -27,6 +38,28 @@ pub struct UiState<'a> {
     pub edit_session_type: &'a mut String,
     pub edit_password: &'a mut String,
     pub edit_original_name: &'a mut String,
+    pub edit_group: &'a mut String,
+    pub edit_tags: &'a mut String,
+    pub edit_notes: &'a mut String,
+    pub edit_servers: &'a mut String,
+    pub edit_provider: &'a mut AuthProviderKind,
+    pub account_group_by_folder: &'a mut bool,
+    pub account_filter_tag: &'a mut String,
+    pub account_favorites_only: &'a mut bool,
+    pub account_search: &'a mut String,
+    pub account_sort: &'a mut AccountSort,
+    pub account_sort_desc: &'a mut bool,
+    pub account_filter_password: &'a mut Option<bool>,
+    pub account_filter_session: &'a mut String,
+    pub vault_password_input: &'a mut String,
+    pub vault_new_password: &'a mut String,
+    pub vault_confirm_password: &'a mut String,
+    pub vault_busy: &'a mut bool,
+    pub account_transfer_format: &'a mut TransferFormat,
+    pub account_transfer_path: &'a mut String,
+    pub account_transfer_password: &'a mut String,
+    pub account_export_confirm: &'a mut Option<String>,
+    pub account_import_plan: &'a mut Vec<ImportCandidate>,
     pub auth_in_progress: &'a mut bool,
     pub packet_filter: &'a mut String,
     pub packet_show_inbound: &'a mut bool,
@@ -45,12 +78,48 @@ pub struct UiState<'a> {
     pub packet_show_only_new: &'a mut bool,
     pub packet_last_seen_id: &'a mut u64,
     pub packet_secondary_selected_id: &'a mut Option<u64>,
-    pub packet_triggers: &'a mut Vec<PacketTrigger>,
     pub packet_trigger_input: &'a mut String,
+    pub packet_trigger_pin: &'a mut bool,
+    pub packet_trigger_notify: &'a mut bool,
+    pub packet_trigger_pause: &'a mut bool,
+    pub packet_trigger_export: &'a mut String,
     pub packet_tag_editor: &'a mut String,
     pub packet_color_hex: &'a mut String,
     pub packet_export_limit: &'a mut u32,
     pub packet_import_buffer: &'a mut String,
+    pub packet_search_input: &'a mut String,
+    pub packet_search_mode: &'a mut SearchMode,
+    pub packet_search_hit: &'a mut Option<SearchHit>,
+    pub packet_search_scroll_to: &'a mut Option<u64>,
+    pub packet_stats_top_n: &'a mut u32,
+    pub packet_stats_by_bytes: &'a mut bool,
+    pub packet_stats_selected: &'a mut Option<(StatsKind, String)>,
+    pub packet_timeline_lanes: &'a mut TimelineLanes,
+    pub packet_timeline_view: &'a mut Option<(u64, u64)>,
+    pub packet_time_range: &'a mut Option<(u64, u64)>,
+    pub packet_infer_channel: &'a mut String,
+    pub packet_infer_result: &'a mut Vec<InferredPacket>,
+    pub packet_infer_draft: &'a mut String,
+    pub packet_correlation_rules: &'a mut Vec<CorrelationRule>,
+    pub packet_correlation_channel_input: &'a mut String,
+    pub packet_correlation_key_input: &'a mut String,
+    pub packet_session_filter: &'a mut Option<u32>,
+    pub packet_note_type_input: &'a mut String,
+    pub packet_note_record_input: &'a mut String,
+    pub packet_note_loaded_for: &'a mut Option<u64>,
+    pub packet_columns: &'a mut Vec<PacketColumn>,
+    pub packet_split_rows: &'a mut bool,
+    pub packet_sort: &'a mut Option<(PacketColumn, bool)>,
+    pub packet_column_field_input: &'a mut String,
+    pub packet_hex_editor: &'a mut Option<HexEditor>,
+    pub packet_builder_channel: &'a mut String,
+    pub packet_builder_packet: &'a mut String,
+    pub packet_builder_input: &'a mut FieldInput,
+    pub packet_snapshots_open: &'a mut bool,
+    pub packet_snapshot_view: &'a mut Option<String>,
+    pub packet_snapshot_name: &'a mut String,
+    pub packet_snapshot_rename: &'a mut Option<(String, String)>,
+    pub packet_snapshot_file: &'a mut String,
 
     pub search_query: &'a mut String,
     pub selected_class: &'a mut Option<String>,
@@ -68,4 +137,8 @@ pub struct UiState<'a> {
     pub auth_tab_result_profile: &'a mut String,
     pub auth_tab_in_progress: &'a mut bool,
     pub auth_tab_error: &'a mut Option<String>,
+    pub auth_tab_provider: &'a mut AuthProviderKind,
+    pub auth_tab_result_username: &'a mut String,
+    pub auth_tab_client_token: &'a mut String,
+    pub auth_tab_account_name: &'a mut String,
 }
@@ -0,0 +1,156 @@
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as B64;
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+use rand::RngCore;
+use rand::rngs::OsRng;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use zeroize::Zeroizing;
+
+/// Метка зашифрованного файла, по ней load отличает его от старого открытого формата
+const VAULT_MARKER: &str = "mc-session-changer";
+const VAULT_VERSION: u32 = 1;
+
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+
+/// Параметры Argon2id: 64 МиБ, 3 прохода — около секунды на слабой машине
+const DEFAULT_M_COST: u32 = 64 * 1024;
+const DEFAULT_T_COST: u32 = 3;
+const DEFAULT_P_COST: u32 = 1;
+
+/// Параметры берутся из файла: без верхней границы подменённый заголовок
+/// заставит выделить гигабайты и считать минутами
+const MAX_M_COST: u32 = 256 * 1024;
+const MAX_T_COST: u32 = 16;
+const MAX_P_COST: u32 = 8;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KdfParams {
+    pub algorithm: String,
+    pub m_cost: u32,
+    pub t_cost: u32,
+    pub p_cost: u32,
+    pub salt: String,
+}
+
+/// Зашифрованный sessions.json. Заголовок открытый, AEAD привязан к параметрам KDF.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VaultFile {
+    pub vault: String,
+    pub version: u32,
+    pub kdf: KdfParams,
+    /// Автоблокировка нужна до разблокировки, поэтому лежит в заголовке
+    #[serde(default)]
+    pub auto_lock_minutes: u32,
+    pub nonce: String,
+    pub ciphertext: String,
+}
+
+impl VaultFile {
+    pub fn is_vault(value: &Value) -> bool {
+        value.get("vault").and_then(Value::as_str) == Some(VAULT_MARKER)
+    }
+
+    fn aad(&self) -> String {
+        format!(
+            "{}:{}:{}:{}:{}:{}:{}",
+            self.vault, self.version, self.kdf.algorithm, self.kdf.m_cost, self.kdf.t_cost, self.kdf.p_cost, self.kdf.salt
+        )
+    }
+}
+
+/// Ключ, выведенный из мастер-пароля. Держится в памяти, пока хранилище разблокировано.
+#[derive(Clone)]
+pub struct VaultKey {
+    key: Zeroizing<[u8; 32]>,
+    kdf: KdfParams,
+}
+
+impl std::fmt::Debug for VaultKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("VaultKey").field("kdf", &self.kdf).finish_non_exhaustive()
+    }
+}
+
+impl VaultKey {
+    /// Новый ключ со свежей солью — для первой установки и смены пароля
+    pub fn create(password: &str) -> Result<Self, String> {
+        if password.is_empty() {
+            return Err("Master password is empty".to_string());
+        }
+        let mut salt = [0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        let kdf = KdfParams {
+            algorithm: "argon2id".to_string(),
+            m_cost: DEFAULT_M_COST,
+            t_cost: DEFAULT_T_COST,
+            p_cost: DEFAULT_P_COST,
+            salt: B64.encode(salt),
+        };
+        Self::derive(password, kdf)
+    }
+
+    pub fn derive(password: &str, kdf: KdfParams) -> Result<Self, String> {
+        if kdf.algorithm != "argon2id" {
+            return Err(format!("Unsupported KDF: {}", kdf.algorithm));
+        }
+        if kdf.m_cost > MAX_M_COST || kdf.t_cost > MAX_T_COST || kdf.p_cost > MAX_P_COST {
+            return Err(format!(
+                "KDF parameters are out of range (m={}, t={}, p={})",
+                kdf.m_cost, kdf.t_cost, kdf.p_cost
+            ));
+        }
+        let salt = B64.decode(&kdf.salt).map_err(|e| format!("Invalid salt: {}", e))?;
+        let params = Params::new(kdf.m_cost, kdf.t_cost, kdf.p_cost, Some(32))
+            .map_err(|e| format!("Invalid KDF parameters: {}", e))?;
+        let mut key = Zeroizing::new([0u8; 32]);
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(password.as_bytes(), &salt, key.as_mut())
+            .map_err(|e| format!("Key derivation failed: {}", e))?;
+        Ok(Self { key, kdf })
+    }
+
+    pub fn kdf(&self) -> &KdfParams {
+        &self.kdf
+    }
+
+    /// Шифрует с новым случайным nonce на каждое сохранение
+    pub fn seal(&self, plaintext: &[u8], auto_lock_minutes: u32) -> Result<VaultFile, String> {
+        let mut nonce = [0u8; NONCE_LEN];
+        OsRng.fill_bytes(&mut nonce);
+        let mut file = VaultFile {
+            vault: VAULT_MARKER.to_string(),
+            version: VAULT_VERSION,
+            kdf: self.kdf.clone(),
+            auto_lock_minutes,
+            nonce: B64.encode(nonce),
+            ciphertext: String::new(),
+        };
+        let aad = file.aad();
+        let ciphertext = XChaCha20Poly1305::new(Key::from_slice(self.key.as_ref()))
+            .encrypt(XNonce::from_slice(&nonce), Payload { msg: plaintext, aad: aad.as_bytes() })
+            .map_err(|_| "Encryption failed".to_string())?;
+        file.ciphertext = B64.encode(ciphertext);
+        Ok(file)
+    }
+
+    /// Неверный пароль и испорченный файл здесь неотличимы
+    pub fn open(&self, file: &VaultFile) -> Result<Zeroizing<Vec<u8>>, String> {
+        if file.version > VAULT_VERSION {
+            return Err(format!("Vault version {} is newer than supported ({})", file.version, VAULT_VERSION));
+        }
+        let nonce = B64.decode(&file.nonce).map_err(|e| format!("Invalid nonce: {}", e))?;
+        if nonce.len() != NONCE_LEN {
+            return Err("Invalid nonce length".to_string());
+        }
+        let ciphertext = B64.decode(&file.ciphertext).map_err(|e| format!("Invalid ciphertext: {}", e))?;
+        let aad = file.aad();
+        XChaCha20Poly1305::new(Key::from_slice(self.key.as_ref()))
+            .decrypt(XNonce::from_slice(&nonce), Payload { msg: &ciphertext, aad: aad.as_bytes() })
+            .map(Zeroizing::new)
+            .map_err(|_| "Wrong master password or corrupted file".to_string())
+    }
+}